@@ -0,0 +1,9 @@
+CREATE TABLE break_history
+(
+    id                 INTEGER PRIMARY KEY AUTOINCREMENT,
+    start_time         TEXT NOT NULL,
+    stop_time          TEXT,
+    duration           INTEGER,
+    interrupted_region TEXT,
+    CONSTRAINT valid_interrupted_region CHECK (interrupted_region IN ('aa1', 'aa2', 'aa3', 'ac1', 'ac2', 'ac3'))
+);
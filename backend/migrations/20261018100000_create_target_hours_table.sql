@@ -0,0 +1,14 @@
+CREATE TABLE target_hours
+(
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    valid_from  TEXT    NOT NULL,
+    valid_until TEXT,
+    monday      INTEGER NOT NULL DEFAULT 0,
+    tuesday     INTEGER NOT NULL DEFAULT 0,
+    wednesday   INTEGER NOT NULL DEFAULT 0,
+    thursday    INTEGER NOT NULL DEFAULT 0,
+    friday      INTEGER NOT NULL DEFAULT 0,
+    saturday    INTEGER NOT NULL DEFAULT 0,
+    sunday      INTEGER NOT NULL DEFAULT 0,
+    CONSTRAINT valid_period CHECK (valid_until IS NULL OR valid_until >= valid_from)
+);
@@ -0,0 +1,6 @@
+CREATE TABLE custom_holidays
+(
+    id   INTEGER PRIMARY KEY AUTOINCREMENT,
+    date TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL
+);
@@ -0,0 +1,14 @@
+CREATE TABLE absences
+(
+    id       INTEGER PRIMARY KEY AUTOINCREMENT,
+    date     TEXT    NOT NULL UNIQUE,
+    kind     TEXT    NOT NULL,
+    half_day INTEGER NOT NULL DEFAULT 0,
+    CONSTRAINT valid_kind CHECK (kind IN ('vacation', 'sick', 'training', 'comp_time'))
+);
+
+CREATE TABLE vacation_entitlements
+(
+    year INTEGER PRIMARY KEY,
+    days REAL NOT NULL
+);
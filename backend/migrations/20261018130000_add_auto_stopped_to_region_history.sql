@@ -0,0 +1 @@
+ALTER TABLE region_history ADD COLUMN auto_stopped INTEGER NOT NULL DEFAULT 0;
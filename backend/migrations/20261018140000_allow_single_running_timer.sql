@@ -0,0 +1,19 @@
+-- Close timers and breaks that were left running next to a newer one. They end
+-- when the newest one started, like a regular start would have stopped them.
+UPDATE region_history
+SET stop_time = (SELECT MAX(start_time) FROM region_history WHERE stop_time IS NULL),
+    duration  = (strftime('%s', (SELECT MAX(start_time) FROM region_history WHERE stop_time IS NULL))
+        - strftime('%s', start_time))
+WHERE stop_time IS NULL
+  AND id <> (SELECT id FROM region_history WHERE stop_time IS NULL ORDER BY start_time DESC, id DESC LIMIT 1);
+
+UPDATE break_history
+SET stop_time = (SELECT MAX(start_time) FROM break_history WHERE stop_time IS NULL),
+    duration  = (strftime('%s', (SELECT MAX(start_time) FROM break_history WHERE stop_time IS NULL))
+        - strftime('%s', start_time))
+WHERE stop_time IS NULL
+  AND id <> (SELECT id FROM break_history WHERE stop_time IS NULL ORDER BY start_time DESC, id DESC LIMIT 1);
+
+-- All running rows share the same indexed value, so at most one can exist
+CREATE UNIQUE INDEX region_history_single_running ON region_history ((stop_time IS NULL)) WHERE stop_time IS NULL;
+CREATE UNIQUE INDEX break_history_single_running ON break_history ((stop_time IS NULL)) WHERE stop_time IS NULL;
@@ -0,0 +1,12 @@
+CREATE TABLE idempotency_keys
+(
+    key          TEXT PRIMARY KEY,
+    method       TEXT    NOT NULL,
+    path         TEXT    NOT NULL,
+    status       INTEGER NOT NULL,
+    content_type TEXT,
+    body         BLOB    NOT NULL,
+    created_at   TEXT    NOT NULL
+);
+
+CREATE INDEX idempotency_keys_created_at ON idempotency_keys (created_at);
@@ -0,0 +1,9 @@
+-- Remembers the events that clients synchronized, so a batch that is sent
+-- again is not applied twice
+CREATE TABLE sync_events
+(
+    client_id TEXT NOT NULL,
+    event_id  TEXT NOT NULL,
+    synced_at TEXT NOT NULL,
+    PRIMARY KEY (client_id, event_id)
+);
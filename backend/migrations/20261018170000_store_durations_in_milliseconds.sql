@@ -0,0 +1,9 @@
+-- Durations were whole seconds calculated by SQLite. Recalculate them in
+-- milliseconds from the stored timestamps.
+UPDATE region_history
+SET duration = CAST(ROUND((julianday(stop_time) - julianday(start_time)) * 86400000) AS INTEGER)
+WHERE stop_time IS NOT NULL;
+
+UPDATE break_history
+SET duration = CAST(ROUND((julianday(stop_time) - julianday(start_time)) * 86400000) AS INTEGER)
+WHERE stop_time IS NOT NULL;
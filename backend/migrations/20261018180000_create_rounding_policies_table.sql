@@ -0,0 +1,8 @@
+CREATE TABLE rounding_policies
+(
+    region    TEXT PRIMARY KEY,
+    mode      TEXT    NOT NULL,
+    increment INTEGER NOT NULL,
+    CONSTRAINT valid_mode CHECK (mode IN ('up', 'down', 'nearest')),
+    CONSTRAINT positive_increment CHECK (increment > 0)
+);
@@ -0,0 +1,129 @@
+-- Every timer action records the rows it changed, so the caller can undo it.
+-- The triggers only record while an action is marked as recording, which is
+-- the case within the transaction of the action.
+CREATE TABLE actions
+(
+    id           INTEGER PRIMARY KEY AUTOINCREMENT,
+    client_id    TEXT    NOT NULL,
+    kind         TEXT    NOT NULL,
+    performed_at TEXT    NOT NULL,
+    recording    INTEGER NOT NULL DEFAULT 0,
+    undone       INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX actions_client_id ON actions (client_id, performed_at);
+
+-- The row before and after the change as JSON, NULL if it did not exist
+CREATE TABLE action_changes
+(
+    id         INTEGER PRIMARY KEY AUTOINCREMENT,
+    action_id  INTEGER NOT NULL REFERENCES actions (id) ON DELETE CASCADE,
+    table_name TEXT    NOT NULL,
+    row_id     INTEGER NOT NULL,
+    before     TEXT,
+    after      TEXT
+);
+
+CREATE INDEX action_changes_action_id ON action_changes (action_id);
+
+CREATE TRIGGER region_history_journal_insert
+    AFTER INSERT
+    ON region_history
+    WHEN EXISTS (SELECT 1 FROM actions WHERE recording)
+BEGIN
+    INSERT INTO action_changes (action_id, table_name, row_id, before, after)
+    SELECT id,
+           'region_history',
+           NEW.id,
+           NULL,
+           json_object('id', NEW.id, 'region', NEW.region, 'start_time', NEW.start_time,
+                       'stop_time', NEW.stop_time, 'duration', NEW.duration,
+                       'auto_stopped', NEW.auto_stopped)
+    FROM actions
+    WHERE recording;
+END;
+
+CREATE TRIGGER region_history_journal_update
+    AFTER UPDATE
+    ON region_history
+    WHEN EXISTS (SELECT 1 FROM actions WHERE recording)
+BEGIN
+    INSERT INTO action_changes (action_id, table_name, row_id, before, after)
+    SELECT id,
+           'region_history',
+           NEW.id,
+           json_object('id', OLD.id, 'region', OLD.region, 'start_time', OLD.start_time,
+                       'stop_time', OLD.stop_time, 'duration', OLD.duration,
+                       'auto_stopped', OLD.auto_stopped),
+           json_object('id', NEW.id, 'region', NEW.region, 'start_time', NEW.start_time,
+                       'stop_time', NEW.stop_time, 'duration', NEW.duration,
+                       'auto_stopped', NEW.auto_stopped)
+    FROM actions
+    WHERE recording;
+END;
+
+CREATE TRIGGER region_history_journal_delete
+    AFTER DELETE
+    ON region_history
+    WHEN EXISTS (SELECT 1 FROM actions WHERE recording)
+BEGIN
+    INSERT INTO action_changes (action_id, table_name, row_id, before, after)
+    SELECT id,
+           'region_history',
+           OLD.id,
+           json_object('id', OLD.id, 'region', OLD.region, 'start_time', OLD.start_time,
+                       'stop_time', OLD.stop_time, 'duration', OLD.duration,
+                       'auto_stopped', OLD.auto_stopped),
+           NULL
+    FROM actions
+    WHERE recording;
+END;
+
+CREATE TRIGGER break_history_journal_insert
+    AFTER INSERT
+    ON break_history
+    WHEN EXISTS (SELECT 1 FROM actions WHERE recording)
+BEGIN
+    INSERT INTO action_changes (action_id, table_name, row_id, before, after)
+    SELECT id,
+           'break_history',
+           NEW.id,
+           NULL,
+           json_object('id', NEW.id, 'start_time', NEW.start_time, 'stop_time', NEW.stop_time,
+                       'duration', NEW.duration, 'interrupted_region', NEW.interrupted_region)
+    FROM actions
+    WHERE recording;
+END;
+
+CREATE TRIGGER break_history_journal_update
+    AFTER UPDATE
+    ON break_history
+    WHEN EXISTS (SELECT 1 FROM actions WHERE recording)
+BEGIN
+    INSERT INTO action_changes (action_id, table_name, row_id, before, after)
+    SELECT id,
+           'break_history',
+           NEW.id,
+           json_object('id', OLD.id, 'start_time', OLD.start_time, 'stop_time', OLD.stop_time,
+                       'duration', OLD.duration, 'interrupted_region', OLD.interrupted_region),
+           json_object('id', NEW.id, 'start_time', NEW.start_time, 'stop_time', NEW.stop_time,
+                       'duration', NEW.duration, 'interrupted_region', NEW.interrupted_region)
+    FROM actions
+    WHERE recording;
+END;
+
+CREATE TRIGGER break_history_journal_delete
+    AFTER DELETE
+    ON break_history
+    WHEN EXISTS (SELECT 1 FROM actions WHERE recording)
+BEGIN
+    INSERT INTO action_changes (action_id, table_name, row_id, before, after)
+    SELECT id,
+           'break_history',
+           OLD.id,
+           json_object('id', OLD.id, 'start_time', OLD.start_time, 'stop_time', OLD.stop_time,
+                       'duration', OLD.duration, 'interrupted_region', OLD.interrupted_region),
+           NULL
+    FROM actions
+    WHERE recording;
+END;
@@ -0,0 +1,83 @@
+ALTER TABLE actions ADD COLUMN user_id TEXT;
+
+-- Every change to region_history, written by triggers within the transaction
+-- of the change. The actor and device are taken from the action that is being
+-- recorded, changes outside of an action have neither.
+CREATE TABLE audit_log
+(
+    id         INTEGER PRIMARY KEY AUTOINCREMENT,
+    entry_id   INTEGER NOT NULL,
+    operation  TEXT    NOT NULL,
+    actor      TEXT,
+    device     TEXT,
+    old_values TEXT,
+    new_values TEXT,
+    changed_at TEXT    NOT NULL,
+    CONSTRAINT valid_operation CHECK (operation IN ('insert', 'update', 'delete'))
+);
+
+CREATE INDEX audit_log_entry_id ON audit_log (entry_id);
+
+CREATE TRIGGER audit_log_no_update
+    BEFORE UPDATE
+    ON audit_log
+BEGIN
+    SELECT RAISE(ABORT, 'The audit log is append-only');
+END;
+
+CREATE TRIGGER audit_log_no_delete
+    BEFORE DELETE
+    ON audit_log
+BEGIN
+    SELECT RAISE(ABORT, 'The audit log is append-only');
+END;
+
+CREATE TRIGGER region_history_audit_insert
+    AFTER INSERT
+    ON region_history
+BEGIN
+    INSERT INTO audit_log (entry_id, operation, actor, device, old_values, new_values, changed_at)
+    VALUES (NEW.id,
+            'insert',
+            (SELECT user_id FROM actions WHERE recording),
+            (SELECT client_id FROM actions WHERE recording),
+            NULL,
+            json_object('region', NEW.region, 'start_time', NEW.start_time,
+                        'stop_time', NEW.stop_time, 'duration', NEW.duration,
+                        'auto_stopped', NEW.auto_stopped),
+            strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+END;
+
+CREATE TRIGGER region_history_audit_update
+    AFTER UPDATE
+    ON region_history
+BEGIN
+    INSERT INTO audit_log (entry_id, operation, actor, device, old_values, new_values, changed_at)
+    VALUES (NEW.id,
+            'update',
+            (SELECT user_id FROM actions WHERE recording),
+            (SELECT client_id FROM actions WHERE recording),
+            json_object('region', OLD.region, 'start_time', OLD.start_time,
+                        'stop_time', OLD.stop_time, 'duration', OLD.duration,
+                        'auto_stopped', OLD.auto_stopped),
+            json_object('region', NEW.region, 'start_time', NEW.start_time,
+                        'stop_time', NEW.stop_time, 'duration', NEW.duration,
+                        'auto_stopped', NEW.auto_stopped),
+            strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+END;
+
+CREATE TRIGGER region_history_audit_delete
+    AFTER DELETE
+    ON region_history
+BEGIN
+    INSERT INTO audit_log (entry_id, operation, actor, device, old_values, new_values, changed_at)
+    VALUES (OLD.id,
+            'delete',
+            (SELECT user_id FROM actions WHERE recording),
+            (SELECT client_id FROM actions WHERE recording),
+            json_object('region', OLD.region, 'start_time', OLD.start_time,
+                        'stop_time', OLD.stop_time, 'duration', OLD.duration,
+                        'auto_stopped', OLD.auto_stopped),
+            NULL,
+            strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+END;
@@ -0,0 +1,116 @@
+-- Deleted entries are kept in the trash until they are purged. The journal and
+-- audit triggers record the new column, so deleting and restoring can be
+-- undone and show up in the audit log.
+ALTER TABLE region_history ADD COLUMN deleted_at TEXT;
+
+CREATE INDEX region_history_deleted_at ON region_history (deleted_at) WHERE deleted_at IS NOT NULL;
+
+DROP TRIGGER region_history_journal_insert;
+DROP TRIGGER region_history_journal_update;
+DROP TRIGGER region_history_journal_delete;
+DROP TRIGGER region_history_audit_insert;
+DROP TRIGGER region_history_audit_update;
+DROP TRIGGER region_history_audit_delete;
+
+CREATE TRIGGER region_history_journal_insert
+    AFTER INSERT
+    ON region_history
+    WHEN EXISTS (SELECT 1 FROM actions WHERE recording)
+BEGIN
+    INSERT INTO action_changes (action_id, table_name, row_id, before, after)
+    SELECT id,
+           'region_history',
+           NEW.id,
+           NULL,
+           json_object('id', NEW.id, 'region', NEW.region, 'start_time', NEW.start_time,
+                       'stop_time', NEW.stop_time, 'duration', NEW.duration,
+                       'auto_stopped', NEW.auto_stopped, 'deleted_at', NEW.deleted_at)
+    FROM actions
+    WHERE recording;
+END;
+
+CREATE TRIGGER region_history_journal_update
+    AFTER UPDATE
+    ON region_history
+    WHEN EXISTS (SELECT 1 FROM actions WHERE recording)
+BEGIN
+    INSERT INTO action_changes (action_id, table_name, row_id, before, after)
+    SELECT id,
+           'region_history',
+           NEW.id,
+           json_object('id', OLD.id, 'region', OLD.region, 'start_time', OLD.start_time,
+                       'stop_time', OLD.stop_time, 'duration', OLD.duration,
+                       'auto_stopped', OLD.auto_stopped, 'deleted_at', OLD.deleted_at),
+           json_object('id', NEW.id, 'region', NEW.region, 'start_time', NEW.start_time,
+                       'stop_time', NEW.stop_time, 'duration', NEW.duration,
+                       'auto_stopped', NEW.auto_stopped, 'deleted_at', NEW.deleted_at)
+    FROM actions
+    WHERE recording;
+END;
+
+CREATE TRIGGER region_history_journal_delete
+    AFTER DELETE
+    ON region_history
+    WHEN EXISTS (SELECT 1 FROM actions WHERE recording)
+BEGIN
+    INSERT INTO action_changes (action_id, table_name, row_id, before, after)
+    SELECT id,
+           'region_history',
+           OLD.id,
+           json_object('id', OLD.id, 'region', OLD.region, 'start_time', OLD.start_time,
+                       'stop_time', OLD.stop_time, 'duration', OLD.duration,
+                       'auto_stopped', OLD.auto_stopped, 'deleted_at', OLD.deleted_at),
+           NULL
+    FROM actions
+    WHERE recording;
+END;
+
+CREATE TRIGGER region_history_audit_insert
+    AFTER INSERT
+    ON region_history
+BEGIN
+    INSERT INTO audit_log (entry_id, operation, actor, device, old_values, new_values, changed_at)
+    VALUES (NEW.id,
+            'insert',
+            (SELECT user_id FROM actions WHERE recording),
+            (SELECT client_id FROM actions WHERE recording),
+            NULL,
+            json_object('region', NEW.region, 'start_time', NEW.start_time,
+                        'stop_time', NEW.stop_time, 'duration', NEW.duration,
+                        'auto_stopped', NEW.auto_stopped, 'deleted_at', NEW.deleted_at),
+            strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+END;
+
+CREATE TRIGGER region_history_audit_update
+    AFTER UPDATE
+    ON region_history
+BEGIN
+    INSERT INTO audit_log (entry_id, operation, actor, device, old_values, new_values, changed_at)
+    VALUES (NEW.id,
+            'update',
+            (SELECT user_id FROM actions WHERE recording),
+            (SELECT client_id FROM actions WHERE recording),
+            json_object('region', OLD.region, 'start_time', OLD.start_time,
+                        'stop_time', OLD.stop_time, 'duration', OLD.duration,
+                        'auto_stopped', OLD.auto_stopped, 'deleted_at', OLD.deleted_at),
+            json_object('region', NEW.region, 'start_time', NEW.start_time,
+                        'stop_time', NEW.stop_time, 'duration', NEW.duration,
+                        'auto_stopped', NEW.auto_stopped, 'deleted_at', NEW.deleted_at),
+            strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+END;
+
+CREATE TRIGGER region_history_audit_delete
+    AFTER DELETE
+    ON region_history
+BEGIN
+    INSERT INTO audit_log (entry_id, operation, actor, device, old_values, new_values, changed_at)
+    VALUES (OLD.id,
+            'delete',
+            (SELECT user_id FROM actions WHERE recording),
+            (SELECT client_id FROM actions WHERE recording),
+            json_object('region', OLD.region, 'start_time', OLD.start_time,
+                        'stop_time', OLD.stop_time, 'duration', OLD.duration,
+                        'auto_stopped', OLD.auto_stopped, 'deleted_at', OLD.deleted_at),
+            NULL,
+            strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+END;
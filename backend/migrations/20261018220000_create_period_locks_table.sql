@@ -0,0 +1,79 @@
+-- A closed month, with its bounds in UTC as they were in the configured time
+-- zone when it was closed.
+CREATE TABLE period_locks
+(
+    year      INTEGER NOT NULL,
+    month     INTEGER NOT NULL,
+    starts_at TEXT    NOT NULL,
+    ends_at   TEXT    NOT NULL,
+    closed_at TEXT    NOT NULL,
+    closed_by TEXT,
+    PRIMARY KEY (year, month)
+);
+
+-- Every closing and reopening of a month
+CREATE TABLE period_lock_log
+(
+    id           INTEGER PRIMARY KEY AUTOINCREMENT,
+    year         INTEGER NOT NULL,
+    month        INTEGER NOT NULL,
+    operation    TEXT    NOT NULL,
+    actor        TEXT,
+    device       TEXT    NOT NULL,
+    reason       TEXT,
+    performed_at TEXT    NOT NULL,
+    CONSTRAINT valid_operation CHECK (operation IN ('close', 'reopen'))
+);
+
+CREATE TRIGGER period_lock_log_no_update
+    BEFORE UPDATE
+    ON period_lock_log
+BEGIN
+    SELECT RAISE(ABORT, 'The period lock log is append-only');
+END;
+
+CREATE TRIGGER period_lock_log_no_delete
+    BEFORE DELETE
+    ON period_lock_log
+BEGIN
+    SELECT RAISE(ABORT, 'The period lock log is append-only');
+END;
+
+-- Entries that overlap a closed month can't be added, changed or deleted.
+-- Entries in the trash are exempt, so it can still be purged.
+CREATE TRIGGER region_history_locked_insert
+    BEFORE INSERT
+    ON region_history
+    WHEN NEW.deleted_at IS NULL
+        AND EXISTS (SELECT 1
+                    FROM period_locks
+                    WHERE NEW.start_time < ends_at
+                      AND (NEW.stop_time IS NULL OR NEW.stop_time > starts_at))
+BEGIN
+    SELECT RAISE(ABORT, 'The period is locked');
+END;
+
+CREATE TRIGGER region_history_locked_update
+    BEFORE UPDATE
+    ON region_history
+    WHEN EXISTS (SELECT 1
+                 FROM period_locks
+                 WHERE (OLD.deleted_at IS NULL AND OLD.start_time < ends_at
+                     AND (OLD.stop_time IS NULL OR OLD.stop_time > starts_at))
+                    OR (NEW.deleted_at IS NULL AND NEW.start_time < ends_at
+                     AND (NEW.stop_time IS NULL OR NEW.stop_time > starts_at)))
+BEGIN
+    SELECT RAISE(ABORT, 'The period is locked');
+END;
+
+CREATE TRIGGER region_history_locked_delete
+    BEFORE DELETE
+    ON region_history
+    WHEN OLD.deleted_at IS NULL
+        AND EXISTS (SELECT 1
+                    FROM period_locks
+                    WHERE OLD.start_time < ends_at
+                      AND (OLD.stop_time IS NULL OR OLD.stop_time > starts_at))
+BEGIN
+    SELECT RAISE(ABORT, 'The period is locked');
+END;
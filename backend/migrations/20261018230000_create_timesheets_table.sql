@@ -0,0 +1,32 @@
+-- The monthly timesheets that users submit for approval
+CREATE TABLE timesheets
+(
+    user_id      TEXT    NOT NULL,
+    year         INTEGER NOT NULL,
+    month        INTEGER NOT NULL,
+    status       TEXT    NOT NULL,
+    submitted_at TEXT    NOT NULL,
+    reviewed_by  TEXT,
+    reviewed_at  TEXT,
+    comment      TEXT,
+    PRIMARY KEY (user_id, year, month),
+    CONSTRAINT valid_status CHECK (status IN ('submitted', 'approved', 'rejected'))
+);
+
+CREATE INDEX timesheets_status ON timesheets (status);
+
+-- Every change of the status of a timesheet
+CREATE TABLE timesheet_events
+(
+    id         INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id    TEXT    NOT NULL,
+    year       INTEGER NOT NULL,
+    month      INTEGER NOT NULL,
+    status     TEXT    NOT NULL,
+    actor      TEXT    NOT NULL,
+    comment    TEXT,
+    changed_at TEXT    NOT NULL,
+    FOREIGN KEY (user_id, year, month) REFERENCES timesheets (user_id, year, month)
+);
+
+CREATE INDEX timesheet_events_timesheet ON timesheet_events (user_id, year, month);
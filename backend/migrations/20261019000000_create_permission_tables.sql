@@ -0,0 +1,27 @@
+-- Users without a role are members
+CREATE TABLE user_roles
+(
+    user_id TEXT PRIMARY KEY NOT NULL,
+    role    TEXT NOT NULL,
+    CONSTRAINT valid_role CHECK (role IN ('admin', 'manager', 'member'))
+);
+
+CREATE TABLE group_members
+(
+    group_name TEXT NOT NULL,
+    user_id    TEXT NOT NULL,
+    PRIMARY KEY (group_name, user_id)
+);
+
+CREATE INDEX group_members_user_id ON group_members (user_id);
+
+-- The users and groups that may book time on a region. Regions without any
+-- row are open to everyone.
+CREATE TABLE region_permissions
+(
+    region       TEXT NOT NULL,
+    subject_kind TEXT NOT NULL,
+    subject      TEXT NOT NULL,
+    PRIMARY KEY (region, subject_kind, subject),
+    CONSTRAINT valid_subject_kind CHECK (subject_kind IN ('user', 'group'))
+);
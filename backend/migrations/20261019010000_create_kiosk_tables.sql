@@ -0,0 +1,21 @@
+-- The PINs and badge numbers with which workers identify at a kiosk, hashed
+-- with the kiosk secret. Each credential identifies exactly one user.
+CREATE TABLE kiosk_credentials
+(
+    user_id TEXT NOT NULL,
+    kind    TEXT NOT NULL,
+    hash    TEXT NOT NULL UNIQUE,
+    PRIMARY KEY (user_id, kind),
+    CONSTRAINT valid_kind CHECK (kind IN ('pin', 'badge'))
+);
+
+-- Recent kiosk logins, to limit how fast PINs can be guessed
+CREATE TABLE kiosk_attempts
+(
+    id           INTEGER PRIMARY KEY AUTOINCREMENT,
+    client_id    TEXT    NOT NULL,
+    succeeded    INTEGER NOT NULL,
+    attempted_at TEXT    NOT NULL
+);
+
+CREATE INDEX kiosk_attempts_attempted_at ON kiosk_attempts (attempted_at);
@@ -0,0 +1,77 @@
+-- Devices register once and identify with their token afterwards. Only a
+-- hash of the token is stored.
+CREATE TABLE devices
+(
+    id            INTEGER PRIMARY KEY AUTOINCREMENT,
+    name          TEXT NOT NULL,
+    kind          TEXT NOT NULL,
+    token_hash    TEXT NOT NULL UNIQUE,
+    registered_at TEXT NOT NULL,
+    last_seen_at  TEXT,
+    revoked_at    TEXT,
+    CONSTRAINT valid_kind CHECK (kind IN ('tablet', 'phone', 'kiosk', 'desktop', 'other'))
+);
+
+-- The device that performed an action, and so made each change of the history
+ALTER TABLE actions ADD COLUMN device_id INTEGER REFERENCES devices (id);
+ALTER TABLE audit_log ADD COLUMN device_id INTEGER;
+
+DROP TRIGGER region_history_audit_insert;
+DROP TRIGGER region_history_audit_update;
+DROP TRIGGER region_history_audit_delete;
+
+CREATE TRIGGER region_history_audit_insert
+    AFTER INSERT
+    ON region_history
+BEGIN
+    INSERT INTO audit_log (entry_id, operation, actor, device, device_id, old_values, new_values,
+                           changed_at)
+    VALUES (NEW.id,
+            'insert',
+            (SELECT user_id FROM actions WHERE recording),
+            (SELECT client_id FROM actions WHERE recording),
+            (SELECT device_id FROM actions WHERE recording),
+            NULL,
+            json_object('region', NEW.region, 'start_time', NEW.start_time,
+                        'stop_time', NEW.stop_time, 'duration', NEW.duration,
+                        'auto_stopped', NEW.auto_stopped, 'deleted_at', NEW.deleted_at),
+            strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+END;
+
+CREATE TRIGGER region_history_audit_update
+    AFTER UPDATE
+    ON region_history
+BEGIN
+    INSERT INTO audit_log (entry_id, operation, actor, device, device_id, old_values, new_values,
+                           changed_at)
+    VALUES (NEW.id,
+            'update',
+            (SELECT user_id FROM actions WHERE recording),
+            (SELECT client_id FROM actions WHERE recording),
+            (SELECT device_id FROM actions WHERE recording),
+            json_object('region', OLD.region, 'start_time', OLD.start_time,
+                        'stop_time', OLD.stop_time, 'duration', OLD.duration,
+                        'auto_stopped', OLD.auto_stopped, 'deleted_at', OLD.deleted_at),
+            json_object('region', NEW.region, 'start_time', NEW.start_time,
+                        'stop_time', NEW.stop_time, 'duration', NEW.duration,
+                        'auto_stopped', NEW.auto_stopped, 'deleted_at', NEW.deleted_at),
+            strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+END;
+
+CREATE TRIGGER region_history_audit_delete
+    AFTER DELETE
+    ON region_history
+BEGIN
+    INSERT INTO audit_log (entry_id, operation, actor, device, device_id, old_values, new_values,
+                           changed_at)
+    VALUES (OLD.id,
+            'delete',
+            (SELECT user_id FROM actions WHERE recording),
+            (SELECT client_id FROM actions WHERE recording),
+            (SELECT device_id FROM actions WHERE recording),
+            json_object('region', OLD.region, 'start_time', OLD.start_time,
+                        'stop_time', OLD.stop_time, 'duration', OLD.duration,
+                        'auto_stopped', OLD.auto_stopped, 'deleted_at', OLD.deleted_at),
+            NULL,
+            strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+END;
@@ -0,0 +1,22 @@
+-- Keys are scoped to the client that sent them and reserved before the request
+-- runs, so concurrent retries can't run it twice. The stored responses are only
+-- kept for retries, so they are dropped.
+DROP TABLE idempotency_keys;
+
+CREATE TABLE idempotency_keys
+(
+    client_id    TEXT NOT NULL,
+    key          TEXT NOT NULL,
+    method       TEXT NOT NULL,
+    path         TEXT NOT NULL,
+    -- The SHA-256 hash of the request body
+    request_hash TEXT NOT NULL,
+    -- NULL while the request is running
+    status       INTEGER,
+    content_type TEXT,
+    body         BLOB NOT NULL,
+    created_at   TEXT NOT NULL,
+    PRIMARY KEY (client_id, key)
+);
+
+CREATE INDEX idempotency_keys_created_at ON idempotency_keys (created_at);
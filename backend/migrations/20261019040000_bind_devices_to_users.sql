@@ -0,0 +1,3 @@
+-- A personal device identifies the user it was registered for, so requests
+-- with its token act as that user.
+ALTER TABLE devices ADD COLUMN user_id TEXT;
@@ -0,0 +1,198 @@
+-- Every entry and break belongs to the user who recorded it, so workers that
+-- share a terminal keep their own timers. Entries without a user form the
+-- shared history of clients that don't identify a user.
+ALTER TABLE region_history ADD COLUMN user_id TEXT;
+ALTER TABLE break_history ADD COLUMN user_id TEXT;
+
+CREATE INDEX region_history_user_id ON region_history (user_id, start_time);
+
+-- At most one timer and one break run per user
+DROP INDEX region_history_single_running;
+DROP INDEX break_history_single_running;
+CREATE UNIQUE INDEX region_history_single_running ON region_history (COALESCE(user_id, '')) WHERE stop_time IS NULL;
+CREATE UNIQUE INDEX break_history_single_running ON break_history (COALESCE(user_id, '')) WHERE stop_time IS NULL;
+
+-- Kiosk logins are limited per kiosk device
+ALTER TABLE kiosk_attempts ADD COLUMN device_id INTEGER;
+
+-- The journal and audit triggers record the new column, so undoing restores
+-- the owner of an entry and the audit log shows it
+DROP TRIGGER region_history_journal_insert;
+DROP TRIGGER region_history_journal_update;
+DROP TRIGGER region_history_journal_delete;
+DROP TRIGGER break_history_journal_insert;
+DROP TRIGGER break_history_journal_update;
+DROP TRIGGER break_history_journal_delete;
+DROP TRIGGER region_history_audit_insert;
+DROP TRIGGER region_history_audit_update;
+DROP TRIGGER region_history_audit_delete;
+
+CREATE TRIGGER region_history_journal_insert
+    AFTER INSERT
+    ON region_history
+    WHEN EXISTS (SELECT 1 FROM actions WHERE recording)
+BEGIN
+    INSERT INTO action_changes (action_id, table_name, row_id, before, after)
+    SELECT id,
+           'region_history',
+           NEW.id,
+           NULL,
+           json_object('id', NEW.id, 'region', NEW.region, 'start_time', NEW.start_time,
+                       'stop_time', NEW.stop_time, 'duration', NEW.duration,
+                       'auto_stopped', NEW.auto_stopped, 'deleted_at', NEW.deleted_at,
+                       'user_id', NEW.user_id)
+    FROM actions
+    WHERE recording;
+END;
+
+CREATE TRIGGER region_history_journal_update
+    AFTER UPDATE
+    ON region_history
+    WHEN EXISTS (SELECT 1 FROM actions WHERE recording)
+BEGIN
+    INSERT INTO action_changes (action_id, table_name, row_id, before, after)
+    SELECT id,
+           'region_history',
+           NEW.id,
+           json_object('id', OLD.id, 'region', OLD.region, 'start_time', OLD.start_time,
+                       'stop_time', OLD.stop_time, 'duration', OLD.duration,
+                       'auto_stopped', OLD.auto_stopped, 'deleted_at', OLD.deleted_at,
+                       'user_id', OLD.user_id),
+           json_object('id', NEW.id, 'region', NEW.region, 'start_time', NEW.start_time,
+                       'stop_time', NEW.stop_time, 'duration', NEW.duration,
+                       'auto_stopped', NEW.auto_stopped, 'deleted_at', NEW.deleted_at,
+                       'user_id', NEW.user_id)
+    FROM actions
+    WHERE recording;
+END;
+
+CREATE TRIGGER region_history_journal_delete
+    AFTER DELETE
+    ON region_history
+    WHEN EXISTS (SELECT 1 FROM actions WHERE recording)
+BEGIN
+    INSERT INTO action_changes (action_id, table_name, row_id, before, after)
+    SELECT id,
+           'region_history',
+           OLD.id,
+           json_object('id', OLD.id, 'region', OLD.region, 'start_time', OLD.start_time,
+                       'stop_time', OLD.stop_time, 'duration', OLD.duration,
+                       'auto_stopped', OLD.auto_stopped, 'deleted_at', OLD.deleted_at,
+                       'user_id', OLD.user_id),
+           NULL
+    FROM actions
+    WHERE recording;
+END;
+
+CREATE TRIGGER break_history_journal_insert
+    AFTER INSERT
+    ON break_history
+    WHEN EXISTS (SELECT 1 FROM actions WHERE recording)
+BEGIN
+    INSERT INTO action_changes (action_id, table_name, row_id, before, after)
+    SELECT id,
+           'break_history',
+           NEW.id,
+           NULL,
+           json_object('id', NEW.id, 'start_time', NEW.start_time, 'stop_time', NEW.stop_time,
+                       'duration', NEW.duration, 'interrupted_region', NEW.interrupted_region,
+                       'user_id', NEW.user_id)
+    FROM actions
+    WHERE recording;
+END;
+
+CREATE TRIGGER break_history_journal_update
+    AFTER UPDATE
+    ON break_history
+    WHEN EXISTS (SELECT 1 FROM actions WHERE recording)
+BEGIN
+    INSERT INTO action_changes (action_id, table_name, row_id, before, after)
+    SELECT id,
+           'break_history',
+           NEW.id,
+           json_object('id', OLD.id, 'start_time', OLD.start_time, 'stop_time', OLD.stop_time,
+                       'duration', OLD.duration, 'interrupted_region', OLD.interrupted_region,
+                       'user_id', OLD.user_id),
+           json_object('id', NEW.id, 'start_time', NEW.start_time, 'stop_time', NEW.stop_time,
+                       'duration', NEW.duration, 'interrupted_region', NEW.interrupted_region,
+                       'user_id', NEW.user_id)
+    FROM actions
+    WHERE recording;
+END;
+
+CREATE TRIGGER break_history_journal_delete
+    AFTER DELETE
+    ON break_history
+    WHEN EXISTS (SELECT 1 FROM actions WHERE recording)
+BEGIN
+    INSERT INTO action_changes (action_id, table_name, row_id, before, after)
+    SELECT id,
+           'break_history',
+           OLD.id,
+           json_object('id', OLD.id, 'start_time', OLD.start_time, 'stop_time', OLD.stop_time,
+                       'duration', OLD.duration, 'interrupted_region', OLD.interrupted_region,
+                       'user_id', OLD.user_id),
+           NULL
+    FROM actions
+    WHERE recording;
+END;
+
+CREATE TRIGGER region_history_audit_insert
+    AFTER INSERT
+    ON region_history
+BEGIN
+    INSERT INTO audit_log (entry_id, operation, actor, device, device_id, old_values, new_values,
+                           changed_at)
+    VALUES (NEW.id,
+            'insert',
+            (SELECT user_id FROM actions WHERE recording),
+            (SELECT client_id FROM actions WHERE recording),
+            (SELECT device_id FROM actions WHERE recording),
+            NULL,
+            json_object('region', NEW.region, 'start_time', NEW.start_time,
+                        'stop_time', NEW.stop_time, 'duration', NEW.duration,
+                        'auto_stopped', NEW.auto_stopped, 'deleted_at', NEW.deleted_at,
+                        'user_id', NEW.user_id),
+            strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+END;
+
+CREATE TRIGGER region_history_audit_update
+    AFTER UPDATE
+    ON region_history
+BEGIN
+    INSERT INTO audit_log (entry_id, operation, actor, device, device_id, old_values, new_values,
+                           changed_at)
+    VALUES (NEW.id,
+            'update',
+            (SELECT user_id FROM actions WHERE recording),
+            (SELECT client_id FROM actions WHERE recording),
+            (SELECT device_id FROM actions WHERE recording),
+            json_object('region', OLD.region, 'start_time', OLD.start_time,
+                        'stop_time', OLD.stop_time, 'duration', OLD.duration,
+                        'auto_stopped', OLD.auto_stopped, 'deleted_at', OLD.deleted_at,
+                        'user_id', OLD.user_id),
+            json_object('region', NEW.region, 'start_time', NEW.start_time,
+                        'stop_time', NEW.stop_time, 'duration', NEW.duration,
+                        'auto_stopped', NEW.auto_stopped, 'deleted_at', NEW.deleted_at,
+                        'user_id', NEW.user_id),
+            strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+END;
+
+CREATE TRIGGER region_history_audit_delete
+    AFTER DELETE
+    ON region_history
+BEGIN
+    INSERT INTO audit_log (entry_id, operation, actor, device, device_id, old_values, new_values,
+                           changed_at)
+    VALUES (OLD.id,
+            'delete',
+            (SELECT user_id FROM actions WHERE recording),
+            (SELECT client_id FROM actions WHERE recording),
+            (SELECT device_id FROM actions WHERE recording),
+            json_object('region', OLD.region, 'start_time', OLD.start_time,
+                        'stop_time', OLD.stop_time, 'duration', OLD.duration,
+                        'auto_stopped', OLD.auto_stopped, 'deleted_at', OLD.deleted_at,
+                        'user_id', OLD.user_id),
+            NULL,
+            strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+END;
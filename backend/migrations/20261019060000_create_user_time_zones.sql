@@ -0,0 +1,7 @@
+-- The IANA time zone of a user, e.g. 'Europe/Berlin'. Reports of users without
+-- one use the configured time zone.
+CREATE TABLE user_time_zones
+(
+    user_id   TEXT PRIMARY KEY,
+    time_zone TEXT NOT NULL
+);
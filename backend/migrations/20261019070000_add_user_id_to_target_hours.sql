@@ -0,0 +1,5 @@
+-- Every user has their own contracted working hours. Schedules without a user
+-- belong to the shared history of clients that don't identify a user.
+ALTER TABLE target_hours ADD COLUMN user_id TEXT;
+
+CREATE INDEX target_hours_user_id ON target_hours (user_id, valid_from);
@@ -0,0 +1,34 @@
+-- Absences and vacation entitlements belong to a user, so several people can
+-- be absent on the same day. Those without a user belong to the shared history
+-- of clients that don't identify a user.
+CREATE TABLE absences_new
+(
+    id       INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id  TEXT,
+    date     TEXT    NOT NULL,
+    kind     TEXT    NOT NULL,
+    half_day INTEGER NOT NULL DEFAULT 0,
+    CONSTRAINT valid_kind CHECK (kind IN ('vacation', 'sick', 'training', 'comp_time'))
+);
+INSERT INTO absences_new (id, date, kind, half_day)
+SELECT id, date, kind, half_day
+FROM absences;
+DROP TABLE absences;
+ALTER TABLE absences_new RENAME TO absences;
+
+CREATE TABLE vacation_entitlements_new
+(
+    user_id TEXT,
+    year    INTEGER NOT NULL,
+    days    REAL    NOT NULL
+);
+INSERT INTO vacation_entitlements_new (year, days)
+SELECT year, days
+FROM vacation_entitlements;
+DROP TABLE vacation_entitlements;
+ALTER TABLE vacation_entitlements_new RENAME TO vacation_entitlements;
+
+-- NULL users are distinct in unique constraints, so the keys are indexes on
+-- the user or the empty string
+CREATE UNIQUE INDEX absences_user_date ON absences (COALESCE(user_id, ''), date);
+CREATE UNIQUE INDEX vacation_entitlements_user_year ON vacation_entitlements (COALESCE(user_id, ''), year);
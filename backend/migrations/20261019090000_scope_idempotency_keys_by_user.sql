@@ -0,0 +1,27 @@
+-- Keys are scoped to the client, user and device that sent them, so users
+-- sharing a client can't replay each other's responses. The columns are
+-- nullable, so the key is a unique index on the coalesced columns. The stored
+-- responses are only kept for retries, so they are dropped.
+DROP TABLE idempotency_keys;
+
+CREATE TABLE idempotency_keys
+(
+    client_id    TEXT NOT NULL,
+    user_id      TEXT,
+    device_id    INTEGER,
+    key          TEXT NOT NULL,
+    method       TEXT NOT NULL,
+    path         TEXT NOT NULL,
+    -- The SHA-256 hash of the request body
+    request_hash TEXT NOT NULL,
+    -- NULL while the request is running
+    status       INTEGER,
+    content_type TEXT,
+    body         BLOB NOT NULL,
+    created_at   TEXT NOT NULL
+);
+
+CREATE UNIQUE INDEX idempotency_keys_scope
+    ON idempotency_keys (client_id, COALESCE(user_id, ''), COALESCE(device_id, 0), key);
+
+CREATE INDEX idempotency_keys_created_at ON idempotency_keys (created_at);
@@ -0,0 +1,71 @@
+-- Absences and breaks in a closed month can't be added, changed or deleted
+-- either, as they count towards the hours of the month.
+CREATE TRIGGER absences_locked_insert
+    BEFORE INSERT
+    ON absences
+    WHEN EXISTS (SELECT 1
+                 FROM period_locks
+                 WHERE year = CAST(strftime('%Y', NEW.date) AS INTEGER)
+                   AND month = CAST(strftime('%m', NEW.date) AS INTEGER))
+BEGIN
+    SELECT RAISE(ABORT, 'The period is locked');
+END;
+
+CREATE TRIGGER absences_locked_update
+    BEFORE UPDATE
+    ON absences
+    WHEN EXISTS (SELECT 1
+                 FROM period_locks
+                 WHERE (year = CAST(strftime('%Y', OLD.date) AS INTEGER)
+                     AND month = CAST(strftime('%m', OLD.date) AS INTEGER))
+                    OR (year = CAST(strftime('%Y', NEW.date) AS INTEGER)
+                     AND month = CAST(strftime('%m', NEW.date) AS INTEGER)))
+BEGIN
+    SELECT RAISE(ABORT, 'The period is locked');
+END;
+
+CREATE TRIGGER absences_locked_delete
+    BEFORE DELETE
+    ON absences
+    WHEN EXISTS (SELECT 1
+                 FROM period_locks
+                 WHERE year = CAST(strftime('%Y', OLD.date) AS INTEGER)
+                   AND month = CAST(strftime('%m', OLD.date) AS INTEGER))
+BEGIN
+    SELECT RAISE(ABORT, 'The period is locked');
+END;
+
+CREATE TRIGGER break_history_locked_insert
+    BEFORE INSERT
+    ON break_history
+    WHEN EXISTS (SELECT 1
+                 FROM period_locks
+                 WHERE NEW.start_time < ends_at
+                   AND (NEW.stop_time IS NULL OR NEW.stop_time > starts_at))
+BEGIN
+    SELECT RAISE(ABORT, 'The period is locked');
+END;
+
+CREATE TRIGGER break_history_locked_update
+    BEFORE UPDATE
+    ON break_history
+    WHEN EXISTS (SELECT 1
+                 FROM period_locks
+                 WHERE (OLD.start_time < ends_at
+                     AND (OLD.stop_time IS NULL OR OLD.stop_time > starts_at))
+                    OR (NEW.start_time < ends_at
+                     AND (NEW.stop_time IS NULL OR NEW.stop_time > starts_at)))
+BEGIN
+    SELECT RAISE(ABORT, 'The period is locked');
+END;
+
+CREATE TRIGGER break_history_locked_delete
+    BEFORE DELETE
+    ON break_history
+    WHEN EXISTS (SELECT 1
+                 FROM period_locks
+                 WHERE OLD.start_time < ends_at
+                   AND (OLD.stop_time IS NULL OR OLD.stop_time > starts_at))
+BEGIN
+    SELECT RAISE(ABORT, 'The period is locked');
+END;
@@ -0,0 +1,128 @@
+use axum::extract::FromRequestParts;
+use axum::http::HeaderMap;
+use axum::http::request::Parts;
+use chrono::Utc;
+use sha2::Digest;
+use sha2::Sha256;
+
+use crate::ApiContext;
+use crate::error::AppError;
+
+/// The request header that identifies the device or app that sends a request.
+pub const CLIENT_ID: &str = "client-id";
+/// The request header that identifies the person using the client, if the
+/// header is trusted.
+pub const USER_ID: &str = "user-id";
+/// The request header with the token of a registered device.
+pub const DEVICE_TOKEN: &str = "device-token";
+/// The request header with the setup token, which allows registering personal
+/// devices without the admin role.
+pub const SETUP_TOKEN: &str = "setup-token";
+
+/// The client id of requests without a `Client-Id` header.
+const ANONYMOUS: &str = "anonymous";
+/// The client id of changes made by the server itself, like stopping
+/// forgotten timers.
+const SYSTEM: &str = "system";
+
+const MAX_ID_LENGTH: usize = 255;
+
+/// The client that performs an action, identified by the `Client-Id` header,
+/// and the person using it. Requests without a client id share one anonymous
+/// client. Registered devices additionally send their token in the
+/// `Device-Token` header.
+///
+/// The person is the user a personal device was registered for. Otherwise, it
+/// is taken from the `User-Id` header, but only if the header is trusted, as
+/// any client can send it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Caller {
+    pub client_id: String,
+    pub user_id: Option<String>,
+    pub device_id: Option<i64>,
+}
+
+impl Default for Caller {
+    fn default() -> Self {
+        Self {
+            client_id: ANONYMOUS.to_string(),
+            user_id: None,
+            device_id: None,
+        }
+    }
+}
+
+impl Caller {
+    /// The caller of changes that the server makes on its own.
+    pub fn system() -> Self {
+        Self {
+            client_id: SYSTEM.to_string(),
+            user_id: None,
+            device_id: None,
+        }
+    }
+
+    /// Returns the user id, for requests that only a known person may make.
+    pub fn require_user(&self) -> Result<&str, AppError> {
+        self.user_id
+            .as_deref()
+            .ok_or(AppError::InvalidInput("The request requires a known user"))
+    }
+}
+
+/// Returns the value of an optional id header.
+fn header_id(headers: &HeaderMap, name: &str) -> Result<Option<String>, AppError> {
+    let Some(value) = headers.get(name) else {
+        return Ok(None);
+    };
+    match value.to_str() {
+        Ok(value) if !value.is_empty() && value.len() <= MAX_ID_LENGTH => {
+            Ok(Some(value.to_string()))
+        }
+        _ => Err(AppError::InvalidInput(
+            "Client and user ids must have 1 to 255 ASCII characters",
+        )),
+    }
+}
+
+/// Returns the hash under which the token of a device is stored.
+pub(crate) fn device_token_hash(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+impl FromRequestParts<ApiContext> for Caller {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        context: &ApiContext,
+    ) -> Result<Self, Self::Rejection> {
+        let (device_id, device_user) = match parts.headers.get(DEVICE_TOKEN) {
+            Some(token) => {
+                let token = token
+                    .to_str()
+                    .map_err(|_| AppError::InvalidInput("The device token must be ASCII"))?;
+                let (device_id, user_id) = context
+                    .device_repository
+                    .touch_device(&device_token_hash(token), Utc::now())
+                    .await?;
+                (Some(device_id), user_id)
+            }
+            None => (None, None),
+        };
+        let user_id = match device_user {
+            Some(user_id) => Some(user_id),
+            None if context.settings.trust_user_id_header => header_id(&parts.headers, USER_ID)?,
+            None => None,
+        };
+
+        Ok(Caller {
+            client_id: header_id(&parts.headers, CLIENT_ID)?.unwrap_or(ANONYMOUS.to_string()),
+            user_id,
+            device_id,
+        })
+    }
+}
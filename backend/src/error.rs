@@ -7,23 +7,65 @@ use crate::repositories::region_repositories::RepositoryError;
 #[derive(thiserror::Error, Debug)]
 pub enum AppError {
     #[error("Error while accessing data")]
-    RepositoryError(#[from] RepositoryError),
+    RepositoryError(RepositoryError),
+    #[error("{0}")]
+    InvalidInput(&'static str),
+    #[error("The period is closed and has to be reopened first")]
+    PeriodLocked,
+    #[error("{0}")]
+    Forbidden(&'static str),
+    #[error("{0}")]
+    Conflict(&'static str),
+}
+
+impl From<RepositoryError> for AppError {
+    fn from(error: RepositoryError) -> Self {
+        match error {
+            RepositoryError::PeriodLocked => AppError::PeriodLocked,
+            RepositoryError::Forbidden => {
+                AppError::Forbidden("Booking time on the region is not permitted")
+            }
+            error => AppError::RepositoryError(error),
+        }
+    }
 }
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         match self {
             AppError::RepositoryError(repository_error) => match repository_error {
-                RepositoryError::TimerNotRunning => (
+                RepositoryError::TimerNotRunning
+                | RepositoryError::BreakAlreadyRunning
+                | RepositoryError::BreakNotRunning
+                | RepositoryError::InvalidPeriod
+                | RepositoryError::OverlappingPeriod => (
                     StatusCode::UNPROCESSABLE_ENTITY,
                     repository_error.to_string(),
                 ),
+                RepositoryError::AlreadyExists
+                | RepositoryError::ChangedSince
+                | RepositoryError::InvalidStatus => {
+                    (StatusCode::CONFLICT, repository_error.to_string())
+                }
+                RepositoryError::PeriodLocked => (StatusCode::LOCKED, repository_error.to_string()),
+                RepositoryError::Forbidden => (StatusCode::FORBIDDEN, repository_error.to_string()),
+                RepositoryError::InvalidCredentials | RepositoryError::UnknownDevice => {
+                    (StatusCode::UNAUTHORIZED, repository_error.to_string())
+                }
+                RepositoryError::TooManyAttempts => {
+                    (StatusCode::TOO_MANY_REQUESTS, repository_error.to_string())
+                }
+                RepositoryError::NotFound => (StatusCode::NOT_FOUND, repository_error.to_string()),
                 RepositoryError::DatabaseError(ref e) => {
                     eprintln!("{}", e);
 
                     (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
                 }
             },
+            AppError::InvalidInput(message) => (StatusCode::BAD_REQUEST, message.to_string()),
+            AppError::PeriodLocked => (StatusCode::LOCKED, self.to_string()),
+            AppError::Forbidden(message) => (StatusCode::FORBIDDEN, message.to_string()),
+            AppError::Conflict(message) => (StatusCode::CONFLICT, message.to_string()),
         }
         .into_response()
     }
@@ -0,0 +1,163 @@
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::models::permission::Role;
+use crate::models::region::Region;
+use crate::models::timesheet::TimesheetStatus;
+
+/// How many events a slow subscriber may fall behind before it misses events.
+const EVENT_CAPACITY: usize = 64;
+
+/// A change to the timers that is pushed to the connected clients of the user
+/// it concerns. Changes of the shared history have no user.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TimerEvent {
+    TimerStarted {
+        user_id: Option<String>,
+        region: Region,
+    },
+    TimerStopped {
+        user_id: Option<String>,
+        region: Region,
+        /// The duration of the stopped entry in milliseconds
+        duration: i64,
+        auto_stopped: bool,
+    },
+    BreakStarted {
+        user_id: Option<String>,
+    },
+    BreakStopped {
+        user_id: Option<String>,
+        /// The duration of the break in milliseconds
+        duration: i64,
+    },
+    /// Past entries changed, so clients should reload the history.
+    HistoryChanged {
+        user_id: Option<String>,
+    },
+    /// A timesheet was submitted, approved or rejected.
+    TimesheetChanged {
+        user_id: String,
+        year: i32,
+        month: u32,
+        status: TimesheetStatus,
+    },
+}
+
+impl TimerEvent {
+    /// The name of the event, as used for the `event` field of server-sent
+    /// events.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TimerEvent::TimerStarted { .. } => "timer_started",
+            TimerEvent::TimerStopped { .. } => "timer_stopped",
+            TimerEvent::BreakStarted { .. } => "break_started",
+            TimerEvent::BreakStopped { .. } => "break_stopped",
+            TimerEvent::HistoryChanged { .. } => "history_changed",
+            TimerEvent::TimesheetChanged { .. } => "timesheet_changed",
+        }
+    }
+
+    /// Whether a client of the user with the role may receive the event.
+    /// Timers, breaks and the history are only sent to their own user,
+    /// timesheets also to managers.
+    pub fn is_visible_to(&self, user_id: Option<&str>, role: Role) -> bool {
+        match self {
+            TimerEvent::TimerStarted { user_id: owner, .. }
+            | TimerEvent::TimerStopped { user_id: owner, .. }
+            | TimerEvent::BreakStarted { user_id: owner }
+            | TimerEvent::BreakStopped { user_id: owner, .. }
+            | TimerEvent::HistoryChanged { user_id: owner } => owner.as_deref() == user_id,
+            TimerEvent::TimesheetChanged { user_id: owner, .. } => {
+                Some(owner.as_str()) == user_id || role >= Role::Manager
+            }
+        }
+    }
+}
+
+/// Distributes [`TimerEvent`]s to all subscribers. Cloning it yields a handle
+/// to the same channel.
+#[derive(Debug, Clone)]
+pub struct Events {
+    sender: broadcast::Sender<TimerEvent>,
+}
+
+impl Default for Events {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_CAPACITY);
+        Self { sender }
+    }
+}
+
+impl Events {
+    /// Sends the event to all current subscribers. Without subscribers, the
+    /// event is dropped.
+    pub fn publish(&self, event: TimerEvent) {
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<TimerEvent> {
+        self.sender.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_publish_reaches_all_subscribers() {
+        // Given
+        let events = Events::default();
+        let mut first = events.subscribe();
+        let mut second = events.clone().subscribe();
+
+        // When
+        events.publish(TimerEvent::TimerStarted {
+            user_id: None,
+            region: Region::Ac1,
+        });
+
+        // Then
+        let expected = TimerEvent::TimerStarted {
+            user_id: None,
+            region: Region::Ac1,
+        };
+        assert_eq!(first.recv().await.unwrap(), expected);
+        assert_eq!(second.recv().await.unwrap(), expected);
+    }
+
+    #[test]
+    fn test_timer_events_are_visible_to_their_user_only() {
+        // Given
+        let event = TimerEvent::BreakStarted {
+            user_id: Some("anna".to_string()),
+        };
+
+        // Then
+        assert!(event.is_visible_to(Some("anna"), Role::Member));
+        assert!(!event.is_visible_to(Some("ben"), Role::Manager));
+        assert!(
+            !event.is_visible_to(None, Role::Member),
+            "Anonymous clients see no users"
+        );
+    }
+
+    #[test]
+    fn test_timesheet_events_are_visible_to_their_user_and_managers() {
+        // Given
+        let event = TimerEvent::TimesheetChanged {
+            user_id: "anna".to_string(),
+            year: 2025,
+            month: 10,
+            status: TimesheetStatus::Submitted,
+        };
+
+        // Then
+        assert!(event.is_visible_to(Some("anna"), Role::Member));
+        assert!(event.is_visible_to(Some("ben"), Role::Manager));
+        assert!(!event.is_visible_to(Some("carl"), Role::Member));
+        assert!(!event.is_visible_to(None, Role::Member));
+    }
+}
@@ -0,0 +1,304 @@
+use std::ops::RangeInclusive;
+
+use chrono::Datelike;
+use chrono::Days;
+use chrono::NaiveDate;
+use chrono::Weekday;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::models::custom_holiday::CustomHoliday;
+
+/// The German federal states (Bundesländer), identified by their ISO 3166-2
+/// subdivision codes.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum FederalState {
+    /// Baden-Württemberg
+    Bw,
+    /// Bayern
+    By,
+    /// Berlin
+    Be,
+    /// Brandenburg
+    Bb,
+    /// Bremen
+    Hb,
+    /// Hamburg
+    Hh,
+    /// Hessen
+    He,
+    /// Mecklenburg-Vorpommern
+    Mv,
+    /// Niedersachsen
+    Ni,
+    /// Nordrhein-Westfalen
+    Nw,
+    /// Rheinland-Pfalz
+    Rp,
+    /// Saarland
+    Sl,
+    /// Sachsen
+    Sn,
+    /// Sachsen-Anhalt
+    St,
+    /// Schleswig-Holstein
+    Sh,
+    /// Thüringen
+    Th,
+}
+
+#[derive(Debug, Serialize, PartialEq, Clone)]
+pub struct Holiday {
+    /// The id of a custom holiday. Public holidays are calculated and have no
+    /// id.
+    pub id: Option<i64>,
+    pub date: NaiveDate,
+    pub name: String,
+    /// Whether the holiday is a company holiday instead of a public holiday.
+    pub custom: bool,
+}
+
+impl Holiday {
+    fn public(date: NaiveDate, name: &str) -> Self {
+        Self {
+            id: None,
+            date,
+            name: name.to_string(),
+            custom: false,
+        }
+    }
+}
+
+impl From<CustomHoliday> for Holiday {
+    fn from(holiday: CustomHoliday) -> Self {
+        Self {
+            id: Some(holiday.id),
+            date: holiday.date,
+            name: holiday.name,
+            custom: true,
+        }
+    }
+}
+
+/// The years whose public holidays can be calculated. The Easter algorithm
+/// only holds for the gregorian calendar, which was introduced in 1582.
+pub const SUPPORTED_YEARS: RangeInclusive<i32> = 1583..=9999;
+
+/// Calculates Easter Sunday of the gregorian calendar using the anonymous
+/// gregorian algorithm (Meeus/Jones/Butcher). Only valid for
+/// [`SUPPORTED_YEARS`].
+pub fn easter_sunday(year: i32) -> NaiveDate {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+        .expect("The easter algorithm always yields a valid date")
+}
+
+/// Buß- und Bettag is the last Wednesday before the 23rd of November.
+fn day_of_repentance(year: i32) -> NaiveDate {
+    let mut date = NaiveDate::from_ymd_opt(year, 11, 22).expect("Valid date");
+    while date.weekday() != Weekday::Wed {
+        date = date.pred_opt().expect("Valid date");
+    }
+    date
+}
+
+/// Returns the public holidays of `year`, sorted by date. Without a federal
+/// state, only the nationwide holidays are returned. Years outside of
+/// [`SUPPORTED_YEARS`] have no public holidays.
+pub fn public_holidays(year: i32, state: Option<FederalState>) -> Vec<Holiday> {
+    use FederalState::*;
+
+    if !SUPPORTED_YEARS.contains(&year) {
+        return Vec::new();
+    }
+    let date = |month, day| NaiveDate::from_ymd_opt(year, month, day).expect("Valid date");
+    let easter = easter_sunday(year);
+    let after_easter = |days: u64| easter + Days::new(days);
+    let in_state = |states: &[FederalState]| state.is_some_and(|state| states.contains(&state));
+
+    let mut holidays = vec![
+        Holiday::public(date(1, 1), "Neujahr"),
+        Holiday::public(easter - Days::new(2), "Karfreitag"),
+        Holiday::public(after_easter(1), "Ostermontag"),
+        Holiday::public(date(5, 1), "Tag der Arbeit"),
+        Holiday::public(after_easter(39), "Christi Himmelfahrt"),
+        Holiday::public(after_easter(50), "Pfingstmontag"),
+        Holiday::public(date(10, 3), "Tag der Deutschen Einheit"),
+        Holiday::public(date(12, 25), "1. Weihnachtstag"),
+        Holiday::public(date(12, 26), "2. Weihnachtstag"),
+    ];
+
+    if in_state(&[Bw, By, St]) {
+        holidays.push(Holiday::public(date(1, 6), "Heilige Drei Könige"));
+    }
+    if (in_state(&[Be]) && year >= 2019) || (in_state(&[Mv]) && year >= 2023) {
+        holidays.push(Holiday::public(date(3, 8), "Internationaler Frauentag"));
+    }
+    if in_state(&[Bb]) {
+        holidays.push(Holiday::public(easter, "Ostersonntag"));
+        holidays.push(Holiday::public(after_easter(49), "Pfingstsonntag"));
+    }
+    if in_state(&[Bw, By, He, Nw, Rp, Sl]) {
+        holidays.push(Holiday::public(after_easter(60), "Fronleichnam"));
+    }
+    if in_state(&[Sl]) {
+        holidays.push(Holiday::public(date(8, 15), "Mariä Himmelfahrt"));
+    }
+    if in_state(&[Th]) && year >= 2019 {
+        holidays.push(Holiday::public(date(9, 20), "Weltkindertag"));
+    }
+    // The 500th anniversary of the reformation was a nationwide holiday
+    if in_state(&[Bb, Mv, Sn, St, Th])
+        || (in_state(&[Hb, Hh, Ni, Sh]) && year >= 2018)
+        || year == 2017
+    {
+        holidays.push(Holiday::public(date(10, 31), "Reformationstag"));
+    }
+    if in_state(&[Bw, By, Nw, Rp, Sl]) {
+        holidays.push(Holiday::public(date(11, 1), "Allerheiligen"));
+    }
+    if in_state(&[Sn]) {
+        holidays.push(Holiday::public(day_of_repentance(year), "Buß- und Bettag"));
+    }
+
+    holidays.sort_by_key(|holiday| holiday.date);
+    holidays
+}
+
+/// Returns the public holidays within `[from, to]` combined with the custom
+/// company holidays, sorted by date.
+pub fn holidays_between(
+    from: NaiveDate,
+    to: NaiveDate,
+    state: Option<FederalState>,
+    custom: Vec<CustomHoliday>,
+) -> Vec<Holiday> {
+    let mut holidays: Vec<Holiday> = (from.year()..=to.year())
+        .flat_map(|year| public_holidays(year, state))
+        .filter(|holiday| from <= holiday.date && holiday.date <= to)
+        .chain(custom.into_iter().map(Holiday::from))
+        .collect();
+
+    holidays.sort_by_key(|holiday| holiday.date);
+    holidays
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_easter_sunday() {
+        assert_eq!(easter_sunday(2019), date(2019, 4, 21));
+        assert_eq!(easter_sunday(2024), date(2024, 3, 31));
+        assert_eq!(easter_sunday(2025), date(2025, 4, 20));
+        assert_eq!(easter_sunday(2026), date(2026, 4, 5));
+        assert_eq!(easter_sunday(2038), date(2038, 4, 25));
+        assert_eq!(easter_sunday(1583), date(1583, 4, 10));
+        assert_eq!(easter_sunday(9999), date(9999, 3, 28));
+    }
+
+    #[test]
+    fn test_nationwide_holidays() {
+        let holidays = public_holidays(2025, None);
+
+        let dates: Vec<NaiveDate> = holidays.iter().map(|holiday| holiday.date).collect();
+        assert_eq!(
+            dates,
+            vec![
+                date(2025, 1, 1),
+                date(2025, 4, 18),
+                date(2025, 4, 21),
+                date(2025, 5, 1),
+                date(2025, 5, 29),
+                date(2025, 6, 9),
+                date(2025, 10, 3),
+                date(2025, 12, 25),
+                date(2025, 12, 26),
+            ]
+        );
+        assert!(holidays.iter().all(|holiday| !holiday.custom));
+    }
+
+    #[test]
+    fn test_state_specific_holidays() {
+        let names = |state| -> Vec<String> {
+            public_holidays(2025, Some(state))
+                .into_iter()
+                .map(|holiday| holiday.name)
+                .collect()
+        };
+
+        assert_eq!(names(FederalState::By).len(), 12);
+        assert!(names(FederalState::By).contains(&"Fronleichnam".to_string()));
+        assert!(names(FederalState::Be).contains(&"Internationaler Frauentag".to_string()));
+        assert!(!names(FederalState::Be).contains(&"Fronleichnam".to_string()));
+        assert!(names(FederalState::Hh).contains(&"Reformationstag".to_string()));
+        assert_eq!(names(FederalState::Bb).len(), 12);
+    }
+
+    #[test]
+    fn test_day_of_repentance_in_saxony() {
+        let holidays = public_holidays(2025, Some(FederalState::Sn));
+
+        let repentance = holidays
+            .iter()
+            .find(|holiday| holiday.name == "Buß- und Bettag")
+            .expect("Saxony should have Buß- und Bettag");
+        assert_eq!(repentance.date, date(2025, 11, 19));
+        assert_eq!(day_of_repentance(2023), date(2023, 11, 22));
+    }
+
+    #[test]
+    fn test_holidays_between_merges_custom_holidays() {
+        let custom = vec![CustomHoliday {
+            id: 7,
+            date: date(2025, 12, 24),
+            name: "Heiligabend".to_string(),
+        }];
+
+        let holidays = holidays_between(date(2025, 12, 1), date(2026, 1, 31), None, custom);
+
+        let names: Vec<&str> = holidays
+            .iter()
+            .map(|holiday| holiday.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "Heiligabend",
+                "1. Weihnachtstag",
+                "2. Weihnachtstag",
+                "Neujahr"
+            ]
+        );
+        assert_eq!(holidays[0].id, Some(7));
+        assert!(holidays[0].custom);
+    }
+
+    #[test]
+    fn test_years_before_the_gregorian_calendar_have_no_holidays() {
+        assert!(public_holidays(-2, Some(FederalState::By)).is_empty());
+        assert!(public_holidays(1582, None).is_empty());
+        assert_eq!(public_holidays(1583, None).len(), 9);
+    }
+}
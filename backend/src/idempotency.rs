@@ -0,0 +1,168 @@
+use axum::body::Body;
+use axum::extract::FromRequestParts;
+use axum::extract::Request;
+use axum::extract::State;
+use axum::http::HeaderValue;
+use axum::http::Method;
+use axum::http::StatusCode;
+use axum::http::header::CONTENT_TYPE;
+use axum::middleware::Next;
+use axum::response::IntoResponse;
+use axum::response::Response;
+use chrono::TimeDelta;
+use chrono::Utc;
+use sha2::Digest;
+use sha2::Sha256;
+
+use crate::ApiContext;
+use crate::caller::Caller;
+use crate::error::AppError;
+use crate::models::idempotency::StoredResponse;
+
+/// The request header that identifies retries of the same request.
+pub const IDEMPOTENCY_KEY: &str = "idempotency-key";
+/// The response header that marks a replayed response.
+pub const IDEMPOTENT_REPLAYED: &str = "idempotent-replayed";
+
+const MAX_KEY_LENGTH: usize = 255;
+/// The largest request body that is hashed, the default limit of axum.
+const MAX_BODY_SIZE: usize = 2 * 1024 * 1024;
+/// How long a request may run before its key is considered abandoned, e.g.
+/// because the server stopped while running it.
+const PENDING_TIMEOUT: TimeDelta = TimeDelta::minutes(1);
+
+/// Replays the stored response of a mutating request whose `Idempotency-Key`
+/// was already used within the retention window, instead of running it again.
+/// Keys are scoped to the client, user and device and may only be reused for
+/// the same request.
+/// A retry that arrives while the request is still running is rejected, and
+/// responses are stored unless the server failed, so a failed request can be
+/// retried.
+pub async fn idempotency(
+    State(context): State<ApiContext>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let is_mutating = matches!(
+        *request.method(),
+        Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+    );
+    let key = request
+        .headers()
+        .get(IDEMPOTENCY_KEY)
+        .map(|key| key.to_str().map(str::to_owned));
+
+    match key {
+        Some(key) if is_mutating => match key {
+            Ok(key) => with_key(&context, key, request, next)
+                .await
+                .unwrap_or_else(IntoResponse::into_response),
+            Err(_) => AppError::InvalidInput("The idempotency key must be ASCII").into_response(),
+        },
+        _ => next.run(request).await,
+    }
+}
+
+async fn with_key(
+    context: &ApiContext,
+    key: String,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    if key.is_empty() || key.len() > MAX_KEY_LENGTH {
+        return Err(AppError::InvalidInput(
+            "The idempotency key must have 1 to 255 characters",
+        ));
+    }
+
+    let now = Utc::now();
+    let (mut parts, body) = request.into_parts();
+    let caller = Caller::from_request_parts(&mut parts, context).await?;
+    let Ok(body) = axum::body::to_bytes(body, MAX_BODY_SIZE).await else {
+        return Ok(StatusCode::PAYLOAD_TOO_LARGE.into_response());
+    };
+    let request_hash = Sha256::digest(&body)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    let reservation = StoredResponse {
+        client_id: caller.client_id,
+        user_id: caller.user_id,
+        device_id: caller.device_id,
+        key,
+        method: parts.method.to_string(),
+        path: parts.uri.path().to_string(),
+        request_hash,
+        status: None,
+        content_type: None,
+        body: Vec::new(),
+        created_at: now,
+    };
+
+    let repository = &context.idempotency_repository;
+    if let Some(stored) = repository
+        .reserve_key(
+            &reservation,
+            now - context.settings.idempotency_retention,
+            now - PENDING_TIMEOUT,
+        )
+        .await?
+    {
+        if stored.method != reservation.method
+            || stored.path != reservation.path
+            || stored.request_hash != reservation.request_hash
+        {
+            return Err(AppError::InvalidInput(
+                "The idempotency key was already used for another request",
+            ));
+        }
+        return match stored.status {
+            Some(_) => Ok(replay(stored)),
+            None => Err(AppError::Conflict(
+                "A request with the idempotency key is still running",
+            )),
+        };
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    if response.status().is_server_error() {
+        repository.release_key(&reservation).await?;
+        return Ok(response);
+    }
+
+    let (parts, body) = response.into_parts();
+    let Ok(body) = axum::body::to_bytes(body, usize::MAX).await else {
+        repository.release_key(&reservation).await?;
+        return Ok(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+    };
+    repository
+        .store_response(
+            &reservation,
+            parts.status.as_u16(),
+            parts
+                .headers
+                .get(CONTENT_TYPE)
+                .and_then(|content_type| content_type.to_str().ok())
+                .map(str::to_owned),
+            body.to_vec(),
+        )
+        .await?;
+
+    Ok(Response::from_parts(parts, Body::from(body)))
+}
+
+fn replay(stored: StoredResponse) -> Response {
+    let mut response = Response::new(Body::from(stored.body));
+    *response.status_mut() = StatusCode::from_u16(stored.status.unwrap_or_default())
+        .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    if let Some(content_type) = stored
+        .content_type
+        .and_then(|content_type| HeaderValue::from_str(&content_type).ok())
+    {
+        response.headers_mut().insert(CONTENT_TYPE, content_type);
+    }
+    response
+        .headers_mut()
+        .insert(IDEMPOTENT_REPLAYED, HeaderValue::from_static("true"));
+    response
+}
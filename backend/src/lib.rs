@@ -1,30 +1,148 @@
 #![forbid(unsafe_code)]
 
+pub mod caller;
 pub mod configuration;
 pub mod db;
 mod error;
+pub mod events;
+pub mod holidays;
+mod idempotency;
 mod models;
+mod reports;
 mod repositories;
 mod routes;
+pub mod tasks;
 
 use std::sync::Arc;
 
 use axum::Router;
+use axum::middleware;
+use axum::routing::delete;
 use axum::routing::get;
 use axum::routing::post;
+use axum::routing::put;
 use tower_http::services::ServeDir;
 use tower_http::services::ServeFile;
 
+use crate::configuration::Settings;
+use crate::events::Events;
+use crate::idempotency::idempotency;
+pub use crate::repositories::absence_repositories::AbsenceRepository;
+pub use crate::repositories::absence_repositories::SqliteAbsenceRepository;
+pub use crate::repositories::audit_repositories::AuditRepository;
+pub use crate::repositories::audit_repositories::SqliteAuditRepository;
+pub use crate::repositories::break_repositories::BreakRepository;
+pub use crate::repositories::break_repositories::SqliteBreakRepository;
+pub use crate::repositories::device_repositories::DeviceRepository;
+pub use crate::repositories::device_repositories::SqliteDeviceRepository;
+pub use crate::repositories::holiday_repositories::HolidayRepository;
+pub use crate::repositories::holiday_repositories::SqliteHolidayRepository;
+pub use crate::repositories::idempotency_repositories::IdempotencyRepository;
+pub use crate::repositories::idempotency_repositories::SqliteIdempotencyRepository;
+pub use crate::repositories::journal_repositories::JournalRepository;
+pub use crate::repositories::journal_repositories::SqliteJournalRepository;
+pub use crate::repositories::kiosk_repositories::KioskRepository;
+pub use crate::repositories::kiosk_repositories::SqliteKioskRepository;
+pub use crate::repositories::period_repositories::PeriodRepository;
+pub use crate::repositories::period_repositories::SqlitePeriodRepository;
+pub use crate::repositories::permission_repositories::PermissionRepository;
+pub use crate::repositories::permission_repositories::SqlitePermissionRepository;
 pub use crate::repositories::region_repositories::RegionRepository;
 pub use crate::repositories::region_repositories::SqliteRegionRepository;
+pub use crate::repositories::region_repositories::StopPolicy;
+pub use crate::repositories::rounding_repositories::RoundingRepository;
+pub use crate::repositories::rounding_repositories::SqliteRoundingRepository;
+pub use crate::repositories::sync_repositories::SqliteSyncRepository;
+pub use crate::repositories::sync_repositories::SyncRepository;
+pub use crate::repositories::target_hours_repositories::SqliteTargetHoursRepository;
+pub use crate::repositories::target_hours_repositories::TargetHoursRepository;
+pub use crate::repositories::time_zone_repositories::SqliteTimeZoneRepository;
+pub use crate::repositories::time_zone_repositories::TimeZoneRepository;
+pub use crate::repositories::timesheet_repositories::SqliteTimesheetRepository;
+pub use crate::repositories::timesheet_repositories::TimesheetRepository;
+use crate::routes::absences::add_absence;
+use crate::routes::absences::delete_absence;
+use crate::routes::absences::list_absences;
+use crate::routes::absences::remaining_vacation;
+use crate::routes::absences::set_vacation_entitlement;
+use crate::routes::audit::entry_changes;
+use crate::routes::auto_stopped_history;
+use crate::routes::breaks::start_break;
+use crate::routes::breaks::stop_break;
+use crate::routes::clean_up_history;
 use crate::routes::currently_active;
+use crate::routes::delete_entry;
+use crate::routes::deleted_history;
+use crate::routes::devices::list_devices;
+use crate::routes::devices::register_device;
+use crate::routes::devices::revoke_device;
+use crate::routes::events::events;
+use crate::routes::history;
 use crate::routes::history_by_region;
+use crate::routes::holidays::add_custom_holiday;
+use crate::routes::holidays::delete_custom_holiday;
+use crate::routes::holidays::list_holidays;
+use crate::routes::kiosk::delete_kiosk_credentials;
+use crate::routes::kiosk::kiosk;
+use crate::routes::kiosk::list_kiosk_credentials;
+use crate::routes::kiosk::set_kiosk_credential;
+use crate::routes::merge_history;
+use crate::routes::periods::close_period;
+use crate::routes::periods::list_locked_periods;
+use crate::routes::periods::period_log;
+use crate::routes::periods::reopen_period;
+use crate::routes::permissions::add_group_member;
+use crate::routes::permissions::delete_role;
+use crate::routes::permissions::list_group_members;
+use crate::routes::permissions::list_region_permissions;
+use crate::routes::permissions::list_roles;
+use crate::routes::permissions::remove_group_member;
+use crate::routes::permissions::set_region_permissions;
+use crate::routes::permissions::set_role;
+use crate::routes::reports::flextime_balance;
+use crate::routes::reports::summary;
+use crate::routes::restore_entry;
+use crate::routes::rounding::delete_rounding;
+use crate::routes::rounding::list_roundings;
+use crate::routes::rounding::set_rounding;
 use crate::routes::start_timer;
 use crate::routes::stop_timer;
+use crate::routes::sync::sync;
+use crate::routes::target_hours::add_target_hours;
+use crate::routes::target_hours::delete_target_hours;
+use crate::routes::target_hours::list_target_hours;
+use crate::routes::time_zones::delete_time_zone;
+use crate::routes::time_zones::get_time_zone;
+use crate::routes::time_zones::set_time_zone;
+use crate::routes::timesheets::approve_timesheet;
+use crate::routes::timesheets::get_timesheet;
+use crate::routes::timesheets::list_timesheets;
+use crate::routes::timesheets::reject_timesheet;
+use crate::routes::timesheets::submit_timesheet;
+use crate::routes::toggle_timer;
+use crate::routes::undo::undo;
+use crate::routes::websocket::websocket;
 
 #[derive(Clone)]
 pub struct ApiContext {
     pub region_repository: Arc<dyn RegionRepository>,
+    pub break_repository: Arc<dyn BreakRepository>,
+    pub target_hours_repository: Arc<dyn TargetHoursRepository>,
+    pub holiday_repository: Arc<dyn HolidayRepository>,
+    pub absence_repository: Arc<dyn AbsenceRepository>,
+    pub idempotency_repository: Arc<dyn IdempotencyRepository>,
+    pub sync_repository: Arc<dyn SyncRepository>,
+    pub rounding_repository: Arc<dyn RoundingRepository>,
+    pub journal_repository: Arc<dyn JournalRepository>,
+    pub audit_repository: Arc<dyn AuditRepository>,
+    pub period_repository: Arc<dyn PeriodRepository>,
+    pub timesheet_repository: Arc<dyn TimesheetRepository>,
+    pub permission_repository: Arc<dyn PermissionRepository>,
+    pub kiosk_repository: Arc<dyn KioskRepository>,
+    pub device_repository: Arc<dyn DeviceRepository>,
+    pub time_zone_repository: Arc<dyn TimeZoneRepository>,
+    pub settings: Settings,
+    pub events: Events,
 }
 
 pub fn app(api_context: ApiContext) -> Router {
@@ -35,8 +153,86 @@ pub fn app(api_context: ApiContext) -> Router {
         .route("/hello_world", axum::routing::get(routes::hello_world))
         .route("/api/{region}/start", post(start_timer))
         .route("/api/{region}/stop", post(stop_timer))
+        .route("/api/{region}/toggle", post(toggle_timer))
         .route("/api/{region}/history", get(history_by_region))
         .route("/api/currently_active", get(currently_active))
+        .route("/api/events", get(events))
+        .route("/api/ws", get(websocket))
+        .route("/api/history", get(history))
+        .route("/api/history/auto_stopped", get(auto_stopped_history))
+        .route("/api/history/cleanup", post(clean_up_history))
+        .route("/api/history/merge", post(merge_history))
+        .route("/api/history/trash", get(deleted_history))
+        .route("/api/history/{id}", delete(delete_entry))
+        .route("/api/history/{id}/restore", post(restore_entry))
+        .route("/api/history/{id}/audit", get(entry_changes))
+        .route("/api/sync", post(sync))
+        .route("/api/undo", post(undo))
+        .route("/api/break/start", post(start_break))
+        .route("/api/break/stop", post(stop_break))
+        .route("/api/summary", get(summary))
+        .route("/api/flextime", get(flextime_balance))
+        .route(
+            "/api/time_zone",
+            get(get_time_zone)
+                .put(set_time_zone)
+                .delete(delete_time_zone),
+        )
+        .route("/api/rounding", get(list_roundings))
+        .route(
+            "/api/rounding/{region}",
+            put(set_rounding).delete(delete_rounding),
+        )
+        .route(
+            "/api/target_hours",
+            get(list_target_hours).post(add_target_hours),
+        )
+        .route("/api/target_hours/{id}", delete(delete_target_hours))
+        .route("/api/holidays", get(list_holidays).post(add_custom_holiday))
+        .route("/api/holidays/{id}", delete(delete_custom_holiday))
+        .route("/api/absences", get(list_absences).post(add_absence))
+        .route("/api/absences/{id}", delete(delete_absence))
+        .route("/api/vacation", get(remaining_vacation))
+        .route("/api/vacation/{year}", put(set_vacation_entitlement))
+        .route("/api/periods", get(list_locked_periods))
+        .route("/api/periods/log", get(period_log))
+        .route("/api/periods/{year}/{month}/close", post(close_period))
+        .route("/api/periods/{year}/{month}/reopen", post(reopen_period))
+        .route("/api/kiosk", post(kiosk))
+        .route("/api/kiosk/credentials", get(list_kiosk_credentials))
+        .route(
+            "/api/kiosk/credentials/{user}",
+            put(set_kiosk_credential).delete(delete_kiosk_credentials),
+        )
+        .route("/api/devices", get(list_devices).post(register_device))
+        .route("/api/devices/{id}", delete(revoke_device))
+        .route("/api/roles", get(list_roles))
+        .route("/api/roles/{user}", put(set_role).delete(delete_role))
+        .route("/api/groups", get(list_group_members))
+        .route(
+            "/api/groups/{group}/members/{user}",
+            put(add_group_member).delete(remove_group_member),
+        )
+        .route("/api/permissions", get(list_region_permissions))
+        .route("/api/permissions/{region}", put(set_region_permissions))
+        .route("/api/timesheets", get(list_timesheets))
+        .route(
+            "/api/timesheets/{year}/{month}/submit",
+            post(submit_timesheet),
+        )
+        .route("/api/timesheets/{user}/{year}/{month}", get(get_timesheet))
+        .route(
+            "/api/timesheets/{user}/{year}/{month}/approve",
+            post(approve_timesheet),
+        )
+        .route(
+            "/api/timesheets/{user}/{year}/{month}/reject",
+            post(reject_timesheet),
+        )
+        .layer(middleware::from_fn_with_state(
+            api_context.clone(),
+            idempotency,
+        ))
         .with_state(api_context)
         .fallback_service(static_frontend_files)
 }
@@ -3,11 +3,34 @@ use std::sync::Arc;
 
 use axum::serve;
 use backend::ApiContext;
+use backend::SqliteAbsenceRepository;
+use backend::SqliteAuditRepository;
+use backend::SqliteBreakRepository;
+use backend::SqliteDeviceRepository;
+use backend::SqliteHolidayRepository;
+use backend::SqliteIdempotencyRepository;
+use backend::SqliteJournalRepository;
+use backend::SqliteKioskRepository;
+use backend::SqlitePeriodRepository;
+use backend::SqlitePermissionRepository;
 use backend::SqliteRegionRepository;
+use backend::SqliteRoundingRepository;
+use backend::SqliteSyncRepository;
+use backend::SqliteTargetHoursRepository;
+use backend::SqliteTimeZoneRepository;
+use backend::SqliteTimesheetRepository;
+use backend::StopPolicy;
 use backend::app;
 use backend::configuration::Configuration;
 use backend::configuration::ConfigurationError;
+use backend::configuration::Settings;
 use backend::configuration::load_configuration;
+use backend::events::Events;
+use backend::tasks::AutoStopPolicy;
+use backend::tasks::spawn_auto_stop_task;
+use backend::tasks::spawn_idempotency_purge_task;
+use backend::tasks::spawn_journal_purge_task;
+use backend::tasks::spawn_trash_purge_task;
 
 #[derive(thiserror::Error, Debug)]
 pub enum AppError {
@@ -33,6 +56,30 @@ async fn main() -> Result<(), AppError> {
         e
     })?;
 
+    let auto_stop_policy = AutoStopPolicy::from(&config);
+    if auto_stop_policy.is_enabled() {
+        spawn_auto_stop_task(
+            api_context.region_repository.clone(),
+            auto_stop_policy,
+            api_context.events.clone(),
+        );
+    }
+
+    spawn_idempotency_purge_task(
+        api_context.idempotency_repository.clone(),
+        api_context.settings.idempotency_retention,
+    );
+
+    spawn_journal_purge_task(
+        api_context.journal_repository.clone(),
+        api_context.settings.undo_window,
+    );
+
+    spawn_trash_purge_task(
+        api_context.region_repository.clone(),
+        api_context.settings.trash_retention,
+    );
+
     let addr = SocketAddr::from(([127, 0, 0, 1], config.application_port));
 
     let listener = tokio::net::TcpListener::bind(addr).await.map_err(|e| {
@@ -51,6 +98,48 @@ async fn main() -> Result<(), AppError> {
 
 async fn api_context(config: &Configuration) -> Result<ApiContext, AppError> {
     let pool = backend::db::connect_to_database(&config.database_url).await?;
-    let region_repository = Arc::new(SqliteRegionRepository::new(pool));
-    Ok(ApiContext { region_repository })
+    let region_repository = Arc::new(
+        SqliteRegionRepository::new(pool.clone())
+            .with_stop_policy(StopPolicy::from(config))
+            .with_restart_behavior(config.restart_behavior),
+    );
+    let break_repository = Arc::new(
+        SqliteBreakRepository::new(pool.clone()).with_stop_policy(StopPolicy::from(config)),
+    );
+    let target_hours_repository = Arc::new(SqliteTargetHoursRepository::new(pool.clone()));
+    let holiday_repository = Arc::new(SqliteHolidayRepository::new(pool.clone()));
+    let absence_repository = Arc::new(SqliteAbsenceRepository::new(pool.clone()));
+    let idempotency_repository = Arc::new(SqliteIdempotencyRepository::new(pool.clone()));
+    let sync_repository = Arc::new(
+        SqliteSyncRepository::new(pool.clone()).with_stop_policy(StopPolicy::from(config)),
+    );
+    let rounding_repository = Arc::new(SqliteRoundingRepository::new(pool.clone()));
+    let journal_repository = Arc::new(SqliteJournalRepository::new(pool.clone()));
+    let audit_repository = Arc::new(SqliteAuditRepository::new(pool.clone()));
+    let period_repository = Arc::new(SqlitePeriodRepository::new(pool.clone()));
+    let timesheet_repository = Arc::new(SqliteTimesheetRepository::new(pool.clone()));
+    let permission_repository = Arc::new(SqlitePermissionRepository::new(pool.clone()));
+    let kiosk_repository = Arc::new(SqliteKioskRepository::new(pool.clone()));
+    let device_repository = Arc::new(SqliteDeviceRepository::new(pool.clone()));
+    let time_zone_repository = Arc::new(SqliteTimeZoneRepository::new(pool));
+    Ok(ApiContext {
+        region_repository,
+        break_repository,
+        target_hours_repository,
+        holiday_repository,
+        absence_repository,
+        idempotency_repository,
+        sync_repository,
+        rounding_repository,
+        journal_repository,
+        audit_repository,
+        period_repository,
+        timesheet_repository,
+        permission_repository,
+        kiosk_repository,
+        device_repository,
+        time_zone_repository,
+        settings: Settings::from(config),
+        events: Events::default(),
+    })
 }
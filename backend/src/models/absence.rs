@@ -0,0 +1,67 @@
+use chrono::NaiveDate;
+use serde::Deserialize;
+use serde::Serialize;
+use sqlx::Type;
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum AbsenceKind {
+    Vacation,
+    Sick,
+    Training,
+    /// Time off that is taken from the flextime balance
+    CompTime,
+}
+
+impl AbsenceKind {
+    /// Whether the absence counts as worked time. Comp time is taken from the
+    /// flextime balance, so it must not be credited.
+    pub fn is_credited(&self) -> bool {
+        !matches!(self, AbsenceKind::CompTime)
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq, Clone, sqlx::FromRow)]
+pub struct Absence {
+    pub id: i64,
+    pub date: NaiveDate,
+    pub kind: AbsenceKind,
+    pub half_day: bool,
+}
+
+impl Absence {
+    /// The number of days this absence takes, either a full or a half day.
+    pub fn days(&self) -> f64 {
+        if self.half_day { 0.5 } else { 1.0 }
+    }
+}
+
+/// An absence for every working day within `[from, to]`. Without `to`, only
+/// `from` is recorded.
+#[derive(Debug, Deserialize)]
+pub struct NewAbsence {
+    /// The user who is absent. Defaults to the caller.
+    pub user_id: Option<String>,
+    pub from: NaiveDate,
+    pub to: Option<NaiveDate>,
+    pub kind: AbsenceKind,
+    #[serde(default)]
+    pub half_day: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, sqlx::FromRow)]
+pub struct VacationEntitlement {
+    pub year: i32,
+    pub days: f64,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct VacationBalance {
+    pub year: i32,
+    pub entitlement: f64,
+    /// Vacation days left over from the previous year
+    pub carried_over: f64,
+    pub taken: f64,
+    pub remaining: f64,
+}
@@ -0,0 +1,35 @@
+use chrono::DateTime;
+use chrono::Utc;
+use serde::Serialize;
+use sqlx::Type;
+
+#[derive(Debug, Serialize, PartialEq, Eq, Clone, Copy, Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum ActionKind {
+    StartTimer,
+    StopTimer,
+    ToggleTimer,
+    StartBreak,
+    StopBreak,
+    CleanUpHistory,
+    MergeHistory,
+    SyncEvents,
+    DeleteEntry,
+    RestoreEntry,
+    AutoStopTimer,
+    /// Splits the entries running at the end of a closed month.
+    ClosePeriod,
+    /// Reverts an earlier action. It cannot be undone itself.
+    Undo,
+}
+
+/// A change to the history that was performed by a client and can be undone.
+#[derive(Debug, Serialize, PartialEq, Clone, sqlx::FromRow)]
+pub struct Action {
+    pub id: i64,
+    pub client_id: String,
+    pub user_id: Option<String>,
+    pub kind: ActionKind,
+    pub performed_at: DateTime<Utc>,
+}
@@ -0,0 +1,35 @@
+use chrono::DateTime;
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::Type;
+use sqlx::types::Json;
+
+#[derive(Debug, Serialize, PartialEq, Eq, Clone, Copy, Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum Operation {
+    Insert,
+    Update,
+    Delete,
+}
+
+/// A recorded change to an entry of the history.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub entry_id: i64,
+    pub operation: Operation,
+    /// The user who made the change, if the client sent one
+    pub actor: Option<String>,
+    /// The client that made the change, or `None` for changes outside of the
+    /// application, like manual corrections in the database
+    pub device: Option<String>,
+    /// The registered device that made the change, if it sent its token
+    pub device_id: Option<i64>,
+    /// The entry before the change, `None` for inserts
+    pub old_values: Option<Json<Value>>,
+    /// The entry after the change, `None` for deletes
+    pub new_values: Option<Json<Value>>,
+    pub changed_at: DateTime<Utc>,
+}
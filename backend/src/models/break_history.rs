@@ -0,0 +1,16 @@
+use chrono::DateTime;
+use chrono::Utc;
+use serde::Serialize;
+
+use crate::models::region::Region;
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct BreakHistory {
+    pub start_time: DateTime<Utc>,
+    pub stop_time: Option<DateTime<Utc>>,
+    /// The duration in milliseconds, once the break is stopped
+    pub duration: Option<i64>,
+    /// The region that was running when the break started. It is resumed once
+    /// the break is stopped.
+    pub interrupted_region: Option<Region>,
+}
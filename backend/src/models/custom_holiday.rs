@@ -0,0 +1,17 @@
+use chrono::NaiveDate;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// A company specific holiday, e.g. a company outing or a bridge day.
+#[derive(Debug, Serialize, PartialEq, Clone, sqlx::FromRow)]
+pub struct CustomHoliday {
+    pub id: i64,
+    pub date: NaiveDate,
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewCustomHoliday {
+    pub date: NaiveDate,
+    pub name: String,
+}
@@ -0,0 +1,52 @@
+use chrono::DateTime;
+use chrono::Utc;
+use serde::Deserialize;
+use serde::Serialize;
+use sqlx::Type;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum DeviceKind {
+    Tablet,
+    Phone,
+    Kiosk,
+    Desktop,
+    Other,
+}
+
+/// A registered device. Its changes to the history are attributed to it.
+#[derive(Debug, Serialize, PartialEq, Clone, sqlx::FromRow)]
+pub struct Device {
+    pub id: i64,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: DeviceKind,
+    /// The user that requests with the device's token act as. Shared devices
+    /// belong to no user.
+    pub user_id: Option<String>,
+    pub registered_at: DateTime<Utc>,
+    /// When the device last sent a request with its token
+    pub last_seen_at: Option<DateTime<Utc>>,
+    /// Revoked devices can no longer send requests with their token
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RegisterDevice {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: DeviceKind,
+    /// Registers a personal device of the user. This requires the admin role
+    /// or the setup token.
+    pub user_id: Option<String>,
+}
+
+/// The response to a registration. The token is only shown once and has to
+/// be sent as the `Device-Token` header afterwards.
+#[derive(Debug, Serialize)]
+pub struct RegisteredDevice {
+    #[serde(flatten)]
+    pub device: Device,
+    pub token: String,
+}
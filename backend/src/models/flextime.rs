@@ -0,0 +1,59 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::models::absence::AbsenceKind;
+
+/// Actual and target working time in seconds. Absences are credited as working
+/// time. The balance is positive if more was worked than contracted.
+#[derive(Debug, Serialize, PartialEq, Default, Clone, Copy)]
+pub struct Balance {
+    pub actual: i64,
+    pub credited: i64,
+    pub target: i64,
+    pub balance: i64,
+}
+
+impl Balance {
+    pub fn new(actual: i64, credited: i64, target: i64) -> Self {
+        Self {
+            actual,
+            credited,
+            target,
+            balance: actual + credited - target,
+        }
+    }
+
+    pub fn add(&mut self, other: Balance) {
+        *self = Balance::new(
+            self.actual + other.actual,
+            self.credited + other.credited,
+            self.target + other.target,
+        );
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct DayBalance {
+    pub date: NaiveDate,
+    pub absence: Option<AbsenceKind>,
+    #[serde(flatten)]
+    pub balance: Balance,
+}
+
+/// The balance of a week (`2025-W40`) or month (`2025-10`).
+#[derive(Debug, Serialize, PartialEq)]
+pub struct PeriodBalance {
+    pub period: String,
+    #[serde(flatten)]
+    pub balance: Balance,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Flextime {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub days: Vec<DayBalance>,
+    pub weeks: Vec<PeriodBalance>,
+    pub months: Vec<PeriodBalance>,
+    pub overall: Balance,
+}
@@ -0,0 +1,24 @@
+use chrono::DateTime;
+use chrono::Utc;
+use sqlx::FromRow;
+
+/// A request with an idempotency key and, once it finished, its response, which
+/// is replayed when the request is sent again.
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct StoredResponse {
+    /// The client that sent the request. Keys of different clients, users and
+    /// devices are independent.
+    pub client_id: String,
+    pub user_id: Option<String>,
+    pub device_id: Option<i64>,
+    pub key: String,
+    pub method: String,
+    pub path: String,
+    /// The SHA-256 hash of the request body
+    pub request_hash: String,
+    /// The status of the response, or `None` while the request is running
+    pub status: Option<u16>,
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+    pub created_at: DateTime<Utc>,
+}
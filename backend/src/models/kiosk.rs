@@ -0,0 +1,75 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::models::region::Region;
+
+/// What a worker identifies with at the kiosk, e.g. `{"pin": "1234"}`.
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum Credential {
+    /// Four to eight digits
+    Pin(String),
+    /// The number of an RFID badge
+    Badge(String),
+}
+
+impl Credential {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Credential::Pin(_) => "pin",
+            Credential::Badge(_) => "badge",
+        }
+    }
+
+    pub fn value(&self) -> &str {
+        match self {
+            Credential::Pin(value) | Credential::Badge(value) => value,
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Credential::Pin(pin) => {
+                (4..=8).contains(&pin.len()) && pin.chars().all(|c| c.is_ascii_digit())
+            }
+            Credential::Badge(badge) => {
+                (1..=64).contains(&badge.len()) && badge.chars().all(|c| c.is_ascii_alphanumeric())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum KioskAction {
+    Start,
+    Stop,
+    Toggle,
+}
+
+/// A single action at the kiosk. The worker is identified for this request
+/// only, there is no session.
+#[derive(Debug, Deserialize)]
+pub struct KioskRequest {
+    #[serde(flatten)]
+    pub credential: Credential,
+    pub region: Region,
+    pub action: KioskAction,
+}
+
+/// The outcome of a kiosk action, so the terminal can greet the worker.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct KioskResponse {
+    pub user_id: String,
+    pub region: Region,
+    pub running: bool,
+    /// The duration of the stopped entry in milliseconds, or zero if the timer
+    /// was started
+    pub duration: i64,
+}
+
+#[derive(Debug, Serialize, PartialEq, Clone, sqlx::FromRow)]
+pub struct KioskCredential {
+    pub user_id: String,
+    pub kind: String,
+}
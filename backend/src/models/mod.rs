@@ -1,2 +1,19 @@
+pub mod absence;
+pub mod action;
+pub mod audit;
+pub mod break_history;
+pub mod custom_holiday;
+pub mod device;
+pub mod flextime;
+pub mod idempotency;
+pub mod kiosk;
+pub mod period;
+pub mod permission;
 pub mod region;
 pub mod region_history;
+pub mod rounding;
+pub mod summary;
+pub mod sync;
+pub mod target_hours;
+pub mod time_zone;
+pub mod timesheet;
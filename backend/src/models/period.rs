@@ -0,0 +1,46 @@
+use chrono::DateTime;
+use chrono::Utc;
+use serde::Deserialize;
+use serde::Serialize;
+use sqlx::Type;
+
+/// A closed month. Entries that overlap it can't be changed until it is
+/// reopened.
+#[derive(Debug, Serialize, PartialEq, Clone, sqlx::FromRow)]
+pub struct PeriodLock {
+    pub year: i32,
+    pub month: u32,
+    /// The start of the month in the time zone it was closed in
+    pub starts_at: DateTime<Utc>,
+    /// The start of the following month
+    pub ends_at: DateTime<Utc>,
+    pub closed_at: DateTime<Utc>,
+    pub closed_by: Option<String>,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq, Clone, Copy, Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum PeriodOperation {
+    Close,
+    Reopen,
+}
+
+/// A closing or reopening of a month.
+#[derive(Debug, Serialize, PartialEq, Clone, sqlx::FromRow)]
+pub struct PeriodLogEntry {
+    pub id: i64,
+    pub year: i32,
+    pub month: u32,
+    pub operation: PeriodOperation,
+    pub actor: Option<String>,
+    pub device: String,
+    pub reason: Option<String>,
+    pub performed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReopenPeriod {
+    /// Why the month has to be changed again
+    pub reason: String,
+}
@@ -0,0 +1,55 @@
+use serde::Deserialize;
+use serde::Serialize;
+use sqlx::Type;
+
+use crate::models::region::Region;
+
+/// What a user may do. Every role includes the rights of the roles before it.
+#[derive(
+    Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default, Type,
+)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum Role {
+    /// Books time on the regions they are allowed to
+    #[default]
+    Member,
+    /// Reviews timesheets
+    Manager,
+    /// Manages roles, permissions and closed periods, and may book time on
+    /// every region
+    Admin,
+}
+
+#[derive(Debug, Serialize, PartialEq, Clone, sqlx::FromRow)]
+pub struct UserRole {
+    pub user_id: String,
+    pub role: Role,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetRole {
+    pub role: Role,
+}
+
+/// The users and groups that may book time on a region.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct AllowList {
+    #[serde(default)]
+    pub users: Vec<String>,
+    #[serde(default)]
+    pub groups: Vec<String>,
+}
+
+#[derive(Debug, Serialize, PartialEq, Clone)]
+pub struct RegionPermissions {
+    pub region: Region,
+    #[serde(flatten)]
+    pub allow_list: AllowList,
+}
+
+#[derive(Debug, Serialize, PartialEq, Clone, sqlx::FromRow)]
+pub struct GroupMember {
+    pub group_name: String,
+    pub user_id: String,
+}
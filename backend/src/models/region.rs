@@ -2,7 +2,7 @@ use serde::Deserialize;
 use serde::Serialize;
 use sqlx::Type;
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Type)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Clone, Type)]
 #[serde(rename_all = "lowercase")]
 #[sqlx(type_name = "TEXT", rename_all = "lowercase")]
 pub enum Region {
@@ -17,7 +17,30 @@ pub enum Region {
 #[derive(Debug, Serialize)]
 pub struct CurrentlyActiveRegion {
     pub region: Option<Region>,
+    /// The milliseconds the active timer has been running
     pub duration: Option<i64>,
+    pub on_break: bool,
+}
+
+/// The state of a region after its timer was toggled.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ToggledTimer {
+    pub region: Region,
+    pub running: bool,
+    /// The duration of the stopped entry in milliseconds, or zero if the timer
+    /// was started
+    pub duration: i64,
+    /// The timer of another region that was stopped to start this one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stopped: Option<StoppedTimer>,
+}
+
+/// A timer that was stopped because another region was started.
+#[derive(Debug, Serialize, PartialEq, Clone)]
+pub struct StoppedTimer {
+    pub region: Region,
+    /// The duration of the stopped entry in milliseconds
+    pub duration: i64,
 }
 
 impl CurrentlyActiveRegion {
@@ -25,6 +48,7 @@ impl CurrentlyActiveRegion {
         CurrentlyActiveRegion {
             region: None,
             duration: None,
+            on_break: false,
         }
     }
 }
@@ -1,13 +1,74 @@
 use chrono::DateTime;
+use chrono::FixedOffset;
+use chrono::TimeZone;
 use chrono::Utc;
 use serde::Serialize;
 
 use crate::models::region::Region;
 
-#[derive(Debug, Serialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, Clone, sqlx::FromRow)]
 pub struct RegionHistory {
+    pub id: i64,
     pub region: Region,
     pub start_time: DateTime<Utc>,
     pub stop_time: Option<DateTime<Utc>>,
+    /// The duration in milliseconds, once the entry is stopped
     pub duration: Option<i64>,
+    /// Whether the timer was stopped automatically, because it was forgotten.
+    /// These entries should be reviewed.
+    pub auto_stopped: bool,
+}
+
+/// A [`RegionHistory`] whose timestamps are rendered with the offset of a time
+/// zone instead of UTC.
+#[derive(Debug, Serialize)]
+pub struct LocalRegionHistory {
+    pub id: i64,
+    pub region: Region,
+    pub start_time: DateTime<FixedOffset>,
+    pub stop_time: Option<DateTime<FixedOffset>>,
+    pub duration: Option<i64>,
+    pub auto_stopped: bool,
+}
+
+impl RegionHistory {
+    pub fn in_time_zone<Tz: TimeZone>(self, tz: &Tz) -> LocalRegionHistory {
+        let local = |time: DateTime<Utc>| time.with_timezone(tz).fixed_offset();
+        LocalRegionHistory {
+            id: self.id,
+            region: self.region,
+            start_time: local(self.start_time),
+            stop_time: self.stop_time.map(local),
+            duration: self.duration,
+            auto_stopped: self.auto_stopped,
+        }
+    }
+}
+
+/// An entry that was removed for being shorter than the minimum duration.
+#[derive(Debug, Serialize)]
+pub struct RemovedEntry {
+    #[serde(flatten)]
+    pub entry: RegionHistory,
+    /// The entry that received the time of the removed entry, or `None` if
+    /// the entry was discarded
+    pub merged_into: Option<i64>,
+}
+
+/// An entry with the user it belongs to.
+#[derive(Debug, sqlx::FromRow)]
+pub struct OwnedEntry {
+    pub user_id: Option<String>,
+    #[sqlx(flatten)]
+    pub entry: RegionHistory,
+}
+
+/// An entry in the trash, which is hidden from the history until it is
+/// restored or purged.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct DeletedEntry {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub entry: RegionHistory,
+    pub deleted_at: DateTime<Utc>,
 }
@@ -0,0 +1,67 @@
+use std::ops::RangeInclusive;
+
+use serde::Deserialize;
+use serde::Serialize;
+use sqlx::Type;
+
+use crate::models::region::Region;
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum RoundingMode {
+    Up,
+    Down,
+    /// Rounds to the closer multiple, and up if both are equally close
+    Nearest,
+}
+
+/// The increments in minutes that policies may use, up to a whole day.
+pub const INCREMENTS: RangeInclusive<i64> = 1..=1440;
+
+/// Rounds durations to a multiple of `increment` minutes, e.g. up to the next
+/// quarter of an hour.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy, sqlx::FromRow)]
+pub struct RoundingPolicy {
+    pub mode: RoundingMode,
+    pub increment: i64,
+}
+
+impl RoundingPolicy {
+    /// Rounds a duration in milliseconds. Increments outside of
+    /// [`INCREMENTS`] leave the duration unchanged.
+    pub fn apply(&self, millis: i64) -> i64 {
+        if !INCREMENTS.contains(&self.increment) {
+            return millis;
+        }
+        let increment = self.increment * 60_000;
+        let rounded_down = millis.div_euclid(increment) * increment;
+        let remainder = millis - rounded_down;
+        let round_up = match self.mode {
+            RoundingMode::Up => remainder > 0,
+            RoundingMode::Down => false,
+            RoundingMode::Nearest => remainder * 2 >= increment,
+        };
+        if round_up {
+            rounded_down + increment
+        } else {
+            rounded_down
+        }
+    }
+}
+
+/// A rounding policy that replaces the default for the entries of a region.
+#[derive(Debug, Serialize, PartialEq, Clone, sqlx::FromRow)]
+pub struct RegionRounding {
+    pub region: Region,
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub policy: RoundingPolicy,
+}
+
+/// The default rounding policy and the policies of individual regions.
+#[derive(Debug, Serialize)]
+pub struct RoundingPolicies {
+    pub default: Option<RoundingPolicy>,
+    pub regions: Vec<RegionRounding>,
+}
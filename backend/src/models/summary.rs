@@ -0,0 +1,29 @@
+use chrono::DateTime;
+use chrono::Utc;
+use serde::Serialize;
+
+use crate::models::region::Region;
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct RegionSummary {
+    pub region: Region,
+    /// The worked milliseconds
+    pub duration: i64,
+    /// The worked milliseconds after rounding each entry with the rounding
+    /// policy of the region
+    pub billable_duration: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Summary {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub regions: Vec<RegionSummary>,
+    /// The worked milliseconds of all regions
+    pub work_duration: i64,
+    /// The billable milliseconds of all regions
+    pub billable_duration: i64,
+    /// The milliseconds spent on breaks
+    pub break_duration: i64,
+    pub break_count: usize,
+}
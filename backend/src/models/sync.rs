@@ -0,0 +1,60 @@
+use chrono::DateTime;
+use chrono::Utc;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::models::region::Region;
+use crate::models::region_history::RegionHistory;
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncAction {
+    Start,
+    Stop,
+}
+
+/// A start or stop that a client recorded, possibly while it was offline.
+#[derive(Debug, Deserialize, Clone)]
+pub struct ClientEvent {
+    /// Identifies the event within the client, so a batch can be resent.
+    pub event_id: String,
+    #[serde(rename = "type")]
+    pub action: SyncAction,
+    pub region: Region,
+    /// When the event happened on the client.
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SyncRequest {
+    pub client_id: String,
+    pub events: Vec<ClientEvent>,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncStatus {
+    /// The event changed the history.
+    Applied,
+    /// The event was already synchronized before.
+    Duplicate,
+    /// The event conflicts with the history and had no effect.
+    Ignored,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct SyncedEvent {
+    pub event_id: String,
+    pub status: SyncStatus,
+    /// Why an event was ignored
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncResponse {
+    pub events: Vec<SyncedEvent>,
+    /// The history from the earliest synchronized event until now, after all
+    /// events were applied.
+    pub history: Vec<RegionHistory>,
+}
@@ -0,0 +1,85 @@
+use chrono::Datelike;
+use chrono::NaiveDate;
+use chrono::Weekday;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// The longest possible target of a day in seconds.
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// The contracted working time per weekday in seconds, valid from
+/// `valid_from` until and including `valid_until`. An open end means the
+/// schedule is valid until it is replaced.
+#[derive(Debug, Serialize, Clone, PartialEq, sqlx::FromRow)]
+pub struct TargetHours {
+    pub id: i64,
+    pub user_id: Option<String>,
+    pub valid_from: NaiveDate,
+    pub valid_until: Option<NaiveDate>,
+    pub monday: i64,
+    pub tuesday: i64,
+    pub wednesday: i64,
+    pub thursday: i64,
+    pub friday: i64,
+    pub saturday: i64,
+    pub sunday: i64,
+}
+
+impl TargetHours {
+    pub fn is_valid_on(&self, date: NaiveDate) -> bool {
+        self.valid_from <= date && self.valid_until.is_none_or(|until| date <= until)
+    }
+
+    /// The target in seconds for the weekday of `date`, ignoring the validity
+    /// period.
+    pub fn target_for(&self, date: NaiveDate) -> i64 {
+        match date.weekday() {
+            Weekday::Mon => self.monday,
+            Weekday::Tue => self.tuesday,
+            Weekday::Wed => self.wednesday,
+            Weekday::Thu => self.thursday,
+            Weekday::Fri => self.friday,
+            Weekday::Sat => self.saturday,
+            Weekday::Sun => self.sunday,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewTargetHours {
+    /// The user whose contract the schedule is. Defaults to the caller.
+    pub user_id: Option<String>,
+    pub valid_from: NaiveDate,
+    pub valid_until: Option<NaiveDate>,
+    #[serde(default)]
+    pub monday: i64,
+    #[serde(default)]
+    pub tuesday: i64,
+    #[serde(default)]
+    pub wednesday: i64,
+    #[serde(default)]
+    pub thursday: i64,
+    #[serde(default)]
+    pub friday: i64,
+    #[serde(default)]
+    pub saturday: i64,
+    #[serde(default)]
+    pub sunday: i64,
+}
+
+impl NewTargetHours {
+    /// Whether every weekday target lies between zero and a full day.
+    pub fn has_valid_targets(&self) -> bool {
+        [
+            self.monday,
+            self.tuesday,
+            self.wednesday,
+            self.thursday,
+            self.friday,
+            self.saturday,
+            self.sunday,
+        ]
+        .iter()
+        .all(|target| (0..=SECONDS_PER_DAY).contains(target))
+    }
+}
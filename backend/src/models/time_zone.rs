@@ -0,0 +1,18 @@
+use chrono_tz::Tz;
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Debug, Deserialize)]
+pub struct SetTimeZone {
+    /// An IANA name like `Europe/Berlin`
+    pub time_zone: Tz,
+}
+
+/// The time zone that the caller's reports are computed in.
+#[derive(Debug, Serialize)]
+pub struct UserTimeZone {
+    pub time_zone: Tz,
+    /// Whether the user chose the time zone, instead of using the configured
+    /// one
+    pub personal: bool,
+}
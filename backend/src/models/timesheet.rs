@@ -0,0 +1,59 @@
+use chrono::DateTime;
+use chrono::Utc;
+use serde::Deserialize;
+use serde::Serialize;
+use sqlx::Type;
+
+use crate::models::flextime::Flextime;
+use crate::models::summary::Summary;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum TimesheetStatus {
+    /// Waiting for a manager's review
+    Submitted,
+    Approved,
+    /// Has to be corrected and submitted again
+    Rejected,
+}
+
+/// The monthly hours of a user, as submitted for approval.
+#[derive(Debug, Serialize, PartialEq, Clone, sqlx::FromRow)]
+pub struct Timesheet {
+    pub user_id: String,
+    pub year: i32,
+    pub month: u32,
+    pub status: TimesheetStatus,
+    pub submitted_at: DateTime<Utc>,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    /// The comment of the last review
+    pub comment: Option<String>,
+}
+
+/// A change of the status of a timesheet.
+#[derive(Debug, Serialize, PartialEq, Clone, sqlx::FromRow)]
+pub struct TimesheetEvent {
+    pub id: i64,
+    pub status: TimesheetStatus,
+    pub actor: String,
+    pub comment: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// A timesheet with every change of its status, oldest first, and the hours
+/// of the month it covers.
+#[derive(Debug, Serialize)]
+pub struct TimesheetDetails {
+    #[serde(flatten)]
+    pub timesheet: Timesheet,
+    pub events: Vec<TimesheetEvent>,
+    pub summary: Summary,
+    pub flextime: Flextime,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TimesheetReview {
+    pub comment: Option<String>,
+}
@@ -0,0 +1,805 @@
+use std::collections::BTreeMap;
+
+use chrono::DateTime;
+use chrono::Datelike;
+use chrono::Days;
+use chrono::LocalResult;
+use chrono::NaiveDate;
+use chrono::NaiveTime;
+use chrono::TimeDelta;
+use chrono::TimeZone;
+use chrono::Utc;
+
+use crate::holidays::Holiday;
+use crate::models::absence::Absence;
+use crate::models::absence::AbsenceKind;
+use crate::models::absence::VacationBalance;
+use crate::models::absence::VacationEntitlement;
+use crate::models::break_history::BreakHistory;
+use crate::models::flextime::Balance;
+use crate::models::flextime::DayBalance;
+use crate::models::flextime::Flextime;
+use crate::models::flextime::PeriodBalance;
+use crate::models::region::Region;
+use crate::models::region_history::RegionHistory;
+use crate::models::rounding::RegionRounding;
+use crate::models::rounding::RoundingPolicy;
+use crate::models::summary::RegionSummary;
+use crate::models::summary::Summary;
+use crate::models::target_hours::TargetHours;
+
+/// Returns the milliseconds between `start` and `stop`. Every stored and
+/// returned duration is calculated by this function, so they cannot disagree.
+pub fn millis_between(start: DateTime<Utc>, stop: DateTime<Utc>) -> i64 {
+    (stop - start).num_milliseconds()
+}
+
+/// Returns the number of milliseconds of the interval `[start, stop)` that lie
+/// within `[from, to)`. A missing `stop` is treated as still running until
+/// `now`.
+fn millis_within(
+    start: DateTime<Utc>,
+    stop: Option<DateTime<Utc>>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> i64 {
+    let start = start.max(from);
+    let stop = stop.unwrap_or(now).min(to);
+    millis_between(start, stop).max(0)
+}
+
+/// The rounding policies that turn worked time into billable time.
+#[derive(Default)]
+pub struct Rounding<'a> {
+    /// Applies to all regions without their own policy
+    pub default: Option<RoundingPolicy>,
+    pub regions: &'a [RegionRounding],
+}
+
+impl Rounding<'_> {
+    /// Returns the billable milliseconds of an entry of the region.
+    fn billable(&self, region: &Region, millis: i64) -> i64 {
+        let policy = self
+            .regions
+            .iter()
+            .find(|rounding| rounding.region == *region)
+            .map(|rounding| rounding.policy)
+            .or(self.default);
+        match policy {
+            Some(policy) => policy.apply(millis),
+            None => millis,
+        }
+    }
+}
+
+/// Summarizes the worked time per region and the taken breaks within `[from,
+/// to)`. Entries crossing the range boundaries only count with the part that
+/// lies inside the range. The billable time rounds every entry on its own, the
+/// stored durations are not changed.
+pub fn summarize(
+    history: &[RegionHistory],
+    breaks: &[BreakHistory],
+    rounding: &Rounding,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Summary {
+    let mut per_region: BTreeMap<Region, (i64, i64)> = BTreeMap::new();
+    for entry in history {
+        let millis = millis_within(entry.start_time, entry.stop_time, from, to, now);
+        let (duration, billable_duration) = per_region.entry(entry.region.clone()).or_default();
+        *duration += millis;
+        *billable_duration += rounding.billable(&entry.region, millis);
+    }
+
+    let break_duration = breaks
+        .iter()
+        .map(|entry| millis_within(entry.start_time, entry.stop_time, from, to, now))
+        .sum();
+
+    let regions: Vec<RegionSummary> = per_region
+        .into_iter()
+        .map(|(region, (duration, billable_duration))| RegionSummary {
+            region,
+            duration,
+            billable_duration,
+        })
+        .collect();
+
+    Summary {
+        from,
+        to,
+        work_duration: regions.iter().map(|region| region.duration).sum(),
+        billable_duration: regions.iter().map(|region| region.billable_duration).sum(),
+        regions,
+        break_duration,
+        break_count: breaks.len(),
+    }
+}
+
+/// Converts the local `time` on `date` in the time zone `tz` to UTC. A time
+/// that occurs twice resolves to the earlier occurrence, a time that is skipped
+/// by a daylight saving time transition to the first valid local time after it.
+pub fn local_to_utc<Tz: TimeZone>(date: NaiveDate, time: NaiveTime, tz: &Tz) -> DateTime<Utc> {
+    let local = date.and_time(time);
+    (0..24)
+        .find_map(
+            |hours| match tz.from_local_datetime(&(local + TimeDelta::hours(hours))) {
+                LocalResult::Single(time) | LocalResult::Ambiguous(time, _) => Some(time),
+                LocalResult::None => None,
+            },
+        )
+        .map(|time| time.with_timezone(&Utc))
+        .unwrap_or_else(|| local.and_utc())
+}
+
+/// Returns the start of `date` in the time zone `tz`, which is also the end of
+/// the previous day.
+pub fn start_of_local_day<Tz: TimeZone>(date: NaiveDate, tz: &Tz) -> DateTime<Utc> {
+    local_to_utc(date, NaiveTime::MIN, tz)
+}
+
+/// Whether `time` is midnight in the time zone `tz`, where one day ends and the
+/// next one starts.
+pub fn is_day_boundary<Tz: TimeZone>(time: DateTime<Utc>, tz: &Tz) -> bool {
+    start_of_local_day(time.with_timezone(tz).date_naive(), tz) == time
+}
+
+/// Splits the interval `[start, stop)` at the local day boundaries of `tz`.
+/// Days are not always 24 hours long, as daylight saving time transitions are
+/// respected.
+pub fn split_at_day_boundaries<Tz: TimeZone>(
+    start: DateTime<Utc>,
+    stop: DateTime<Utc>,
+    tz: &Tz,
+) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    let mut segments = Vec::new();
+    let mut segment_start = start;
+
+    while segment_start < stop {
+        let next_day = segment_start.with_timezone(tz).date_naive() + Days::new(1);
+        let segment_stop = start_of_local_day(next_day, tz).min(stop);
+        segments.push((segment_start, segment_stop));
+        segment_start = segment_stop;
+    }
+
+    if segments.is_empty() {
+        segments.push((start, stop));
+    }
+    segments
+}
+
+/// Splits every entry at the local day boundaries of `tz`, so that each entry
+/// belongs to a single day. A running entry is split up to `now` and its last
+/// part keeps running. The stored entries are not changed.
+pub fn split_history_by_day<Tz: TimeZone>(
+    history: Vec<RegionHistory>,
+    tz: &Tz,
+    now: DateTime<Utc>,
+) -> Vec<RegionHistory> {
+    history
+        .into_iter()
+        .flat_map(|entry| {
+            let stop = entry.stop_time.unwrap_or(now.max(entry.start_time));
+            let segments = split_at_day_boundaries(entry.start_time, stop, tz);
+            let last = segments.len() - 1;
+
+            segments
+                .into_iter()
+                .enumerate()
+                .map(|(index, (start_time, stop_time))| {
+                    let is_running = index == last && entry.stop_time.is_none();
+                    RegionHistory {
+                        start_time,
+                        stop_time: (!is_running).then_some(stop_time),
+                        duration: (!is_running).then(|| millis_between(start_time, stop_time)),
+                        ..entry.clone()
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Everything that determines how much working time is expected on a day.
+pub struct Calendar<'a> {
+    pub schedules: &'a [TargetHours],
+    pub holidays: &'a [Holiday],
+    pub absences: &'a [Absence],
+}
+
+impl Calendar<'_> {
+    /// Returns the contracted working time in seconds for `date`, based on the
+    /// schedule that is valid on that day. There is no target on holidays.
+    pub fn target_on(&self, date: NaiveDate) -> i64 {
+        if self.holidays.iter().any(|holiday| holiday.date == date) {
+            return 0;
+        }
+
+        self.schedules
+            .iter()
+            .find(|schedule| schedule.is_valid_on(date))
+            .map(|schedule| schedule.target_for(date))
+            .unwrap_or(0)
+    }
+
+    pub fn absence_on(&self, date: NaiveDate) -> Option<&Absence> {
+        self.absences.iter().find(|absence| absence.date == date)
+    }
+
+    /// Returns the working time in seconds that is credited for an absence on
+    /// `date`. A credited absence counts as the target of that day, or half of
+    /// it for half days.
+    fn credit_on(&self, date: NaiveDate) -> i64 {
+        match self.absence_on(date) {
+            Some(absence) if absence.kind.is_credited() => {
+                let target = self.target_on(date);
+                if absence.half_day { target / 2 } else { target }
+            }
+            _ => 0,
+        }
+    }
+
+    /// Returns the days within `[from, to]` that have target hours.
+    pub fn working_days(&self, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+        from.iter_days()
+            .take_while(|date| *date <= to)
+            .filter(|date| self.target_on(*date) > 0)
+            .collect()
+    }
+}
+
+/// Calculates the flextime balance (actual plus credited absences minus target
+/// working time) for every day in `[from, to]`, grouped into ISO weeks, months
+/// and an overall balance. Days are local days of the time zone `tz`. Like the
+/// target hours, balances are in seconds.
+pub fn flextime<Tz: TimeZone>(
+    history: &[RegionHistory],
+    calendar: &Calendar,
+    from: NaiveDate,
+    to: NaiveDate,
+    tz: &Tz,
+    now: DateTime<Utc>,
+) -> Flextime {
+    let mut days = Vec::new();
+    let mut weeks: BTreeMap<String, Balance> = BTreeMap::new();
+    let mut months: BTreeMap<String, Balance> = BTreeMap::new();
+    let mut overall = Balance::default();
+
+    for date in from.iter_days().take_while(|date| *date <= to) {
+        let day_start = start_of_local_day(date, tz);
+        let day_end = start_of_local_day(date + Days::new(1), tz);
+        let actual = history
+            .iter()
+            .map(|entry| millis_within(entry.start_time, entry.stop_time, day_start, day_end, now))
+            .sum::<i64>()
+            / 1000;
+        let balance = Balance::new(actual, calendar.credit_on(date), calendar.target_on(date));
+
+        let week = date.iso_week();
+        weeks
+            .entry(format!("{}-W{:02}", week.year(), week.week()))
+            .or_default()
+            .add(balance);
+        months
+            .entry(format!("{}-{:02}", date.year(), date.month()))
+            .or_default()
+            .add(balance);
+        overall.add(balance);
+        days.push(DayBalance {
+            date,
+            absence: calendar.absence_on(date).map(|absence| absence.kind),
+            balance,
+        });
+    }
+
+    let into_periods = |periods: BTreeMap<String, Balance>| {
+        periods
+            .into_iter()
+            .map(|(period, balance)| PeriodBalance { period, balance })
+            .collect()
+    };
+
+    Flextime {
+        from,
+        to,
+        days,
+        weeks: into_periods(weeks),
+        months: into_periods(months),
+        overall,
+    }
+}
+
+/// Calculates the remaining vacation of `year`. Vacation that was not taken is
+/// carried over into the following year, starting with the first year that has
+/// an entitlement.
+pub fn vacation_balance(
+    year: i32,
+    entitlements: &[VacationEntitlement],
+    absences: &[Absence],
+) -> VacationBalance {
+    let taken_in = |year: i32| -> f64 {
+        absences
+            .iter()
+            .filter(|absence| absence.kind == AbsenceKind::Vacation && absence.date.year() == year)
+            .map(Absence::days)
+            .sum()
+    };
+    let entitlement_in = |year: i32| -> f64 {
+        entitlements
+            .iter()
+            .find(|entitlement| entitlement.year == year)
+            .map(|entitlement| entitlement.days)
+            .unwrap_or(0.0)
+    };
+
+    let first_year = entitlements
+        .iter()
+        .map(|entitlement| entitlement.year)
+        .min()
+        .unwrap_or(year)
+        .min(year);
+
+    let mut carried_over = 0.0;
+    for previous in first_year..year {
+        carried_over = (entitlement_in(previous) + carried_over - taken_in(previous)).max(0.0);
+    }
+
+    let entitlement = entitlement_in(year);
+    let taken = taken_in(year);
+    VacationBalance {
+        year,
+        entitlement,
+        carried_over,
+        taken,
+        remaining: entitlement + carried_over - taken,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::models::rounding::RoundingMode;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 10, 1, hour, minute, 0).unwrap()
+    }
+
+    fn entry(region: Region, start: DateTime<Utc>, stop: Option<DateTime<Utc>>) -> RegionHistory {
+        RegionHistory {
+            id: 0,
+            region,
+            start_time: start,
+            stop_time: stop,
+            duration: stop.map(|stop| millis_between(start, stop)),
+            auto_stopped: false,
+        }
+    }
+
+    #[test]
+    fn test_summarize_groups_by_region_and_separates_breaks() {
+        // Given
+        let history = vec![
+            entry(Region::Ac1, at(8, 0), Some(at(10, 0))),
+            entry(Region::Aa1, at(10, 0), Some(at(12, 0))),
+            entry(Region::Ac1, at(12, 30), Some(at(13, 0))),
+        ];
+        let breaks = vec![BreakHistory {
+            start_time: at(12, 0),
+            stop_time: Some(at(12, 30)),
+            duration: Some(1_800_000),
+            interrupted_region: Some(Region::Aa1),
+        }];
+
+        // When
+        let summary = summarize(
+            &history,
+            &breaks,
+            &Rounding::default(),
+            at(0, 0),
+            at(23, 0),
+            at(23, 0),
+        );
+
+        // Then
+        assert_eq!(
+            summary.regions,
+            vec![
+                RegionSummary {
+                    region: Region::Aa1,
+                    duration: 7_200_000,
+                    billable_duration: 7_200_000,
+                },
+                RegionSummary {
+                    region: Region::Ac1,
+                    duration: 9_000_000,
+                    billable_duration: 9_000_000,
+                },
+            ]
+        );
+        assert_eq!(summary.work_duration, 16_200_000);
+        assert_eq!(summary.break_duration, 1_800_000);
+        assert_eq!(summary.break_count, 1);
+    }
+
+    #[test]
+    fn test_summarize_clips_entries_to_range() {
+        // Given
+        let history = vec![
+            entry(Region::Ac1, at(7, 0), Some(at(9, 0))),
+            entry(Region::Ac2, at(9, 0), None),
+        ];
+
+        // When
+        let now = at(10, 0);
+        let summary = summarize(
+            &history,
+            &[],
+            &Rounding::default(),
+            at(8, 0),
+            now + TimeDelta::hours(5),
+            now,
+        );
+
+        // Then
+        assert_eq!(summary.work_duration, 7_200_000);
+        assert_eq!(summary.break_duration, 0);
+    }
+
+    #[test]
+    fn test_summarize_rounds_billable_duration_per_entry() {
+        // Given: two entries of 7 minutes and one of 40 minutes
+        let history = vec![
+            entry(Region::Ac1, at(8, 0), Some(at(8, 7))),
+            entry(Region::Ac1, at(9, 0), Some(at(9, 7))),
+            entry(Region::Aa1, at(10, 0), Some(at(10, 40))),
+        ];
+        let regions = vec![RegionRounding {
+            region: Region::Aa1,
+            policy: RoundingPolicy {
+                mode: RoundingMode::Nearest,
+                increment: 6,
+            },
+        }];
+        let rounding = Rounding {
+            default: Some(RoundingPolicy {
+                mode: RoundingMode::Up,
+                increment: 15,
+            }),
+            regions: &regions,
+        };
+
+        // When
+        let summary = summarize(&history, &[], &rounding, at(0, 0), at(23, 0), at(23, 0));
+
+        // Then
+        assert_eq!(summary.regions[0].region, Region::Aa1);
+        assert_eq!(summary.regions[0].duration, 40 * 60_000);
+        assert_eq!(
+            summary.regions[0].billable_duration,
+            42 * 60_000,
+            "The region policy rounds to the nearest 6 minutes"
+        );
+        assert_eq!(summary.regions[1].duration, 14 * 60_000);
+        assert_eq!(
+            summary.regions[1].billable_duration,
+            30 * 60_000,
+            "The default policy rounds every entry up to 15 minutes"
+        );
+        assert_eq!(summary.work_duration, 54 * 60_000);
+        assert_eq!(summary.billable_duration, 72 * 60_000);
+    }
+
+    #[test]
+    fn test_rounding_modes() {
+        let policy = |mode| RoundingPolicy {
+            mode,
+            increment: 15,
+        };
+        let minutes = |minutes: i64| minutes * 60_000;
+
+        assert_eq!(policy(RoundingMode::Up).apply(minutes(16)), minutes(30));
+        assert_eq!(policy(RoundingMode::Up).apply(minutes(15)), minutes(15));
+        assert_eq!(policy(RoundingMode::Up).apply(0), 0);
+        assert_eq!(policy(RoundingMode::Down).apply(minutes(29)), minutes(15));
+        assert_eq!(
+            policy(RoundingMode::Nearest).apply(minutes(22)),
+            minutes(15)
+        );
+        assert_eq!(
+            policy(RoundingMode::Nearest).apply(minutes(22) + 30_000),
+            minutes(30),
+            "Halfway rounds up"
+        );
+    }
+
+    #[test]
+    fn test_rounding_ignores_invalid_increments() {
+        for increment in [0, 1441, i64::MAX] {
+            let policy = RoundingPolicy {
+                mode: RoundingMode::Up,
+                increment,
+            };
+            assert_eq!(policy.apply(60_001), 60_001);
+        }
+    }
+
+    fn schedule(valid_from: NaiveDate, valid_until: Option<NaiveDate>, daily: i64) -> TargetHours {
+        TargetHours {
+            id: 0,
+            user_id: None,
+            valid_from,
+            valid_until,
+            monday: daily,
+            tuesday: daily,
+            wednesday: daily,
+            thursday: daily,
+            friday: daily,
+            saturday: 0,
+            sunday: 0,
+        }
+    }
+
+    #[test]
+    fn test_flextime_balances_days_weeks_and_months() {
+        // Given: Tuesday 2025-09-30 until Friday 2025-10-03
+        let day = |month, day| NaiveDate::from_ymd_opt(2025, month, day).unwrap();
+        let schedules = vec![
+            schedule(day(9, 1), Some(day(9, 30)), 8 * 3600),
+            schedule(day(10, 1), None, 6 * 3600),
+        ];
+        let history = vec![
+            entry(
+                Region::Ac1,
+                start_of_local_day(day(9, 30), &Utc) + TimeDelta::hours(8),
+                Some(start_of_local_day(day(9, 30), &Utc) + TimeDelta::hours(17)),
+            ),
+            entry(Region::Ac1, at(8, 0), Some(at(12, 0))),
+        ];
+
+        // When
+        let calendar = Calendar {
+            schedules: &schedules,
+            holidays: &[],
+            absences: &[],
+        };
+        let result = flextime(&history, &calendar, day(9, 30), day(10, 3), &Utc, at(23, 0));
+
+        // Then
+        assert_eq!(result.days.len(), 4);
+        assert_eq!(result.days[0].balance, Balance::new(9 * 3600, 0, 8 * 3600));
+        assert_eq!(result.days[1].balance, Balance::new(4 * 3600, 0, 6 * 3600));
+        assert_eq!(result.days[2].balance, Balance::new(0, 0, 6 * 3600));
+        assert_eq!(result.weeks.len(), 1);
+        assert_eq!(result.weeks[0].period, "2025-W40");
+        assert_eq!(
+            result.months,
+            vec![
+                PeriodBalance {
+                    period: "2025-09".to_string(),
+                    balance: Balance::new(9 * 3600, 0, 8 * 3600),
+                },
+                PeriodBalance {
+                    period: "2025-10".to_string(),
+                    balance: Balance::new(4 * 3600, 0, 18 * 3600),
+                },
+            ]
+        );
+        assert_eq!(result.overall, Balance::new(13 * 3600, 0, 26 * 3600));
+        assert_eq!(result.overall.balance, -13 * 3600);
+    }
+
+    #[test]
+    fn test_flextime_skips_target_on_holidays() {
+        // Given: Friday 2025-10-03 is the Tag der Deutschen Einheit
+        let day = |month, day| NaiveDate::from_ymd_opt(2025, month, day).unwrap();
+        let schedules = vec![schedule(day(1, 1), None, 8 * 3600)];
+        let holidays = crate::holidays::public_holidays(2025, None);
+
+        // When
+        let calendar = Calendar {
+            schedules: &schedules,
+            holidays: &holidays,
+            absences: &[],
+        };
+        let result = flextime(&[], &calendar, day(10, 2), day(10, 3), &Utc, at(23, 0));
+
+        // Then
+        assert_eq!(result.days[0].balance, Balance::new(0, 0, 8 * 3600));
+        assert_eq!(result.days[1].balance, Balance::new(0, 0, 0));
+    }
+
+    #[test]
+    fn test_flextime_uses_local_days() {
+        // Given: 23:30 UTC on 2025-09-30 is already 01:30 on 2025-10-01 in Berlin
+        let day = |month, day| NaiveDate::from_ymd_opt(2025, month, day).unwrap();
+        let schedules = vec![schedule(day(1, 1), None, 0)];
+        let history = vec![entry(
+            Region::Ac1,
+            at(0, 0) - TimeDelta::minutes(30),
+            Some(at(2, 0)),
+        )];
+        let calendar = Calendar {
+            schedules: &schedules,
+            holidays: &[],
+            absences: &[],
+        };
+
+        // When
+        let in_utc = flextime(&history, &calendar, day(9, 30), day(10, 1), &Utc, at(23, 0));
+        let in_berlin = flextime(
+            &history,
+            &calendar,
+            day(9, 30),
+            day(10, 1),
+            &chrono_tz::Europe::Berlin,
+            at(23, 0),
+        );
+
+        // Then
+        assert_eq!(in_utc.days[0].balance.actual, 1800);
+        assert_eq!(in_utc.days[1].balance.actual, 7200);
+        assert_eq!(in_berlin.days[0].balance.actual, 0);
+        assert_eq!(in_berlin.days[1].balance.actual, 9000);
+    }
+
+    #[test]
+    fn test_flextime_credits_absences() {
+        // Given: vacation on Monday, half a sick day on Tuesday and comp time on
+        // Wednesday
+        let day = |month, day| NaiveDate::from_ymd_opt(2025, month, day).unwrap();
+        let schedules = vec![schedule(day(1, 1), None, 8 * 3600)];
+        let absence = |id, date, kind, half_day| Absence {
+            id,
+            date,
+            kind,
+            half_day,
+        };
+        let absences = vec![
+            absence(1, day(9, 29), AbsenceKind::Vacation, false),
+            absence(2, day(9, 30), AbsenceKind::Sick, true),
+            absence(3, day(10, 1), AbsenceKind::CompTime, false),
+        ];
+        let calendar = Calendar {
+            schedules: &schedules,
+            holidays: &[],
+            absences: &absences,
+        };
+
+        // When
+        let result = flextime(&[], &calendar, day(9, 29), day(10, 1), &Utc, at(23, 0));
+
+        // Then
+        assert_eq!(result.days[0].absence, Some(AbsenceKind::Vacation));
+        assert_eq!(result.days[0].balance, Balance::new(0, 8 * 3600, 8 * 3600));
+        assert_eq!(result.days[1].balance, Balance::new(0, 4 * 3600, 8 * 3600));
+        assert_eq!(result.days[2].balance, Balance::new(0, 0, 8 * 3600));
+        assert_eq!(result.overall.balance, -12 * 3600);
+    }
+
+    #[test]
+    fn test_split_at_day_boundaries() {
+        // Given
+        let start = at(22, 0);
+        let stop = at(22, 0) + TimeDelta::hours(28);
+
+        // When
+        let segments = split_at_day_boundaries(start, stop, &Utc);
+
+        // Then
+        let midnight = start_of_local_day(NaiveDate::from_ymd_opt(2025, 10, 2).unwrap(), &Utc);
+        assert_eq!(
+            segments,
+            vec![
+                (start, midnight),
+                (midnight, midnight + TimeDelta::days(1)),
+                (midnight + TimeDelta::days(1), stop),
+            ]
+        );
+        assert_eq!(split_at_day_boundaries(at(8, 0), at(9, 0), &Utc).len(), 1);
+    }
+
+    #[test]
+    fn test_split_at_day_boundaries_respects_daylight_saving_time() {
+        // Given: The night from 2025-10-25 to 2025-10-26 has 25 hours in Berlin.
+        // Midnight in Berlin is 22:00 UTC before and 23:00 UTC after the switch.
+        let berlin = chrono_tz::Europe::Berlin;
+        let start: DateTime<Utc> = "2025-10-25T20:00:00Z".parse().unwrap();
+        let stop: DateTime<Utc> = "2025-10-27T01:00:00Z".parse().unwrap();
+
+        // When
+        let segments = split_at_day_boundaries(start, stop, &berlin);
+
+        // Then
+        let first_midnight: DateTime<Utc> = "2025-10-25T22:00:00Z".parse().unwrap();
+        let second_midnight: DateTime<Utc> = "2025-10-26T23:00:00Z".parse().unwrap();
+        assert_eq!(
+            segments,
+            vec![
+                (start, first_midnight),
+                (first_midnight, second_midnight),
+                (second_midnight, stop),
+            ]
+        );
+        assert_eq!((second_midnight - first_midnight).num_hours(), 25);
+    }
+
+    #[test]
+    fn test_split_history_by_day_keeps_running_entry_open() {
+        // Given
+        let history = vec![
+            entry(Region::Ac1, at(8, 0), Some(at(9, 0))),
+            entry(Region::Aa1, at(22, 0), None),
+        ];
+        let now = at(22, 0) + TimeDelta::hours(4);
+
+        // When
+        let split = split_history_by_day(history, &Utc, now);
+
+        // Then
+        assert_eq!(split.len(), 3);
+        assert_eq!(split[0].duration, Some(3_600_000));
+        assert_eq!(split[1].region, Region::Aa1);
+        assert_eq!(split[1].duration, Some(7_200_000));
+        assert_eq!(split[2].stop_time, None);
+        assert_eq!(split[2].duration, None);
+    }
+
+    #[test]
+    fn test_vacation_balance_carries_over_remaining_days() {
+        // Given
+        let day = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+        let entitlements = vec![
+            VacationEntitlement {
+                year: 2024,
+                days: 30.0,
+            },
+            VacationEntitlement {
+                year: 2025,
+                days: 28.0,
+            },
+        ];
+        let mut absences: Vec<Absence> = day(2024, 8, 1)
+            .iter_days()
+            .take(27)
+            .enumerate()
+            .map(|(id, date)| Absence {
+                id: id as i64,
+                date,
+                kind: AbsenceKind::Vacation,
+                half_day: false,
+            })
+            .collect();
+        absences.push(Absence {
+            id: 100,
+            date: day(2025, 3, 3),
+            kind: AbsenceKind::Vacation,
+            half_day: true,
+        });
+        absences.push(Absence {
+            id: 101,
+            date: day(2025, 3, 4),
+            kind: AbsenceKind::Sick,
+            half_day: false,
+        });
+
+        // When
+        let balance = vacation_balance(2025, &entitlements, &absences);
+
+        // Then
+        assert_eq!(
+            balance,
+            VacationBalance {
+                year: 2025,
+                entitlement: 28.0,
+                carried_over: 3.0,
+                taken: 0.5,
+                remaining: 30.5,
+            }
+        );
+    }
+}
@@ -0,0 +1,315 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use sqlx::SqlitePool;
+
+use crate::models::absence::Absence;
+use crate::models::absence::AbsenceKind;
+use crate::models::absence::VacationEntitlement;
+use crate::repositories::region_repositories::RepositoryError;
+
+#[async_trait]
+pub trait AbsenceRepository: Send + Sync {
+    /// Records an absence of the user for every date. Either all absences are
+    /// recorded or, if the user is already absent on one of the dates, none.
+    async fn add_absences(
+        &self,
+        user_id: Option<&str>,
+        dates: Vec<NaiveDate>,
+        kind: AbsenceKind,
+        half_day: bool,
+    ) -> Result<Vec<Absence>, RepositoryError>;
+    /// Returns the user's absences within `[from, to]`, ordered by date.
+    async fn get_absences(
+        &self,
+        user_id: Option<&str>,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<Absence>, RepositoryError>;
+    /// Deletes an absence of the user.
+    async fn delete_absence(&self, user_id: Option<&str>, id: i64) -> Result<(), RepositoryError>;
+    /// Sets the user's vacation entitlement of a year, replacing an existing
+    /// one.
+    async fn set_vacation_entitlement(
+        &self,
+        user_id: Option<&str>,
+        entitlement: VacationEntitlement,
+    ) -> Result<(), RepositoryError>;
+    /// Returns all vacation entitlements of the user, ordered by year.
+    async fn get_vacation_entitlements(
+        &self,
+        user_id: Option<&str>,
+    ) -> Result<Vec<VacationEntitlement>, RepositoryError>;
+}
+
+pub struct SqliteAbsenceRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteAbsenceRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AbsenceRepository for SqliteAbsenceRepository {
+    async fn add_absences(
+        &self,
+        user_id: Option<&str>,
+        dates: Vec<NaiveDate>,
+        kind: AbsenceKind,
+        half_day: bool,
+    ) -> Result<Vec<Absence>, RepositoryError> {
+        let mut transaction = self.pool.begin().await?;
+        let mut absences = Vec::with_capacity(dates.len());
+
+        for date in dates {
+            let absence: Absence = sqlx::query_as(
+                r#"
+                INSERT INTO absences (user_id, date, kind, half_day)
+                VALUES ($1, $2, $3, $4)
+                RETURNING id, date, kind, half_day
+                "#,
+            )
+            .bind(user_id)
+            .bind(date)
+            .bind(kind)
+            .bind(half_day)
+            .fetch_one(&mut *transaction)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(ref db_error) if db_error.is_unique_violation() => {
+                    RepositoryError::AlreadyExists
+                }
+                e => e.into(),
+            })?;
+            absences.push(absence);
+        }
+
+        transaction.commit().await?;
+        Ok(absences)
+    }
+
+    async fn get_absences(
+        &self,
+        user_id: Option<&str>,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<Absence>, RepositoryError> {
+        let result: Vec<Absence> = sqlx::query_as(
+            r#"
+            SELECT id, date, kind, half_day
+            FROM absences
+            WHERE user_id IS $1 AND date >= $2 AND date <= $3
+            ORDER BY date ASC
+            "#,
+        )
+        .bind(user_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    async fn delete_absence(&self, user_id: Option<&str>, id: i64) -> Result<(), RepositoryError> {
+        let result = sqlx::query("DELETE FROM absences WHERE id = $1 AND user_id IS $2")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        match result.rows_affected() {
+            0 => Err(RepositoryError::NotFound),
+            _ => Ok(()),
+        }
+    }
+
+    async fn set_vacation_entitlement(
+        &self,
+        user_id: Option<&str>,
+        entitlement: VacationEntitlement,
+    ) -> Result<(), RepositoryError> {
+        sqlx::query(
+            r#"
+            INSERT INTO vacation_entitlements (user_id, year, days)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (COALESCE(user_id, ''), year) DO UPDATE SET days = excluded.days
+            "#,
+        )
+        .bind(user_id)
+        .bind(entitlement.year)
+        .bind(entitlement.days)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_vacation_entitlements(
+        &self,
+        user_id: Option<&str>,
+    ) -> Result<Vec<VacationEntitlement>, RepositoryError> {
+        let result: Vec<VacationEntitlement> = sqlx::query_as(
+            "SELECT year, days FROM vacation_entitlements WHERE user_id IS $1 ORDER BY year ASC",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, month, day).unwrap()
+    }
+
+    #[sqlx::test]
+    async fn test_add_absences(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteAbsenceRepository::new(pool);
+
+        // When
+        let added = repo
+            .add_absences(
+                None,
+                vec![date(8, 4), date(8, 5)],
+                AbsenceKind::Vacation,
+                false,
+            )
+            .await
+            .expect("Adding absences should succeed");
+
+        // Then
+        let absences = repo
+            .get_absences(None, date(8, 1), date(8, 31))
+            .await
+            .unwrap();
+        assert_eq!(absences, added, "The added absences should be stored");
+        assert_eq!(absences.len(), 2, "Two absences should be stored");
+        assert_eq!(absences[0].kind, AbsenceKind::Vacation);
+        assert!(!absences[0].half_day, "Absences should be full days");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_add_absences_is_atomic(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteAbsenceRepository::new(pool);
+        repo.add_absences(None, vec![date(8, 5)], AbsenceKind::Sick, true)
+            .await
+            .unwrap();
+
+        // When
+        let result = repo
+            .add_absences(
+                None,
+                vec![date(8, 4), date(8, 5)],
+                AbsenceKind::Vacation,
+                false,
+            )
+            .await;
+
+        // Then
+        assert!(
+            matches!(result, Err(RepositoryError::AlreadyExists)),
+            "Should fail with AlreadyExists error"
+        );
+        let absences = repo
+            .get_absences(None, date(8, 1), date(8, 31))
+            .await
+            .unwrap();
+        assert_eq!(absences.len(), 1, "No additional absence should be stored");
+        assert_eq!(absences[0].kind, AbsenceKind::Sick);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_users_are_absent_independently(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given: Anna is on vacation
+        let repo = SqliteAbsenceRepository::new(pool);
+        repo.add_absences(Some("anna"), vec![date(8, 4)], AbsenceKind::Vacation, false)
+            .await
+            .unwrap();
+
+        // When
+        let ben = repo
+            .add_absences(Some("ben"), vec![date(8, 4)], AbsenceKind::Sick, false)
+            .await;
+        let anna_again = repo
+            .add_absences(Some("anna"), vec![date(8, 4)], AbsenceKind::Sick, false)
+            .await;
+
+        // Then
+        assert!(ben.is_ok(), "Ben may be absent on the same day");
+        assert!(matches!(anna_again, Err(RepositoryError::AlreadyExists)));
+        let anna = repo
+            .get_absences(Some("anna"), date(8, 1), date(8, 31))
+            .await
+            .unwrap();
+        assert_eq!(anna.len(), 1);
+        assert_eq!(anna[0].kind, AbsenceKind::Vacation);
+        assert!(
+            matches!(
+                repo.delete_absence(Some("ben"), anna[0].id).await,
+                Err(RepositoryError::NotFound)
+            ),
+            "Ben can't delete Anna's absence"
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_set_vacation_entitlement_replaces_existing(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteAbsenceRepository::new(pool);
+        repo.set_vacation_entitlement(
+            None,
+            VacationEntitlement {
+                year: 2025,
+                days: 28.0,
+            },
+        )
+        .await
+        .unwrap();
+
+        // When
+        repo.set_vacation_entitlement(
+            None,
+            VacationEntitlement {
+                year: 2025,
+                days: 30.0,
+            },
+        )
+        .await
+        .expect("Replacing the entitlement should succeed");
+
+        // Then
+        let entitlements = repo.get_vacation_entitlements(None).await.unwrap();
+        assert_eq!(
+            entitlements,
+            vec![VacationEntitlement {
+                year: 2025,
+                days: 30.0,
+            }]
+        );
+        assert!(
+            repo.get_vacation_entitlements(Some("anna"))
+                .await
+                .unwrap()
+                .is_empty(),
+            "Other users have their own entitlements"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,181 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+use crate::models::audit::AuditEntry;
+use crate::repositories::region_repositories::RepositoryError;
+
+/// Reads the audit log, which the database writes on every change to the
+/// history.
+#[async_trait]
+pub trait AuditRepository: Send + Sync {
+    /// Returns all changes to the entry, oldest first.
+    async fn get_entry_changes(&self, entry_id: i64) -> Result<Vec<AuditEntry>, RepositoryError>;
+    /// Returns the user the entry belongs to, `None` for the shared history.
+    /// Purged entries are looked up in their last recorded change.
+    async fn get_entry_owner(&self, entry_id: i64) -> Result<Option<String>, RepositoryError>;
+}
+
+pub struct SqliteAuditRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteAuditRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AuditRepository for SqliteAuditRepository {
+    async fn get_entry_changes(&self, entry_id: i64) -> Result<Vec<AuditEntry>, RepositoryError> {
+        let result: Vec<AuditEntry> = sqlx::query_as(
+            r#"
+            SELECT id, entry_id, operation, actor, device, device_id, old_values, new_values, changed_at
+            FROM audit_log
+            WHERE entry_id = $1
+            ORDER BY id ASC
+            "#,
+        )
+        .bind(entry_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    async fn get_entry_owner(&self, entry_id: i64) -> Result<Option<String>, RepositoryError> {
+        let result: Option<(Option<String>,)> = sqlx::query_as(
+            r#"
+            SELECT user_id
+            FROM (SELECT user_id, 0 AS source
+                  FROM region_history
+                  WHERE id = $1
+                  UNION ALL
+                  SELECT json_extract(COALESCE(new_values, old_values), '$.user_id'), id
+                  FROM audit_log
+                  WHERE entry_id = $1)
+            ORDER BY source = 0 DESC, source DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(entry_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        result
+            .map(|(user_id,)| user_id)
+            .ok_or(RepositoryError::NotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+    use crate::caller::Caller;
+    use crate::models::audit::Operation;
+    use crate::models::region::Region;
+    use crate::repositories::region_repositories::RegionRepository;
+    use crate::repositories::region_repositories::SqliteRegionRepository;
+
+    #[sqlx::test]
+    async fn test_changes_are_recorded_with_caller(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let regions = SqliteRegionRepository::new(pool.clone());
+        let repo = SqliteAuditRepository::new(pool);
+        let caller = Caller {
+            client_id: "tablet".to_string(),
+            user_id: Some("anna".to_string()),
+            device_id: None,
+        };
+        regions.start_timer(Region::Ac1, &caller).await.unwrap();
+        regions.stop_timer(Region::Ac1, &caller).await.unwrap();
+        let entry = regions
+            .get_history_by_region(Some("anna"), Region::Ac1)
+            .await
+            .unwrap();
+
+        // When
+        let changes = repo
+            .get_entry_changes(entry[0].id)
+            .await
+            .expect("Reading the audit log should succeed");
+
+        // Then
+        let operations: Vec<Operation> = changes.iter().map(|change| change.operation).collect();
+        assert_eq!(operations, vec![Operation::Insert, Operation::Update]);
+        assert!(
+            changes.iter().all(|change| {
+                change.actor.as_deref() == Some("anna")
+                    && change.device.as_deref() == Some("tablet")
+            }),
+            "Every change should name the caller"
+        );
+        assert_eq!(
+            changes[1].old_values.as_ref().unwrap()["stop_time"],
+            Value::Null
+        );
+        assert_eq!(
+            changes[1].new_values.as_ref().unwrap()["duration"],
+            entry[0].duration.unwrap()
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_entry_owner(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given: Anna's entry was purged after being deleted
+        let regions = SqliteRegionRepository::new(pool.clone());
+        let repo = SqliteAuditRepository::new(pool.clone());
+        let anna = Caller {
+            user_id: Some("anna".to_string()),
+            ..Caller::default()
+        };
+        regions.start_timer(Region::Ac1, &anna).await.unwrap();
+        regions.stop_timer(Region::Ac1, &anna).await.unwrap();
+        let entry = regions
+            .get_history_by_region(Some("anna"), Region::Ac1)
+            .await
+            .unwrap()
+            .remove(0);
+
+        // When
+        let owner = repo.get_entry_owner(entry.id).await.unwrap();
+        sqlx::query("DELETE FROM region_history")
+            .execute(&pool)
+            .await?;
+        let purged_owner = repo.get_entry_owner(entry.id).await.unwrap();
+        let unknown = repo.get_entry_owner(entry.id + 1).await;
+
+        // Then
+        assert_eq!(owner.as_deref(), Some("anna"));
+        assert_eq!(purged_owner.as_deref(), Some("anna"));
+        assert!(matches!(unknown, Err(RepositoryError::NotFound)));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_audit_log_is_append_only(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let regions = SqliteRegionRepository::new(pool.clone());
+        regions
+            .start_timer(Region::Ac1, &Caller::default())
+            .await
+            .unwrap();
+
+        // When
+        let update = sqlx::query("UPDATE audit_log SET actor = 'someone else'")
+            .execute(&pool)
+            .await;
+        let delete = sqlx::query("DELETE FROM audit_log").execute(&pool).await;
+
+        // Then
+        assert!(update.is_err(), "Changes must not be rewritten");
+        assert!(delete.is_err(), "Changes must not be deleted");
+
+        Ok(())
+    }
+}
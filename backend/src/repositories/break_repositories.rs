@@ -0,0 +1,400 @@
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Utc;
+use sqlx::SqliteConnection;
+use sqlx::SqlitePool;
+
+use crate::caller::Caller;
+use crate::models::action::ActionKind;
+use crate::models::break_history::BreakHistory;
+use crate::models::region::Region;
+use crate::reports::millis_between;
+use crate::repositories::journal_repositories::begin_action;
+use crate::repositories::journal_repositories::end_action;
+use crate::repositories::region_repositories::RepositoryError;
+use crate::repositories::region_repositories::StopPolicy;
+use crate::repositories::region_repositories::begin_write;
+use crate::repositories::region_repositories::insert_entry;
+use crate::repositories::region_repositories::running_entry;
+
+#[async_trait]
+pub trait BreakRepository: Send + Sync {
+    /// Starts a break of the caller. Their running region timer is stopped and
+    /// remembered, so it can be resumed when the break ends.
+    async fn start_break(&self, caller: &Caller) -> Result<(), RepositoryError>;
+    /// Stops the caller's running break, resumes the interrupted region and
+    /// returns the duration of the break.
+    async fn stop_break(&self, caller: &Caller) -> Result<i64, RepositoryError>;
+    /// Returns all breaks of the user that overlap the time range `[from, to)`,
+    /// including a break that is still running.
+    async fn get_breaks_between(
+        &self,
+        user_id: Option<&str>,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<BreakHistory>, RepositoryError>;
+}
+
+pub struct SqliteBreakRepository {
+    pool: SqlitePool,
+    stop_policy: StopPolicy,
+}
+
+impl SqliteBreakRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            pool,
+            stop_policy: StopPolicy::default(),
+        }
+    }
+
+    /// Sets how the timer that a break interrupts is stored.
+    pub fn with_stop_policy(mut self, stop_policy: StopPolicy) -> Self {
+        self.stop_policy = stop_policy;
+        self
+    }
+}
+
+/// Stops the user's running break at `now`. Returns the start time and the
+/// interrupted region of the stopped break, or `None` if no break was running.
+pub(crate) async fn stop_running_break(
+    connection: &mut SqliteConnection,
+    user_id: Option<&str>,
+    now: DateTime<Utc>,
+) -> Result<Option<(DateTime<Utc>, Option<Region>)>, RepositoryError> {
+    let running: Option<(i64, DateTime<Utc>, Option<Region>)> = sqlx::query_as(
+        r#"
+        SELECT id, start_time, interrupted_region
+        FROM break_history
+        WHERE stop_time IS NULL AND user_id IS $1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(&mut *connection)
+    .await?;
+    let Some((id, start_time, interrupted_region)) = running else {
+        return Ok(None);
+    };
+
+    sqlx::query(
+        r#"
+        UPDATE break_history
+        SET stop_time = $1,
+            duration = $2
+        WHERE id = $3
+        "#,
+    )
+    .bind(now)
+    .bind(millis_between(start_time, now))
+    .bind(id)
+    .execute(&mut *connection)
+    .await?;
+
+    Ok(Some((start_time, interrupted_region)))
+}
+
+#[async_trait]
+impl BreakRepository for SqliteBreakRepository {
+    async fn start_break(&self, caller: &Caller) -> Result<(), RepositoryError> {
+        let now = Utc::now();
+        let mut transaction = begin_write(&self.pool).await?;
+        begin_action(&mut transaction, caller, ActionKind::StartBreak, now).await?;
+        let user_id = caller.user_id.as_deref();
+
+        let running_break: Option<(i64,)> = sqlx::query_as(
+            "SELECT id FROM break_history WHERE stop_time IS NULL AND user_id IS $1",
+        )
+        .bind(user_id)
+        .fetch_optional(&mut *transaction)
+        .await?;
+        if running_break.is_some() {
+            return Err(RepositoryError::BreakAlreadyRunning);
+        }
+
+        // Stop the active timer and remember its region
+        let interrupted = running_entry(&mut transaction, user_id, None).await?;
+        if let Some((id, _, start_time)) = interrupted {
+            self.stop_policy
+                .stop(&mut transaction, id, start_time, now)
+                .await?;
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO break_history (start_time, interrupted_region, user_id)
+            VALUES ($1, $2, $3)
+            "#,
+        )
+        .bind(now)
+        .bind(interrupted.map(|(_, region, _)| region))
+        .bind(user_id)
+        .execute(&mut *transaction)
+        .await?;
+
+        end_action(&mut transaction).await?;
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    async fn stop_break(&self, caller: &Caller) -> Result<i64, RepositoryError> {
+        let now = Utc::now();
+        let mut transaction = begin_write(&self.pool).await?;
+        begin_action(&mut transaction, caller, ActionKind::StopBreak, now).await?;
+
+        let user_id = caller.user_id.as_deref();
+        let Some((start_time, interrupted_region)) =
+            stop_running_break(&mut transaction, user_id, now).await?
+        else {
+            return Err(RepositoryError::BreakNotRunning);
+        };
+
+        // Resume work on the region that was interrupted by the break
+        if let Some(region) = interrupted_region {
+            insert_entry(&mut transaction, user_id, &region, now, None).await?;
+        }
+
+        end_action(&mut transaction).await?;
+        transaction.commit().await?;
+        Ok(millis_between(start_time, now))
+    }
+
+    async fn get_breaks_between(
+        &self,
+        user_id: Option<&str>,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<BreakHistory>, RepositoryError> {
+        let result: Vec<BreakHistory> = sqlx::query_as(
+            r#"
+            SELECT start_time, stop_time, duration, interrupted_region
+            FROM break_history
+            WHERE start_time < $2 AND (stop_time IS NULL OR stop_time > $1) AND user_id IS $3
+            ORDER BY start_time ASC
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+
+    use super::*;
+    use crate::configuration::MinDuration;
+    use crate::configuration::ShortEntryAction;
+    use crate::repositories::region_repositories::RegionRepository;
+    use crate::repositories::region_repositories::SqliteRegionRepository;
+
+    fn last_hour() -> (DateTime<Utc>, DateTime<Utc>) {
+        let now = Utc::now();
+        (now - TimeDelta::hours(1), now + TimeDelta::hours(1))
+    }
+
+    #[sqlx::test]
+    async fn test_start_break_stops_running_timer(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let region_repo = SqliteRegionRepository::new(pool.clone());
+        let repo = SqliteBreakRepository::new(pool);
+        region_repo
+            .start_timer(Region::Ac1, &Caller::default())
+            .await
+            .unwrap();
+
+        // When
+        let result = repo.start_break(&Caller::default()).await;
+
+        // Then
+        assert!(result.is_ok(), "Starting a break should succeed");
+
+        let history = region_repo
+            .get_history_by_region(None, Region::Ac1)
+            .await
+            .expect("History should succeed");
+        assert_eq!(history.len(), 1, "History should contain one entry");
+        assert!(history[0].stop_time.is_some(), "Timer should be stopped");
+
+        let (from, to) = last_hour();
+        let breaks = repo.get_breaks_between(None, from, to).await.unwrap();
+        assert_eq!(breaks.len(), 1, "One break should be recorded");
+        assert!(breaks[0].stop_time.is_none(), "Break should be running");
+        assert_eq!(
+            breaks[0].interrupted_region,
+            Some(Region::Ac1),
+            "Break should remember the interrupted region"
+        );
+
+        let active = region_repo.currently_active_timer(None).await.unwrap();
+        assert!(active.region.is_none(), "No region should be active");
+        assert!(active.on_break, "A break should be active");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_start_break_splits_interrupted_entry_at_midnight(
+        pool: SqlitePool,
+    ) -> sqlx::Result<()> {
+        // Given
+        sqlx::query("INSERT INTO region_history (region, start_time) VALUES ('ac1', $1)")
+            .bind(Utc::now() - TimeDelta::days(1))
+            .execute(&pool)
+            .await?;
+        let region_repo = SqliteRegionRepository::new(pool.clone());
+        let repo = SqliteBreakRepository::new(pool).with_stop_policy(StopPolicy {
+            split_on_stop: true,
+            ..StopPolicy::default()
+        });
+
+        // When
+        repo.start_break(&Caller::default()).await.unwrap();
+
+        // Then
+        let history = region_repo
+            .get_history_by_region(None, Region::Ac1)
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 2, "The entry should be split in two days");
+        assert!(history.iter().all(|entry| entry.stop_time.is_some()));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_start_break_discards_short_entry(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let region_repo = SqliteRegionRepository::new(pool.clone());
+        let repo = SqliteBreakRepository::new(pool).with_stop_policy(StopPolicy {
+            min_duration: Some(MinDuration {
+                duration: TimeDelta::seconds(5),
+                action: ShortEntryAction::Discard,
+            }),
+            ..StopPolicy::default()
+        });
+        region_repo
+            .start_timer(Region::Ac1, &Caller::default())
+            .await
+            .unwrap();
+
+        // When
+        repo.start_break(&Caller::default()).await.unwrap();
+
+        // Then
+        let history = region_repo
+            .get_history_by_region(None, Region::Ac1)
+            .await
+            .unwrap();
+        assert!(history.is_empty(), "The short entry should be discarded");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_start_break_while_break_running(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteBreakRepository::new(pool);
+        repo.start_break(&Caller::default()).await.unwrap();
+
+        // When
+        let result = repo.start_break(&Caller::default()).await;
+
+        // Then
+        assert!(
+            matches!(result, Err(RepositoryError::BreakAlreadyRunning)),
+            "Should fail with BreakAlreadyRunning error"
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_stop_break_resumes_interrupted_region(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let region_repo = SqliteRegionRepository::new(pool.clone());
+        let repo = SqliteBreakRepository::new(pool);
+        region_repo
+            .start_timer(Region::Aa2, &Caller::default())
+            .await
+            .unwrap();
+        repo.start_break(&Caller::default()).await.unwrap();
+
+        // When
+        let duration = repo
+            .stop_break(&Caller::default())
+            .await
+            .expect("Stopping the break should succeed");
+
+        // Then
+        assert!(duration >= 0, "Duration should not be negative");
+
+        let (from, to) = last_hour();
+        let breaks = repo.get_breaks_between(None, from, to).await.unwrap();
+        assert_eq!(breaks.len(), 1, "One break should be recorded");
+        assert!(breaks[0].stop_time.is_some(), "Break should be stopped");
+
+        let active = region_repo.currently_active_timer(None).await.unwrap();
+        assert_eq!(active.region, Some(Region::Aa2), "Aa2 should be resumed");
+        assert!(!active.on_break, "No break should be active");
+
+        let history = region_repo
+            .get_history_by_region(None, Region::Aa2)
+            .await
+            .unwrap();
+        assert_eq!(
+            history.len(),
+            2,
+            "Aa2 history should contain the entries before and after the break"
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_stop_break_not_running(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteBreakRepository::new(pool);
+
+        // When
+        let result = repo.stop_break(&Caller::default()).await;
+
+        // Then
+        assert!(
+            matches!(result, Err(RepositoryError::BreakNotRunning)),
+            "Should fail with BreakNotRunning error"
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_start_timer_ends_running_break(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let region_repo = SqliteRegionRepository::new(pool.clone());
+        let repo = SqliteBreakRepository::new(pool);
+        repo.start_break(&Caller::default()).await.unwrap();
+
+        // When
+        region_repo
+            .start_timer(Region::Ac2, &Caller::default())
+            .await
+            .unwrap();
+
+        // Then
+        let (from, to) = last_hour();
+        let breaks = repo.get_breaks_between(None, from, to).await.unwrap();
+        assert_eq!(breaks.len(), 1, "One break should be recorded");
+        assert!(breaks[0].stop_time.is_some(), "Break should be stopped");
+
+        let active = region_repo.currently_active_timer(None).await.unwrap();
+        assert_eq!(active.region, Some(Region::Ac2), "Ac2 should be active");
+        assert!(!active.on_break, "No break should be active");
+
+        Ok(())
+    }
+}
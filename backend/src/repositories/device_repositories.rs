@@ -0,0 +1,199 @@
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+use crate::models::device::Device;
+use crate::models::device::DeviceKind;
+use crate::repositories::region_repositories::RepositoryError;
+
+#[async_trait]
+pub trait DeviceRepository: Send + Sync {
+    async fn register_device(
+        &self,
+        name: &str,
+        kind: DeviceKind,
+        user_id: Option<&str>,
+        token_hash: &str,
+    ) -> Result<Device, RepositoryError>;
+    /// Returns the id of the device with the token and the user it belongs to,
+    /// and updates when it was last seen. Fails for unknown and revoked
+    /// devices.
+    async fn touch_device(
+        &self,
+        token_hash: &str,
+        now: DateTime<Utc>,
+    ) -> Result<(i64, Option<String>), RepositoryError>;
+    /// Returns all devices, including revoked ones, in the order they were
+    /// registered.
+    async fn get_device(&self, id: i64) -> Result<Device, RepositoryError>;
+    async fn get_devices(&self) -> Result<Vec<Device>, RepositoryError>;
+    async fn revoke_device(&self, id: i64) -> Result<Device, RepositoryError>;
+}
+
+pub struct SqliteDeviceRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteDeviceRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl DeviceRepository for SqliteDeviceRepository {
+    async fn register_device(
+        &self,
+        name: &str,
+        kind: DeviceKind,
+        user_id: Option<&str>,
+        token_hash: &str,
+    ) -> Result<Device, RepositoryError> {
+        let result: Device = sqlx::query_as(
+            r#"
+            INSERT INTO devices (name, kind, user_id, token_hash, registered_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, name, kind, user_id, registered_at, last_seen_at, revoked_at
+            "#,
+        )
+        .bind(name)
+        .bind(kind)
+        .bind(user_id)
+        .bind(token_hash)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    async fn touch_device(
+        &self,
+        token_hash: &str,
+        now: DateTime<Utc>,
+    ) -> Result<(i64, Option<String>), RepositoryError> {
+        let result: Option<(i64, Option<String>)> = sqlx::query_as(
+            r#"
+            UPDATE devices
+            SET last_seen_at = $1
+            WHERE token_hash = $2 AND revoked_at IS NULL
+            RETURNING id, user_id
+            "#,
+        )
+        .bind(now)
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        result.ok_or(RepositoryError::UnknownDevice)
+    }
+
+    async fn get_device(&self, id: i64) -> Result<Device, RepositoryError> {
+        let result: Option<Device> = sqlx::query_as(
+            r#"
+            SELECT id, name, kind, user_id, registered_at, last_seen_at, revoked_at
+            FROM devices
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        result.ok_or(RepositoryError::NotFound)
+    }
+
+    async fn get_devices(&self) -> Result<Vec<Device>, RepositoryError> {
+        let result: Vec<Device> = sqlx::query_as(
+            r#"
+            SELECT id, name, kind, user_id, registered_at, last_seen_at, revoked_at
+            FROM devices
+            ORDER BY id ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    async fn revoke_device(&self, id: i64) -> Result<Device, RepositoryError> {
+        let result: Option<Device> = sqlx::query_as(
+            r#"
+            UPDATE devices
+            SET revoked_at = $1
+            WHERE id = $2 AND revoked_at IS NULL
+            RETURNING id, name, kind, user_id, registered_at, last_seen_at, revoked_at
+            "#,
+        )
+        .bind(Utc::now())
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        result.ok_or(RepositoryError::NotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test]
+    async fn test_revoked_device_is_unknown(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteDeviceRepository::new(pool);
+        let device = repo
+            .register_device("Entrance", DeviceKind::Tablet, None, "hash-1")
+            .await
+            .unwrap();
+        let now = Utc::now();
+        assert_eq!(
+            repo.touch_device("hash-1", now).await.unwrap(),
+            (device.id, None)
+        );
+
+        // When
+        let revoked = repo
+            .revoke_device(device.id)
+            .await
+            .expect("Revoking should succeed");
+
+        // Then
+        assert_eq!(revoked.last_seen_at, Some(now));
+        assert!(revoked.revoked_at.is_some());
+        assert!(matches!(
+            repo.touch_device("hash-1", Utc::now()).await,
+            Err(RepositoryError::UnknownDevice)
+        ));
+        assert!(matches!(
+            repo.revoke_device(device.id).await,
+            Err(RepositoryError::NotFound)
+        ));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_personal_device_identifies_user(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteDeviceRepository::new(pool);
+        let device = repo
+            .register_device("Anna's phone", DeviceKind::Phone, Some("anna"), "hash-1")
+            .await
+            .unwrap();
+
+        // When
+        let identity = repo
+            .touch_device("hash-1", Utc::now())
+            .await
+            .expect("The device should be known");
+
+        // Then
+        assert_eq!(identity, (device.id, Some("anna".to_string())));
+        assert_eq!(device.user_id.as_deref(), Some("anna"));
+
+        Ok(())
+    }
+}
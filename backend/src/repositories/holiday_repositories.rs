@@ -0,0 +1,160 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use sqlx::SqlitePool;
+
+use crate::models::custom_holiday::CustomHoliday;
+use crate::models::custom_holiday::NewCustomHoliday;
+use crate::repositories::region_repositories::RepositoryError;
+
+#[async_trait]
+pub trait HolidayRepository: Send + Sync {
+    async fn add_custom_holiday(
+        &self,
+        holiday: NewCustomHoliday,
+    ) -> Result<CustomHoliday, RepositoryError>;
+    /// Returns the custom holidays within `[from, to]`, ordered by date.
+    async fn get_custom_holidays(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<CustomHoliday>, RepositoryError>;
+    async fn delete_custom_holiday(&self, id: i64) -> Result<(), RepositoryError>;
+}
+
+pub struct SqliteHolidayRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteHolidayRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl HolidayRepository for SqliteHolidayRepository {
+    async fn add_custom_holiday(
+        &self,
+        holiday: NewCustomHoliday,
+    ) -> Result<CustomHoliday, RepositoryError> {
+        let result: CustomHoliday = sqlx::query_as(
+            r#"
+            INSERT INTO custom_holidays (date, name)
+            VALUES ($1, $2)
+            RETURNING id, date, name
+            "#,
+        )
+        .bind(holiday.date)
+        .bind(holiday.name)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db_error) if db_error.is_unique_violation() => {
+                RepositoryError::AlreadyExists
+            }
+            e => RepositoryError::DatabaseError(e),
+        })?;
+
+        Ok(result)
+    }
+
+    async fn get_custom_holidays(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<CustomHoliday>, RepositoryError> {
+        let result: Vec<CustomHoliday> = sqlx::query_as(
+            r#"
+            SELECT id, date, name
+            FROM custom_holidays
+            WHERE date >= $1 AND date <= $2
+            ORDER BY date ASC
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    async fn delete_custom_holiday(&self, id: i64) -> Result<(), RepositoryError> {
+        let result = sqlx::query("DELETE FROM custom_holidays WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        match result.rows_affected() {
+            0 => Err(RepositoryError::NotFound),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, month, day).unwrap()
+    }
+
+    fn new_holiday(date: NaiveDate, name: &str) -> NewCustomHoliday {
+        NewCustomHoliday {
+            date,
+            name: name.to_string(),
+        }
+    }
+
+    #[sqlx::test]
+    async fn test_add_and_get_custom_holidays(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteHolidayRepository::new(pool);
+        repo.add_custom_holiday(new_holiday(date(12, 24), "Heiligabend"))
+            .await
+            .expect("Adding a holiday should succeed");
+        repo.add_custom_holiday(new_holiday(date(6, 13), "Betriebsausflug"))
+            .await
+            .expect("Adding a holiday should succeed");
+
+        // When
+        let holidays = repo
+            .get_custom_holidays(date(1, 1), date(12, 31))
+            .await
+            .expect("Fetching holidays should succeed");
+        let december = repo
+            .get_custom_holidays(date(12, 1), date(12, 31))
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(holidays.len(), 2, "Both holidays should be returned");
+        assert_eq!(holidays[0].name, "Betriebsausflug", "Ordered by date");
+        assert_eq!(december.len(), 1, "Only holidays in range are returned");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_add_custom_holiday_twice(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteHolidayRepository::new(pool);
+        repo.add_custom_holiday(new_holiday(date(12, 24), "Heiligabend"))
+            .await
+            .unwrap();
+
+        // When
+        let result = repo
+            .add_custom_holiday(new_holiday(date(12, 24), "Heiligabend"))
+            .await;
+
+        // Then
+        assert!(
+            matches!(result, Err(RepositoryError::AlreadyExists)),
+            "Should fail with AlreadyExists error"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,342 @@
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+use crate::models::idempotency::StoredResponse;
+use crate::repositories::region_repositories::RepositoryError;
+use crate::repositories::region_repositories::begin_write;
+
+#[async_trait]
+pub trait IdempotencyRepository: Send + Sync {
+    /// Reserves the key of a request that is about to run. Returns `None` if
+    /// the key was reserved, or the request that already uses the key.
+    ///
+    /// Keys created before `created_after` are expired, and keys of requests
+    /// that are still running since before `pending_after` were abandoned, so
+    /// both are reserved anew.
+    async fn reserve_key(
+        &self,
+        request: &StoredResponse,
+        created_after: DateTime<Utc>,
+        pending_after: DateTime<Utc>,
+    ) -> Result<Option<StoredResponse>, RepositoryError>;
+    /// Stores the response of a reserved key.
+    async fn store_response(
+        &self,
+        request: &StoredResponse,
+        status: u16,
+        content_type: Option<String>,
+        body: Vec<u8>,
+    ) -> Result<(), RepositoryError>;
+    /// Releases a reserved key without a response, so the request can be
+    /// retried.
+    async fn release_key(&self, request: &StoredResponse) -> Result<(), RepositoryError>;
+    /// Deletes all keys created before `created_before` and returns how many
+    /// were deleted.
+    async fn purge_responses(&self, created_before: DateTime<Utc>) -> Result<u64, RepositoryError>;
+}
+
+pub struct SqliteIdempotencyRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteIdempotencyRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl IdempotencyRepository for SqliteIdempotencyRepository {
+    async fn reserve_key(
+        &self,
+        request: &StoredResponse,
+        created_after: DateTime<Utc>,
+        pending_after: DateTime<Utc>,
+    ) -> Result<Option<StoredResponse>, RepositoryError> {
+        let mut transaction = begin_write(&self.pool).await?;
+        let reserved = sqlx::query(
+            r#"
+            INSERT INTO idempotency_keys (client_id, user_id, device_id, key, method, path,
+                                          request_hash, status, content_type, body, created_at)
+            VALUES ($1, $9, $10, $2, $3, $4, $5, NULL, NULL, x'', $6)
+            ON CONFLICT (client_id, COALESCE(user_id, ''), COALESCE(device_id, 0), key) DO UPDATE
+            SET method       = excluded.method,
+                path         = excluded.path,
+                request_hash = excluded.request_hash,
+                status       = NULL,
+                content_type = NULL,
+                body         = x'',
+                created_at   = excluded.created_at
+            WHERE idempotency_keys.created_at <= $7
+               OR (idempotency_keys.status IS NULL AND idempotency_keys.created_at <= $8)
+            "#,
+        )
+        .bind(&request.client_id)
+        .bind(&request.key)
+        .bind(&request.method)
+        .bind(&request.path)
+        .bind(&request.request_hash)
+        .bind(request.created_at)
+        .bind(created_after)
+        .bind(pending_after)
+        .bind(&request.user_id)
+        .bind(request.device_id)
+        .execute(&mut *transaction)
+        .await?;
+        if reserved.rows_affected() > 0 {
+            transaction.commit().await?;
+            return Ok(None);
+        }
+
+        let existing: StoredResponse = sqlx::query_as(
+            r#"
+            SELECT client_id, user_id, device_id, key, method, path, request_hash, status,
+                   content_type, body, created_at
+            FROM idempotency_keys
+            WHERE client_id = $1 AND user_id IS $2 AND device_id IS $3 AND key = $4
+            "#,
+        )
+        .bind(&request.client_id)
+        .bind(&request.user_id)
+        .bind(request.device_id)
+        .bind(&request.key)
+        .fetch_one(&mut *transaction)
+        .await?;
+
+        Ok(Some(existing))
+    }
+
+    async fn store_response(
+        &self,
+        request: &StoredResponse,
+        status: u16,
+        content_type: Option<String>,
+        body: Vec<u8>,
+    ) -> Result<(), RepositoryError> {
+        sqlx::query(
+            r#"
+            UPDATE idempotency_keys
+            SET status       = $1,
+                content_type = $2,
+                body         = $3
+            WHERE client_id = $4 AND user_id IS $5 AND device_id IS $6 AND key = $7
+            "#,
+        )
+        .bind(status)
+        .bind(content_type)
+        .bind(body)
+        .bind(&request.client_id)
+        .bind(&request.user_id)
+        .bind(request.device_id)
+        .bind(&request.key)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn release_key(&self, request: &StoredResponse) -> Result<(), RepositoryError> {
+        sqlx::query(
+            r#"
+            DELETE FROM idempotency_keys
+            WHERE client_id = $1 AND user_id IS $2 AND device_id IS $3 AND key = $4
+            "#,
+        )
+        .bind(&request.client_id)
+        .bind(&request.user_id)
+        .bind(request.device_id)
+        .bind(&request.key)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn purge_responses(&self, created_before: DateTime<Utc>) -> Result<u64, RepositoryError> {
+        let result = sqlx::query("DELETE FROM idempotency_keys WHERE created_at <= $1")
+            .bind(created_before)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+
+    use super::*;
+
+    fn request(key: &str, created_at: DateTime<Utc>) -> StoredResponse {
+        StoredResponse {
+            client_id: "tablet".to_string(),
+            user_id: None,
+            device_id: None,
+            key: key.to_string(),
+            method: "POST".to_string(),
+            path: "/api/ac1/start".to_string(),
+            request_hash: "hash".to_string(),
+            status: None,
+            content_type: None,
+            body: Vec::new(),
+            created_at,
+        }
+    }
+
+    #[sqlx::test]
+    async fn test_reserve_key_and_store_response(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteIdempotencyRepository::new(pool);
+        let now = Utc::now();
+        let an_hour_ago = now - TimeDelta::hours(1);
+        let reserved = repo
+            .reserve_key(&request("first", now), an_hour_ago, an_hour_ago)
+            .await
+            .unwrap();
+        assert_eq!(reserved, None, "A new key should be reserved");
+
+        // When: a concurrent retry arrives
+        let pending = repo
+            .reserve_key(&request("first", now), an_hour_ago, an_hour_ago)
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(
+            pending.map(|pending| pending.status),
+            Some(None),
+            "The retry should find the running request"
+        );
+
+        // When: the request finished
+        repo.store_response(&request("first", now), 200, None, b"done".to_vec())
+            .await
+            .unwrap();
+        let stored = repo
+            .reserve_key(&request("first", now), an_hour_ago, an_hour_ago)
+            .await
+            .unwrap()
+            .expect("The response should be stored");
+
+        // Then
+        assert_eq!(stored.status, Some(200));
+        assert_eq!(stored.body, b"done");
+
+        // When: another client uses the same key
+        let other_client = repo
+            .reserve_key(
+                &StoredResponse {
+                    client_id: "phone".to_string(),
+                    ..request("first", now)
+                },
+                an_hour_ago,
+                an_hour_ago,
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(other_client, None, "Keys should be scoped by client");
+
+        // When: another user of the client uses the same key
+        let other_user = repo
+            .reserve_key(
+                &StoredResponse {
+                    user_id: Some("anna".to_string()),
+                    ..request("first", now)
+                },
+                an_hour_ago,
+                an_hour_ago,
+            )
+            .await
+            .unwrap();
+        let other_device = repo
+            .reserve_key(
+                &StoredResponse {
+                    device_id: Some(1),
+                    ..request("first", now)
+                },
+                an_hour_ago,
+                an_hour_ago,
+            )
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(other_user, None, "Keys should be scoped by user");
+        assert_eq!(other_device, None, "Keys should be scoped by device");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_reserve_expired_and_released_keys(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteIdempotencyRepository::new(pool);
+        let now = Utc::now();
+        let yesterday = now - TimeDelta::days(1);
+        repo.reserve_key(&request("old", yesterday), yesterday, yesterday)
+            .await
+            .unwrap();
+        repo.store_response(&request("old", yesterday), 200, None, Vec::new())
+            .await
+            .unwrap();
+        repo.reserve_key(&request("failed", now), yesterday, yesterday)
+            .await
+            .unwrap();
+
+        // When
+        repo.release_key(&request("failed", now)).await.unwrap();
+        let expired = repo
+            .reserve_key(&request("old", now), now - TimeDelta::hours(1), now)
+            .await
+            .unwrap();
+        let released = repo
+            .reserve_key(&request("failed", now), yesterday, yesterday)
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(expired, None, "An expired key should be reserved anew");
+        assert_eq!(released, None, "A released key should be reserved anew");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_purge_responses(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteIdempotencyRepository::new(pool);
+        let now = Utc::now();
+        let long_ago = now - TimeDelta::days(3);
+        repo.reserve_key(
+            &request("old", now - TimeDelta::days(2)),
+            long_ago,
+            long_ago,
+        )
+        .await
+        .unwrap();
+        repo.reserve_key(&request("new", now), long_ago, long_ago)
+            .await
+            .unwrap();
+
+        // When
+        let purged = repo
+            .purge_responses(now - TimeDelta::days(1))
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(purged, 1, "Only the old key should be purged");
+        let remaining = repo
+            .reserve_key(&request("new", now), long_ago, long_ago)
+            .await
+            .unwrap();
+        assert!(remaining.is_some(), "The new key should be kept");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,466 @@
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Utc;
+use sqlx::SqliteConnection;
+use sqlx::SqlitePool;
+
+use crate::caller::Caller;
+use crate::models::action::Action;
+use crate::models::action::ActionKind;
+use crate::repositories::region_repositories::RepositoryError;
+use crate::repositories::region_repositories::begin_write;
+
+/// Starts recording the changes to the history as an action of the caller.
+/// Must be called within the transaction that performs the action and be
+/// followed by [`end_action`].
+pub(crate) async fn begin_action(
+    connection: &mut SqliteConnection,
+    caller: &Caller,
+    kind: ActionKind,
+    now: DateTime<Utc>,
+) -> Result<(), RepositoryError> {
+    sqlx::query(
+        r#"
+        INSERT INTO actions (client_id, user_id, device_id, kind, performed_at, recording)
+        VALUES ($1, $2, $3, $4, $5, TRUE)
+        "#,
+    )
+    .bind(&caller.client_id)
+    .bind(&caller.user_id)
+    .bind(caller.device_id)
+    .bind(kind)
+    .bind(now)
+    .execute(&mut *connection)
+    .await?;
+
+    Ok(())
+}
+
+/// Stops recording the current action. An action that changed nothing is
+/// dropped, so it cannot be undone in place of an earlier one.
+pub(crate) async fn end_action(connection: &mut SqliteConnection) -> Result<(), RepositoryError> {
+    sqlx::query(
+        r#"
+        DELETE FROM actions
+        WHERE recording
+          AND NOT EXISTS (SELECT 1 FROM action_changes WHERE action_id = actions.id)
+        "#,
+    )
+    .execute(&mut *connection)
+    .await?;
+    sqlx::query("UPDATE actions SET recording = FALSE WHERE recording")
+        .execute(&mut *connection)
+        .await?;
+
+    Ok(())
+}
+
+#[async_trait]
+pub trait JournalRepository: Send + Sync {
+    /// Reverts the most recent action of the caller's client, user and device
+    /// that was performed after `performed_after` and was not undone yet,
+    /// restoring the rows it changed. Fails if the rows were changed again
+    /// since.
+    async fn undo_last_action(
+        &self,
+        caller: &Caller,
+        performed_after: DateTime<Utc>,
+    ) -> Result<Action, RepositoryError>;
+    /// Deletes all actions performed before `performed_before` and returns how
+    /// many were deleted.
+    async fn purge_actions(&self, performed_before: DateTime<Utc>) -> Result<u64, RepositoryError>;
+}
+
+pub struct SqliteJournalRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteJournalRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+/// A row that an action changed, as JSON before and after the change.
+#[derive(sqlx::FromRow)]
+struct Change {
+    table_name: String,
+    row_id: i64,
+    before: Option<String>,
+    after: Option<String>,
+}
+
+/// Returns the current row of a journaled table as JSON, in the same shape as
+/// the journal triggers record it.
+async fn current_row(
+    connection: &mut SqliteConnection,
+    table_name: &str,
+    row_id: i64,
+) -> Result<Option<String>, RepositoryError> {
+    let query = match table_name {
+        "region_history" => {
+            r#"
+            SELECT json_object('id', id, 'region', region, 'start_time', start_time,
+                               'stop_time', stop_time, 'duration', duration,
+                               'auto_stopped', auto_stopped, 'deleted_at', deleted_at,
+                               'user_id', user_id)
+            FROM region_history
+            WHERE id = $1
+            "#
+        }
+        _ => {
+            r#"
+            SELECT json_object('id', id, 'start_time', start_time, 'stop_time', stop_time,
+                               'duration', duration, 'interrupted_region', interrupted_region,
+                               'user_id', user_id)
+            FROM break_history
+            WHERE id = $1
+            "#
+        }
+    };
+    let row: Option<(String,)> = sqlx::query_as(query)
+        .bind(row_id)
+        .fetch_optional(&mut *connection)
+        .await?;
+
+    Ok(row.map(|(row,)| row))
+}
+
+/// Reverts a single change by replacing the row with its previous state.
+async fn revert_change(
+    connection: &mut SqliteConnection,
+    change: &Change,
+) -> Result<(), RepositoryError> {
+    let (delete, restore) = match change.table_name.as_str() {
+        "region_history" => (
+            "DELETE FROM region_history WHERE id = $1",
+            r#"
+            INSERT INTO region_history (id, region, start_time, stop_time, duration, auto_stopped,
+                                        deleted_at, user_id)
+            SELECT json_extract($1, '$.id'), json_extract($1, '$.region'),
+                   json_extract($1, '$.start_time'), json_extract($1, '$.stop_time'),
+                   json_extract($1, '$.duration'), json_extract($1, '$.auto_stopped'),
+                   json_extract($1, '$.deleted_at'), json_extract($1, '$.user_id')
+            "#,
+        ),
+        _ => (
+            "DELETE FROM break_history WHERE id = $1",
+            r#"
+            INSERT INTO break_history (id, start_time, stop_time, duration, interrupted_region,
+                                       user_id)
+            SELECT json_extract($1, '$.id'), json_extract($1, '$.start_time'),
+                   json_extract($1, '$.stop_time'), json_extract($1, '$.duration'),
+                   json_extract($1, '$.interrupted_region'), json_extract($1, '$.user_id')
+            "#,
+        ),
+    };
+
+    sqlx::query(delete)
+        .bind(change.row_id)
+        .execute(&mut *connection)
+        .await?;
+    if let Some(before) = &change.before {
+        sqlx::query(restore)
+            .bind(before)
+            .execute(&mut *connection)
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[async_trait]
+impl JournalRepository for SqliteJournalRepository {
+    async fn undo_last_action(
+        &self,
+        caller: &Caller,
+        performed_after: DateTime<Utc>,
+    ) -> Result<Action, RepositoryError> {
+        let mut transaction = begin_write(&self.pool).await?;
+
+        let action: Action = sqlx::query_as(
+            r#"
+            SELECT id, client_id, user_id, kind, performed_at
+            FROM actions
+            WHERE client_id = $1
+              AND user_id IS $3
+              AND ($4 IS NULL OR device_id = $4)
+              AND NOT undone
+              AND performed_at > $2
+              AND kind != 'undo'
+            ORDER BY id DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(&caller.client_id)
+        .bind(performed_after)
+        .bind(&caller.user_id)
+        .bind(caller.device_id)
+        .fetch_optional(&mut *transaction)
+        .await?
+        .ok_or(RepositoryError::NotFound)?;
+
+        let changes: Vec<Change> = sqlx::query_as(
+            r#"
+            SELECT table_name, row_id, before, after
+            FROM action_changes
+            WHERE action_id = $1
+            ORDER BY id ASC
+            "#,
+        )
+        .bind(action.id)
+        .fetch_all(&mut *transaction)
+        .await?;
+
+        // Every row must still be in the state the action left it in, the
+        // last change of a row holds that state
+        for (index, change) in changes.iter().enumerate() {
+            let is_last_change = !changes[index + 1..].iter().any(|later| {
+                later.table_name == change.table_name && later.row_id == change.row_id
+            });
+            if is_last_change
+                && current_row(&mut transaction, &change.table_name, change.row_id).await?
+                    != change.after
+            {
+                return Err(RepositoryError::ChangedSince);
+            }
+        }
+
+        // The revert is recorded as well, so the audit log shows who undid it
+        begin_action(&mut transaction, caller, ActionKind::Undo, Utc::now()).await?;
+        for change in changes.iter().rev() {
+            revert_change(&mut transaction, change).await?;
+        }
+        end_action(&mut transaction).await?;
+
+        sqlx::query("UPDATE actions SET undone = TRUE WHERE id = $1")
+            .bind(action.id)
+            .execute(&mut *transaction)
+            .await?;
+
+        transaction.commit().await?;
+        Ok(action)
+    }
+
+    async fn purge_actions(&self, performed_before: DateTime<Utc>) -> Result<u64, RepositoryError> {
+        let result = sqlx::query("DELETE FROM actions WHERE performed_at < $1")
+            .bind(performed_before)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::region::Region;
+    use crate::models::region_history::RegionHistory;
+    use crate::repositories::region_repositories::RegionRepository;
+    use crate::repositories::region_repositories::SqliteRegionRepository;
+
+    fn caller(client_id: &str) -> Caller {
+        Caller {
+            client_id: client_id.to_string(),
+            user_id: None,
+            device_id: None,
+        }
+    }
+
+    async fn history(pool: &SqlitePool) -> Vec<RegionHistory> {
+        sqlx::query_as(
+            r#"
+            SELECT id, region, start_time, stop_time, duration, auto_stopped
+            FROM region_history
+            ORDER BY id ASC
+            "#,
+        )
+        .fetch_all(pool)
+        .await
+        .unwrap()
+    }
+
+    #[sqlx::test]
+    async fn test_undo_restores_previous_state(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given: the tablet started Ac1 and then Ac2 by accident
+        let regions = SqliteRegionRepository::new(pool.clone());
+        let repo = SqliteJournalRepository::new(pool.clone());
+        let tablet = caller("tablet");
+        regions.start_timer(Region::Ac1, &tablet).await.unwrap();
+        let before = history(&pool).await;
+        regions.start_timer(Region::Ac2, &tablet).await.unwrap();
+
+        // When
+        let action = repo
+            .undo_last_action(&caller("tablet"), DateTime::<Utc>::MIN_UTC)
+            .await
+            .expect("Undoing should succeed");
+
+        // Then
+        assert_eq!(action.kind, ActionKind::StartTimer);
+        let after: Vec<_> = history(&pool)
+            .await
+            .into_iter()
+            .map(|entry| (entry.id, entry.region, entry.start_time, entry.stop_time))
+            .collect();
+        let before: Vec<_> = before
+            .into_iter()
+            .map(|entry| (entry.id, entry.region, entry.start_time, entry.stop_time))
+            .collect();
+        assert_eq!(after, before, "Ac1 should run again as before");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_undo_only_reverts_actions_of_the_caller(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let regions = SqliteRegionRepository::new(pool.clone());
+        let repo = SqliteJournalRepository::new(pool.clone());
+        regions
+            .start_timer(Region::Ac1, &caller("tablet"))
+            .await
+            .unwrap();
+
+        // When
+        let result = repo
+            .undo_last_action(&caller("phone"), DateTime::<Utc>::MIN_UTC)
+            .await;
+
+        // Then
+        assert!(
+            matches!(result, Err(RepositoryError::NotFound)),
+            "The phone has no action to undo"
+        );
+        assert_eq!(history(&pool).await.len(), 1);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_undo_only_reverts_actions_of_the_user(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given: Anna and Ben share the kiosk, Ben started a timer after Anna
+        let regions = SqliteRegionRepository::new(pool.clone());
+        let repo = SqliteJournalRepository::new(pool.clone());
+        let user = |user_id: &str| Caller {
+            user_id: Some(user_id.to_string()),
+            ..caller("kiosk")
+        };
+        regions
+            .start_timer(Region::Ac1, &user("anna"))
+            .await
+            .unwrap();
+        regions
+            .start_timer(Region::Ac2, &user("ben"))
+            .await
+            .unwrap();
+
+        // When
+        let anna = repo
+            .undo_last_action(&user("anna"), DateTime::<Utc>::MIN_UTC)
+            .await
+            .expect("Undoing should succeed");
+        let ben = repo
+            .undo_last_action(&user("ben"), DateTime::<Utc>::MIN_UTC)
+            .await
+            .expect("Undoing should succeed");
+        let again = repo
+            .undo_last_action(&user("ben"), DateTime::<Utc>::MIN_UTC)
+            .await;
+
+        // Then
+        assert_eq!(anna.user_id.as_deref(), Some("anna"));
+        assert_eq!(ben.user_id.as_deref(), Some("ben"));
+        assert!(
+            matches!(again, Err(RepositoryError::NotFound)),
+            "Ben can't undo Anna's action"
+        );
+        assert!(history(&pool).await.is_empty());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_undo_fails_when_entries_changed_since(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given: the phone stopped the timer that the tablet started
+        let regions = SqliteRegionRepository::new(pool.clone());
+        let repo = SqliteJournalRepository::new(pool.clone());
+        regions
+            .start_timer(Region::Ac1, &caller("tablet"))
+            .await
+            .unwrap();
+        regions
+            .stop_timer(Region::Ac1, &caller("phone"))
+            .await
+            .unwrap();
+
+        // When
+        let result = repo
+            .undo_last_action(&caller("tablet"), DateTime::<Utc>::MIN_UTC)
+            .await;
+
+        // Then
+        assert!(
+            matches!(result, Err(RepositoryError::ChangedSince)),
+            "The stopped entry should not be removed"
+        );
+        assert_eq!(history(&pool).await.len(), 1);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_undo_ignores_actions_outside_window(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let regions = SqliteRegionRepository::new(pool.clone());
+        let repo = SqliteJournalRepository::new(pool.clone());
+        regions
+            .start_timer(Region::Ac1, &caller("tablet"))
+            .await
+            .unwrap();
+
+        // When
+        let result = repo.undo_last_action(&caller("tablet"), Utc::now()).await;
+
+        // Then
+        assert!(
+            matches!(result, Err(RepositoryError::NotFound)),
+            "The action is too old to be undone"
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_undo_delete_restores_entry(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let regions = SqliteRegionRepository::new(pool.clone());
+        let repo = SqliteJournalRepository::new(pool.clone());
+        let tablet = caller("tablet");
+        regions.start_timer(Region::Ac1, &tablet).await.unwrap();
+        regions.stop_timer(Region::Ac1, &tablet).await.unwrap();
+        let entry = history(&pool).await.remove(0);
+        regions.delete_entry(entry.id, &tablet).await.unwrap();
+
+        // When
+        let action = repo
+            .undo_last_action(&tablet, DateTime::<Utc>::MIN_UTC)
+            .await
+            .expect("Undoing should succeed");
+
+        // Then
+        assert_eq!(action.kind, ActionKind::DeleteEntry);
+        assert_eq!(
+            regions
+                .get_history_by_region(None, Region::Ac1)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(regions.get_deleted_history(None).await.unwrap().is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,209 @@
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Utc;
+use sqlx::SqlitePool;
+
+use crate::models::kiosk::KioskCredential;
+use crate::repositories::region_repositories::RepositoryError;
+use crate::repositories::region_repositories::begin_write;
+
+#[async_trait]
+pub trait KioskRepository: Send + Sync {
+    /// Sets the user's PIN or badge, replacing an earlier one of the same
+    /// kind. Fails if another user has the same credential.
+    async fn set_credential(
+        &self,
+        user_id: &str,
+        kind: &str,
+        hash: &str,
+    ) -> Result<(), RepositoryError>;
+    /// Removes all credentials of the user.
+    async fn delete_credentials(&self, user_id: &str) -> Result<(), RepositoryError>;
+    /// Lists which users have which kinds of credentials.
+    async fn get_credentials(&self) -> Result<Vec<KioskCredential>, RepositoryError>;
+    /// Returns the user with the credential and records the attempt at the
+    /// kiosk device. Fails without checking the credential if at least
+    /// `max_attempts` attempts at the device failed since `failed_since`.
+    async fn identify(
+        &self,
+        hash: &str,
+        device_id: i64,
+        client_id: &str,
+        max_attempts: i64,
+        failed_since: DateTime<Utc>,
+    ) -> Result<String, RepositoryError>;
+}
+
+pub struct SqliteKioskRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteKioskRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl KioskRepository for SqliteKioskRepository {
+    async fn set_credential(
+        &self,
+        user_id: &str,
+        kind: &str,
+        hash: &str,
+    ) -> Result<(), RepositoryError> {
+        sqlx::query(
+            r#"
+            INSERT INTO kiosk_credentials (user_id, kind, hash)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, kind) DO UPDATE SET hash = excluded.hash
+            "#,
+        )
+        .bind(user_id)
+        .bind(kind)
+        .bind(hash)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db_error) if db_error.is_unique_violation() => {
+                RepositoryError::AlreadyExists
+            }
+            e => RepositoryError::DatabaseError(e),
+        })?;
+
+        Ok(())
+    }
+
+    async fn delete_credentials(&self, user_id: &str) -> Result<(), RepositoryError> {
+        let result = sqlx::query("DELETE FROM kiosk_credentials WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        match result.rows_affected() {
+            0 => Err(RepositoryError::NotFound),
+            _ => Ok(()),
+        }
+    }
+
+    async fn get_credentials(&self) -> Result<Vec<KioskCredential>, RepositoryError> {
+        let result: Vec<KioskCredential> =
+            sqlx::query_as("SELECT user_id, kind FROM kiosk_credentials ORDER BY user_id, kind")
+                .fetch_all(&self.pool)
+                .await?;
+
+        Ok(result)
+    }
+
+    async fn identify(
+        &self,
+        hash: &str,
+        device_id: i64,
+        client_id: &str,
+        max_attempts: i64,
+        failed_since: DateTime<Utc>,
+    ) -> Result<String, RepositoryError> {
+        let mut transaction = begin_write(&self.pool).await?;
+
+        sqlx::query("DELETE FROM kiosk_attempts WHERE attempted_at < $1")
+            .bind(failed_since)
+            .execute(&mut *transaction)
+            .await?;
+        let (failures,): (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM kiosk_attempts WHERE NOT succeeded AND device_id = $1",
+        )
+        .bind(device_id)
+        .fetch_one(&mut *transaction)
+        .await?;
+        if failures >= max_attempts {
+            transaction.commit().await?;
+            return Err(RepositoryError::TooManyAttempts);
+        }
+
+        let user: Option<(String,)> =
+            sqlx::query_as("SELECT user_id FROM kiosk_credentials WHERE hash = $1")
+                .bind(hash)
+                .fetch_optional(&mut *transaction)
+                .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO kiosk_attempts (device_id, client_id, succeeded, attempted_at)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(device_id)
+        .bind(client_id)
+        .bind(user.is_some())
+        .bind(Utc::now())
+        .execute(&mut *transaction)
+        .await?;
+
+        // Failed attempts are committed as well, so they count
+        transaction.commit().await?;
+        user.map(|(user_id,)| user_id)
+            .ok_or(RepositoryError::InvalidCredentials)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test]
+    async fn test_identify_by_credential(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteKioskRepository::new(pool);
+        repo.set_credential("anna", "pin", "hash-1").await.unwrap();
+
+        // When
+        let anna = repo
+            .identify("hash-1", 1, "kiosk", 3, DateTime::<Utc>::MIN_UTC)
+            .await;
+        let unknown = repo
+            .identify("hash-2", 1, "kiosk", 3, DateTime::<Utc>::MIN_UTC)
+            .await;
+
+        // Then
+        assert_eq!(anna.unwrap(), "anna");
+        assert!(matches!(unknown, Err(RepositoryError::InvalidCredentials)));
+        assert!(
+            matches!(
+                repo.set_credential("ben", "pin", "hash-1").await,
+                Err(RepositoryError::AlreadyExists)
+            ),
+            "A PIN must identify a single user"
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_identify_refuses_after_failed_attempts(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given: someone guessed three times at the first kiosk
+        let repo = SqliteKioskRepository::new(pool);
+        repo.set_credential("anna", "pin", "hash-1").await.unwrap();
+        let window_start = Utc::now() - chrono::TimeDelta::minutes(5);
+        for guess in ["guess-1", "guess-2", "guess-3"] {
+            let _ = repo.identify(guess, 1, "kiosk", 3, window_start).await;
+        }
+
+        // When
+        let result = repo.identify("hash-1", 1, "kiosk", 3, window_start).await;
+        let other_kiosk = repo.identify("hash-1", 2, "kiosk", 3, window_start).await;
+        let after_lockout = repo.identify("hash-1", 1, "kiosk", 3, Utc::now()).await;
+
+        // Then
+        assert!(
+            matches!(result, Err(RepositoryError::TooManyAttempts)),
+            "Even the correct PIN is refused while locked out"
+        );
+        assert_eq!(
+            other_kiosk.unwrap(),
+            "anna",
+            "Other kiosks are not locked out"
+        );
+        assert_eq!(after_lockout.unwrap(), "anna");
+
+        Ok(())
+    }
+}
@@ -1 +1,16 @@
+pub mod absence_repositories;
+pub mod audit_repositories;
+pub mod break_repositories;
+pub mod device_repositories;
+pub mod holiday_repositories;
+pub mod idempotency_repositories;
+pub mod journal_repositories;
+pub mod kiosk_repositories;
+pub mod period_repositories;
+pub mod permission_repositories;
 pub mod region_repositories;
+pub mod rounding_repositories;
+pub mod sync_repositories;
+pub mod target_hours_repositories;
+pub mod time_zone_repositories;
+pub mod timesheet_repositories;
@@ -0,0 +1,434 @@
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Utc;
+use sqlx::SqliteConnection;
+use sqlx::SqlitePool;
+
+use crate::caller::Caller;
+use crate::models::action::ActionKind;
+use crate::models::period::PeriodLock;
+use crate::models::period::PeriodLogEntry;
+use crate::models::period::PeriodOperation;
+use crate::repositories::journal_repositories::begin_action;
+use crate::repositories::journal_repositories::end_action;
+use crate::repositories::region_repositories::RepositoryError;
+use crate::repositories::region_repositories::begin_write;
+use crate::repositories::region_repositories::split_running_entries;
+
+#[async_trait]
+pub trait PeriodRepository: Send + Sync {
+    /// Closes the month that spans `[starts_at, ends_at)`, so its entries can
+    /// no longer be changed. Timers and breaks that are still running are split
+    /// at `ends_at`, so they can still be stopped. The split is recorded as an
+    /// action of the caller.
+    async fn close_period(
+        &self,
+        year: i32,
+        month: u32,
+        starts_at: DateTime<Utc>,
+        ends_at: DateTime<Utc>,
+        caller: &Caller,
+    ) -> Result<PeriodLock, RepositoryError>;
+    /// Reopens a closed month. The reason is kept in the log.
+    async fn reopen_period(
+        &self,
+        year: i32,
+        month: u32,
+        reason: &str,
+        caller: &Caller,
+    ) -> Result<PeriodLogEntry, RepositoryError>;
+    /// Returns the closed months, most recent first.
+    async fn get_locked_periods(&self) -> Result<Vec<PeriodLock>, RepositoryError>;
+    /// Returns every closing and reopening, oldest first.
+    async fn get_period_log(&self) -> Result<Vec<PeriodLogEntry>, RepositoryError>;
+}
+
+pub struct SqlitePeriodRepository {
+    pool: SqlitePool,
+}
+
+impl SqlitePeriodRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+async fn log_operation(
+    connection: &mut SqliteConnection,
+    year: i32,
+    month: u32,
+    operation: PeriodOperation,
+    reason: Option<&str>,
+    caller: &Caller,
+    now: DateTime<Utc>,
+) -> Result<PeriodLogEntry, RepositoryError> {
+    let result: PeriodLogEntry = sqlx::query_as(
+        r#"
+        INSERT INTO period_lock_log (year, month, operation, actor, device, reason, performed_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING id, year, month, operation, actor, device, reason, performed_at
+        "#,
+    )
+    .bind(year)
+    .bind(month)
+    .bind(operation)
+    .bind(&caller.user_id)
+    .bind(&caller.client_id)
+    .bind(reason)
+    .bind(now)
+    .fetch_one(&mut *connection)
+    .await?;
+
+    Ok(result)
+}
+
+#[async_trait]
+impl PeriodRepository for SqlitePeriodRepository {
+    async fn close_period(
+        &self,
+        year: i32,
+        month: u32,
+        starts_at: DateTime<Utc>,
+        ends_at: DateTime<Utc>,
+        caller: &Caller,
+    ) -> Result<PeriodLock, RepositoryError> {
+        let now = Utc::now();
+        let mut transaction = begin_write(&self.pool).await?;
+
+        begin_action(&mut transaction, caller, ActionKind::ClosePeriod, now).await?;
+        split_running_entries(&mut transaction, ends_at).await?;
+        end_action(&mut transaction).await?;
+        let lock: PeriodLock = sqlx::query_as(
+            r#"
+            INSERT INTO period_locks (year, month, starts_at, ends_at, closed_at, closed_by)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING year, month, starts_at, ends_at, closed_at, closed_by
+            "#,
+        )
+        .bind(year)
+        .bind(month)
+        .bind(starts_at)
+        .bind(ends_at)
+        .bind(now)
+        .bind(&caller.user_id)
+        .fetch_one(&mut *transaction)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db_error) if db_error.is_unique_violation() => {
+                RepositoryError::AlreadyExists
+            }
+            e => RepositoryError::DatabaseError(e),
+        })?;
+        log_operation(
+            &mut transaction,
+            year,
+            month,
+            PeriodOperation::Close,
+            None,
+            caller,
+            now,
+        )
+        .await?;
+
+        transaction.commit().await?;
+        Ok(lock)
+    }
+
+    async fn reopen_period(
+        &self,
+        year: i32,
+        month: u32,
+        reason: &str,
+        caller: &Caller,
+    ) -> Result<PeriodLogEntry, RepositoryError> {
+        let mut transaction = begin_write(&self.pool).await?;
+
+        let result = sqlx::query("DELETE FROM period_locks WHERE year = $1 AND month = $2")
+            .bind(year)
+            .bind(month)
+            .execute(&mut *transaction)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::NotFound);
+        }
+        let entry = log_operation(
+            &mut transaction,
+            year,
+            month,
+            PeriodOperation::Reopen,
+            Some(reason),
+            caller,
+            Utc::now(),
+        )
+        .await?;
+
+        transaction.commit().await?;
+        Ok(entry)
+    }
+
+    async fn get_locked_periods(&self) -> Result<Vec<PeriodLock>, RepositoryError> {
+        let result: Vec<PeriodLock> = sqlx::query_as(
+            r#"
+            SELECT year, month, starts_at, ends_at, closed_at, closed_by
+            FROM period_locks
+            ORDER BY year DESC, month DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    async fn get_period_log(&self) -> Result<Vec<PeriodLogEntry>, RepositoryError> {
+        let result: Vec<PeriodLogEntry> = sqlx::query_as(
+            r#"
+            SELECT id, year, month, operation, actor, device, reason, performed_at
+            FROM period_lock_log
+            ORDER BY id ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::models::absence::AbsenceKind;
+    use crate::models::region::Region;
+    use crate::repositories::absence_repositories::AbsenceRepository;
+    use crate::repositories::absence_repositories::SqliteAbsenceRepository;
+    use crate::repositories::region_repositories::RegionRepository;
+    use crate::repositories::region_repositories::SqliteRegionRepository;
+
+    fn at(day: u32, hour: u32) -> DateTime<Utc> {
+        format!("2025-10-{day:02}T{hour:02}:00:00Z")
+            .parse()
+            .unwrap()
+    }
+
+    async fn close_october(repo: &SqlitePeriodRepository) {
+        let ends_at = "2025-11-01T00:00:00Z".parse().unwrap();
+        repo.close_period(2025, 10, at(1, 0), ends_at, &Caller::default())
+            .await
+            .expect("Closing should succeed");
+    }
+
+    #[sqlx::test]
+    async fn test_closed_period_rejects_changes(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        sqlx::query(
+            r#"
+            INSERT INTO region_history (region, start_time, stop_time, duration)
+            VALUES ('ac1', '2025-10-01T08:00:00+00:00', '2025-10-01T09:00:00+00:00', 3600000)
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+        let regions = SqliteRegionRepository::new(pool.clone());
+        let repo = SqlitePeriodRepository::new(pool);
+        close_october(&repo).await;
+
+        // When
+        let result = regions.delete_entry(1, &Caller::default()).await;
+
+        // Then
+        assert!(matches!(result, Err(RepositoryError::PeriodLocked)));
+        assert_eq!(
+            regions
+                .get_history_by_region(None, Region::Ac1)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_closed_period_rejects_absences_and_breaks(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        sqlx::query(
+            r#"
+            INSERT INTO break_history (start_time, stop_time, duration)
+            VALUES ('2025-10-01T12:00:00+00:00', '2025-10-01T12:30:00+00:00', 1800000)
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+        let absences = SqliteAbsenceRepository::new(pool.clone());
+        let october = NaiveDate::from_ymd_opt(2025, 10, 6).unwrap();
+        let absence = absences
+            .add_absences(None, vec![october], AbsenceKind::Sick, false)
+            .await
+            .unwrap()
+            .remove(0);
+        let repo = SqlitePeriodRepository::new(pool.clone());
+        close_october(&repo).await;
+
+        // When
+        let added = absences
+            .add_absences(
+                None,
+                vec![october.succ_opt().unwrap()],
+                AbsenceKind::Sick,
+                false,
+            )
+            .await;
+        let deleted = absences.delete_absence(None, absence.id).await;
+        let changed_break = sqlx::query("UPDATE break_history SET duration = 0")
+            .execute(&pool)
+            .await;
+
+        // Then
+        assert!(matches!(added, Err(RepositoryError::PeriodLocked)));
+        assert!(matches!(deleted, Err(RepositoryError::PeriodLocked)));
+        assert!(matches!(
+            changed_break.map_err(RepositoryError::from),
+            Err(RepositoryError::PeriodLocked)
+        ));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_reopened_period_accepts_changes(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        sqlx::query(
+            r#"
+            INSERT INTO region_history (region, start_time, stop_time, duration, user_id)
+            VALUES ('ac1', '2025-10-01T08:00:00+00:00', '2025-10-01T09:00:00+00:00', 3600000, 'anna')
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+        let regions = SqliteRegionRepository::new(pool.clone());
+        let repo = SqlitePeriodRepository::new(pool);
+        close_october(&repo).await;
+        let caller = Caller {
+            client_id: "office".to_string(),
+            user_id: Some("anna".to_string()),
+            device_id: None,
+        };
+
+        // When
+        repo.reopen_period(2025, 10, "Forgot a correction", &caller)
+            .await
+            .expect("Reopening should succeed");
+
+        // Then
+        assert!(regions.delete_entry(1, &caller).await.is_ok());
+        assert!(repo.get_locked_periods().await.unwrap().is_empty());
+        let log = repo.get_period_log().await.unwrap();
+        let operations: Vec<PeriodOperation> = log.iter().map(|entry| entry.operation).collect();
+        assert_eq!(
+            operations,
+            vec![PeriodOperation::Close, PeriodOperation::Reopen]
+        );
+        assert_eq!(log[1].actor.as_deref(), Some("anna"));
+        assert_eq!(log[1].reason.as_deref(), Some("Forgot a correction"));
+        assert!(matches!(
+            repo.reopen_period(2025, 10, "Again", &caller).await,
+            Err(RepositoryError::NotFound)
+        ));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_close_period_splits_running_entry(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given: a timer and a break that have been running since the end of
+        // October
+        sqlx::query(
+            r#"
+            INSERT INTO region_history (region, start_time)
+            VALUES ('ac1', '2025-10-31T22:00:00+00:00')
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO break_history (start_time)
+            VALUES ('2025-10-31T23:00:00+00:00')
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+        let regions = SqliteRegionRepository::new(pool.clone());
+        let repo = SqlitePeriodRepository::new(pool.clone());
+
+        // When
+        close_october(&repo).await;
+
+        // Then: October keeps its part and the timer can still be stopped
+        regions
+            .stop_timer(Region::Ac1, &Caller::default())
+            .await
+            .expect("Stopping should succeed");
+        let history = regions
+            .get_history_by_region(None, Region::Ac1)
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 2);
+        assert!(history.iter().all(|entry| entry.stop_time.is_some()));
+        let october = history.iter().find(|entry| entry.id == 1).unwrap();
+        assert_eq!(
+            october.stop_time,
+            Some("2025-11-01T00:00:00Z".parse().unwrap())
+        );
+        assert_eq!(october.duration, Some(7_200_000));
+        let (breaks, running_breaks): (i64, i64) =
+            sqlx::query_as("SELECT COUNT(*), COUNT(*) - COUNT(stop_time) FROM break_history")
+                .fetch_one(&pool)
+                .await?;
+        assert_eq!((breaks, running_breaks), (2, 1), "The break is split too");
+        let (unattributed,): (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM audit_log WHERE device IS NULL")
+                .fetch_one(&pool)
+                .await?;
+        assert_eq!(
+            unattributed, 1,
+            "Only the entry inserted outside of the application lacks a caller"
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_trash_of_closed_period_can_be_purged(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given: the entry was deleted before the month was closed
+        sqlx::query(
+            r#"
+            INSERT INTO region_history (region, start_time, stop_time, duration, deleted_at)
+            VALUES ('ac1', '2025-10-01T08:00:00+00:00', '2025-10-01T09:00:00+00:00', 3600000,
+                    '2025-10-02T08:00:00+00:00')
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+        let regions = SqliteRegionRepository::new(pool.clone());
+        let repo = SqlitePeriodRepository::new(pool);
+        close_october(&repo).await;
+
+        // When
+        let restored = regions.restore_entry(1, &Caller::default()).await;
+        let purged = regions.purge_deleted_entries(at(3, 0)).await;
+
+        // Then
+        assert!(
+            matches!(restored, Err(RepositoryError::PeriodLocked)),
+            "Restoring would change the closed month"
+        );
+        assert_eq!(purged.unwrap(), 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,306 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+use crate::models::permission::AllowList;
+use crate::models::permission::GroupMember;
+use crate::models::permission::RegionPermissions;
+use crate::models::permission::Role;
+use crate::models::permission::UserRole;
+use crate::models::region::Region;
+use crate::repositories::region_repositories::RepositoryError;
+use crate::repositories::region_repositories::begin_write;
+
+#[async_trait]
+pub trait PermissionRepository: Send + Sync {
+    /// Returns the stored role of the user, or `None` for members.
+    async fn get_role(&self, user_id: &str) -> Result<Option<Role>, RepositoryError>;
+    async fn get_roles(&self) -> Result<Vec<UserRole>, RepositoryError>;
+    async fn set_role(&self, user_id: &str, role: Role) -> Result<UserRole, RepositoryError>;
+    async fn delete_role(&self, user_id: &str) -> Result<(), RepositoryError>;
+    async fn get_group_members(&self) -> Result<Vec<GroupMember>, RepositoryError>;
+    async fn add_group_member(
+        &self,
+        group_name: &str,
+        user_id: &str,
+    ) -> Result<(), RepositoryError>;
+    async fn remove_group_member(
+        &self,
+        group_name: &str,
+        user_id: &str,
+    ) -> Result<(), RepositoryError>;
+    /// Returns the allow-lists of all restricted regions, ordered by region.
+    async fn get_region_permissions(&self) -> Result<Vec<RegionPermissions>, RepositoryError>;
+    /// Replaces the allow-list of the region. An empty list opens the region
+    /// to everyone.
+    async fn set_region_permissions(
+        &self,
+        region: Region,
+        allow_list: AllowList,
+    ) -> Result<RegionPermissions, RepositoryError>;
+    /// Whether the user, or anyone without a user id, may book time on the
+    /// region. Roles are not considered.
+    async fn may_book(
+        &self,
+        region: &Region,
+        user_id: Option<&str>,
+    ) -> Result<bool, RepositoryError>;
+}
+
+pub struct SqlitePermissionRepository {
+    pool: SqlitePool,
+}
+
+impl SqlitePermissionRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl PermissionRepository for SqlitePermissionRepository {
+    async fn get_role(&self, user_id: &str) -> Result<Option<Role>, RepositoryError> {
+        let result: Option<(Role,)> =
+            sqlx::query_as("SELECT role FROM user_roles WHERE user_id = $1")
+                .bind(user_id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(result.map(|(role,)| role))
+    }
+
+    async fn get_roles(&self) -> Result<Vec<UserRole>, RepositoryError> {
+        let result: Vec<UserRole> =
+            sqlx::query_as("SELECT user_id, role FROM user_roles ORDER BY user_id ASC")
+                .fetch_all(&self.pool)
+                .await?;
+
+        Ok(result)
+    }
+
+    async fn set_role(&self, user_id: &str, role: Role) -> Result<UserRole, RepositoryError> {
+        let result: UserRole = sqlx::query_as(
+            r#"
+            INSERT INTO user_roles (user_id, role)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id) DO UPDATE SET role = excluded.role
+            RETURNING user_id, role
+            "#,
+        )
+        .bind(user_id)
+        .bind(role)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    async fn delete_role(&self, user_id: &str) -> Result<(), RepositoryError> {
+        let result = sqlx::query("DELETE FROM user_roles WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        match result.rows_affected() {
+            0 => Err(RepositoryError::NotFound),
+            _ => Ok(()),
+        }
+    }
+
+    async fn get_group_members(&self) -> Result<Vec<GroupMember>, RepositoryError> {
+        let result: Vec<GroupMember> = sqlx::query_as(
+            "SELECT group_name, user_id FROM group_members ORDER BY group_name ASC, user_id ASC",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    async fn add_group_member(
+        &self,
+        group_name: &str,
+        user_id: &str,
+    ) -> Result<(), RepositoryError> {
+        sqlx::query(
+            r#"
+            INSERT INTO group_members (group_name, user_id)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(group_name)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn remove_group_member(
+        &self,
+        group_name: &str,
+        user_id: &str,
+    ) -> Result<(), RepositoryError> {
+        let result =
+            sqlx::query("DELETE FROM group_members WHERE group_name = $1 AND user_id = $2")
+                .bind(group_name)
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+
+        match result.rows_affected() {
+            0 => Err(RepositoryError::NotFound),
+            _ => Ok(()),
+        }
+    }
+
+    async fn get_region_permissions(&self) -> Result<Vec<RegionPermissions>, RepositoryError> {
+        let rows: Vec<(Region, String, String)> = sqlx::query_as(
+            r#"
+            SELECT region, subject_kind, subject
+            FROM region_permissions
+            ORDER BY region ASC, subject ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut result: Vec<RegionPermissions> = Vec::new();
+        for (region, subject_kind, subject) in rows {
+            if result.last().is_none_or(|last| last.region != region) {
+                result.push(RegionPermissions {
+                    region: region.clone(),
+                    allow_list: AllowList::default(),
+                });
+            }
+            let allow_list = &mut result.last_mut().expect("was just pushed").allow_list;
+            match subject_kind.as_str() {
+                "group" => allow_list.groups.push(subject),
+                _ => allow_list.users.push(subject),
+            }
+        }
+
+        Ok(result)
+    }
+
+    async fn set_region_permissions(
+        &self,
+        region: Region,
+        allow_list: AllowList,
+    ) -> Result<RegionPermissions, RepositoryError> {
+        let mut transaction = begin_write(&self.pool).await?;
+
+        sqlx::query("DELETE FROM region_permissions WHERE region = $1")
+            .bind(&region)
+            .execute(&mut *transaction)
+            .await?;
+        let subjects = allow_list
+            .users
+            .iter()
+            .map(|user| ("user", user))
+            .chain(allow_list.groups.iter().map(|group| ("group", group)));
+        for (subject_kind, subject) in subjects {
+            sqlx::query(
+                r#"
+                INSERT INTO region_permissions (region, subject_kind, subject)
+                VALUES ($1, $2, $3)
+                ON CONFLICT DO NOTHING
+                "#,
+            )
+            .bind(&region)
+            .bind(subject_kind)
+            .bind(subject)
+            .execute(&mut *transaction)
+            .await?;
+        }
+
+        transaction.commit().await?;
+        Ok(RegionPermissions { region, allow_list })
+    }
+
+    async fn may_book(
+        &self,
+        region: &Region,
+        user_id: Option<&str>,
+    ) -> Result<bool, RepositoryError> {
+        let (allowed,): (bool,) = sqlx::query_as(
+            r#"
+            SELECT NOT EXISTS (SELECT 1 FROM region_permissions WHERE region = $1)
+                OR EXISTS (SELECT 1
+                           FROM region_permissions
+                           WHERE region = $1 AND subject_kind = 'user' AND subject = $2)
+                OR EXISTS (SELECT 1
+                           FROM region_permissions
+                           JOIN group_members ON group_members.group_name = subject
+                           WHERE region = $1 AND subject_kind = 'group' AND user_id = $2)
+            "#,
+        )
+        .bind(region)
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(allowed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test]
+    async fn test_allow_list_restricts_region(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given: Ac1 is restricted to Anna and the cleaning group
+        let repo = SqlitePermissionRepository::new(pool);
+        repo.set_region_permissions(
+            Region::Ac1,
+            AllowList {
+                users: vec!["anna".to_string()],
+                groups: vec!["cleaning".to_string()],
+            },
+        )
+        .await
+        .unwrap();
+        repo.add_group_member("cleaning", "carl").await.unwrap();
+
+        // When
+        let anna = repo.may_book(&Region::Ac1, Some("anna")).await.unwrap();
+        let carl = repo.may_book(&Region::Ac1, Some("carl")).await.unwrap();
+        let ben = repo.may_book(&Region::Ac1, Some("ben")).await.unwrap();
+        let anonymous = repo.may_book(&Region::Ac1, None).await.unwrap();
+        let open_region = repo.may_book(&Region::Aa1, None).await.unwrap();
+
+        // Then
+        assert!(anna, "Anna is on the allow-list");
+        assert!(carl, "Carl is in an allowed group");
+        assert!(!ben);
+        assert!(!anonymous);
+        assert!(open_region, "Regions without an allow-list are open");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_empty_allow_list_opens_region(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqlitePermissionRepository::new(pool);
+        let allow_list = AllowList {
+            users: vec!["anna".to_string()],
+            groups: Vec::new(),
+        };
+        repo.set_region_permissions(Region::Ac1, allow_list)
+            .await
+            .unwrap();
+
+        // When
+        repo.set_region_permissions(Region::Ac1, AllowList::default())
+            .await
+            .unwrap();
+
+        // Then
+        assert!(repo.may_book(&Region::Ac1, Some("ben")).await.unwrap());
+        assert!(repo.get_region_permissions().await.unwrap().is_empty());
+
+        Ok(())
+    }
+}
@@ -1,364 +1,2290 @@
 use async_trait::async_trait;
 use chrono::DateTime;
 use chrono::Utc;
+use chrono_tz::Tz;
+use sqlx::Connection;
+use sqlx::Sqlite;
+use sqlx::SqliteConnection;
 use sqlx::SqlitePool;
+use sqlx::Transaction;
 
+use crate::caller::Caller;
+use crate::configuration::Configuration;
+use crate::configuration::MinDuration;
+use crate::configuration::RestartBehavior;
+use crate::configuration::ShortEntryAction;
+use crate::models::action::ActionKind;
 use crate::models::region::CurrentlyActiveRegion;
 use crate::models::region::Region;
+use crate::models::region::StoppedTimer;
+use crate::models::region::ToggledTimer;
+use crate::models::region_history::DeletedEntry;
+use crate::models::region_history::OwnedEntry;
 use crate::models::region_history::RegionHistory;
+use crate::models::region_history::RemovedEntry;
+use crate::reports::is_day_boundary;
+use crate::reports::millis_between;
+use crate::reports::split_at_day_boundaries;
+use crate::repositories::break_repositories::stop_running_break;
+use crate::repositories::journal_repositories::begin_action;
+use crate::repositories::journal_repositories::end_action;
 
 #[derive(thiserror::Error, Debug)]
 pub enum RepositoryError {
     #[error("No timer is running for the region")]
     TimerNotRunning,
+    #[error("A break is already running")]
+    BreakAlreadyRunning,
+    #[error("No break is running")]
+    BreakNotRunning,
+    #[error("The requested entry does not exist")]
+    NotFound,
+    #[error("The entry already exists")]
+    AlreadyExists,
+    #[error("The period must not end before it starts")]
+    InvalidPeriod,
+    #[error("The period overlaps with an existing period")]
+    OverlappingPeriod,
+    #[error("The entries were changed since")]
+    ChangedSince,
+    #[error("The period is closed")]
+    PeriodLocked,
+    #[error("The timesheet does not have the required status")]
+    InvalidStatus,
+    #[error("Booking time on the region is not permitted")]
+    Forbidden,
+    #[error("The PIN or badge is unknown")]
+    InvalidCredentials,
+    #[error("Too many failed attempts, try again later")]
+    TooManyAttempts,
+    #[error("The device is unknown or was revoked")]
+    UnknownDevice,
     #[error("Database error: {0}")]
-    DatabaseError(#[from] sqlx::Error),
+    DatabaseError(sqlx::Error),
+}
+
+/// The message with which the database rejects changes to a closed month.
+const PERIOD_LOCKED_MESSAGE: &str = "The period is locked";
+
+impl From<sqlx::Error> for RepositoryError {
+    fn from(error: sqlx::Error) -> Self {
+        match error {
+            sqlx::Error::Database(ref db_error) if db_error.message() == PERIOD_LOCKED_MESSAGE => {
+                RepositoryError::PeriodLocked
+            }
+            error => RepositoryError::DatabaseError(error),
+        }
+    }
+}
+
+/// Begins a transaction that takes the write lock right away, so concurrent
+/// writers wait for each other instead of deciding on an outdated state.
+pub(crate) async fn begin_write(
+    pool: &SqlitePool,
+) -> Result<Transaction<'static, Sqlite>, RepositoryError> {
+    Ok(pool.begin_with("BEGIN IMMEDIATE").await?)
 }
 
 #[async_trait]
 pub trait RegionRepository: Send + Sync {
-    async fn start_timer(&self, region: Region) -> Result<(), RepositoryError>;
-    async fn stop_timer(&self, region: Region) -> Result<i64, RepositoryError>;
+    /// Starts the timer of the region and returns the timer that was stopped
+    /// for it, if another one was running.
+    async fn start_timer(
+        &self,
+        region: Region,
+        caller: &Caller,
+    ) -> Result<Option<StoppedTimer>, RepositoryError>;
+    /// Stops the timer of the region and returns the duration of the entry, or
+    /// zero if the entry was removed for being too short.
+    async fn stop_timer(&self, region: Region, caller: &Caller) -> Result<i64, RepositoryError>;
+    /// Stops the timer of the region if it is running, and starts it otherwise.
+    async fn toggle_timer(
+        &self,
+        region: Region,
+        caller: &Caller,
+    ) -> Result<ToggledTimer, RepositoryError>;
+    /// Returns the entries of the user on the region. `None` selects the
+    /// entries of callers without a user.
     async fn get_history_by_region(
         &self,
+        user_id: Option<&str>,
         region: Region,
     ) -> Result<Vec<RegionHistory>, RepositoryError>;
-    async fn currently_active_timer(&self) -> Result<CurrentlyActiveRegion, RepositoryError>;
+    /// Returns all entries of the user that overlap the time range
+    /// `[from, to)`, including a timer that is still running.
+    async fn get_history_between(
+        &self,
+        user_id: Option<&str>,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<RegionHistory>, RepositoryError>;
+    async fn currently_active_timer(
+        &self,
+        user_id: Option<&str>,
+    ) -> Result<CurrentlyActiveRegion, RepositoryError>;
+    async fn get_running_timer(
+        &self,
+        user_id: Option<&str>,
+    ) -> Result<Option<RegionHistory>, RepositoryError>;
+    /// Returns the running timers of all users.
+    async fn get_running_timers(&self) -> Result<Vec<OwnedEntry>, RepositoryError>;
+    /// Stops the running entry `id` at `stop_time` and marks it as
+    /// auto-stopped. Returns the stopped entry as a whole, even if it was
+    /// split into days, or `None` if the entry was not running.
+    async fn auto_stop_timer(
+        &self,
+        id: i64,
+        stop_time: DateTime<Utc>,
+    ) -> Result<Option<RegionHistory>, RepositoryError>;
+    /// Returns all auto-stopped entries of the user, most recent first.
+    async fn get_auto_stopped_history(
+        &self,
+        user_id: Option<&str>,
+    ) -> Result<Vec<RegionHistory>, RepositoryError>;
+    /// Removes all of the caller's stopped entries that are shorter than
+    /// `min_duration`, oldest first. Entries that start or end at midnight are
+    /// kept, as they are usually the short part of an entry that was split
+    /// into days, and so are entries in closed months. A dry run only
+    /// reports the entries that would be removed.
+    async fn remove_short_entries(
+        &self,
+        min_duration: MinDuration,
+        dry_run: bool,
+        caller: &Caller,
+    ) -> Result<Vec<RemovedEntry>, RepositoryError>;
+    /// Merges every entry of the caller into their entry of the same region
+    /// that ended when it started. Entries that were split at midnight stay
+    /// split, if entries are split on stop, and entries in closed months
+    /// stay as they are. A dry run only reports the entries that would be
+    /// merged.
+    async fn merge_contiguous_entries(
+        &self,
+        dry_run: bool,
+        caller: &Caller,
+    ) -> Result<Vec<RemovedEntry>, RepositoryError>;
+    /// Moves the caller's entry to the trash. A running entry is stopped
+    /// first like any timer, the entries split off it are deleted with it, and
+    /// an entry that is too short is removed for good instead.
+    async fn delete_entry(&self, id: i64, caller: &Caller)
+    -> Result<DeletedEntry, RepositoryError>;
+    /// Returns the user's entries in the trash, most recently deleted first.
+    async fn get_deleted_history(
+        &self,
+        user_id: Option<&str>,
+    ) -> Result<Vec<DeletedEntry>, RepositoryError>;
+    /// Moves the caller's entry out of the trash, back into the history.
+    async fn restore_entry(
+        &self,
+        id: i64,
+        caller: &Caller,
+    ) -> Result<RegionHistory, RepositoryError>;
+    /// Permanently deletes the entries that were moved to the trash before
+    /// `deleted_before`. Returns the number of purged entries.
+    async fn purge_deleted_entries(
+        &self,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<u64, RepositoryError>;
+}
+
+/// Decides how the entry of a stopped timer is stored.
+#[derive(Debug, Clone, Copy)]
+pub struct StopPolicy {
+    /// Stores an entry that spans multiple days as one entry per day
+    pub split_on_stop: bool,
+    /// The time zone whose midnight separates the days
+    pub time_zone: Tz,
+    /// Removes entries that are shorter than the minimum duration
+    pub min_duration: Option<MinDuration>,
+}
+
+impl Default for StopPolicy {
+    fn default() -> Self {
+        Self {
+            split_on_stop: false,
+            time_zone: Tz::UTC,
+            min_duration: None,
+        }
+    }
+}
+
+impl From<&Configuration> for StopPolicy {
+    fn from(configuration: &Configuration) -> Self {
+        Self {
+            split_on_stop: configuration.split_on_stop,
+            time_zone: configuration.time_zone(),
+            min_duration: configuration.min_duration(),
+        }
+    }
+}
+
+impl StopPolicy {
+    /// Stops the running entry `id` at `now` and returns its duration, or zero
+    /// if it was too short and removed. Every way of stopping a timer goes
+    /// through here, so the entries are stored the same way whether the timer
+    /// was stopped explicitly, by starting another region or a break, or
+    /// automatically.
+    pub(crate) async fn stop(
+        &self,
+        connection: &mut SqliteConnection,
+        id: i64,
+        start_time: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<i64, RepositoryError> {
+        if let Some(min_duration) = self
+            .min_duration
+            .filter(|min_duration| min_duration.is_too_short(start_time, now))
+        {
+            remove_short_entry(connection, id, start_time, now, min_duration.action).await?;
+            return Ok(0);
+        }
+
+        let segments = match self.split_on_stop {
+            true => split_at_day_boundaries(start_time, now, &self.time_zone),
+            false => vec![(start_time, now)],
+        };
+
+        // The stored entry keeps the first day, every further day gets an
+        // entry of its own
+        let mut duration = 0;
+        for (index, (segment_start, segment_stop)) in segments.into_iter().enumerate() {
+            duration += match index {
+                0 => stop_entry(connection, id, segment_start, segment_stop).await?,
+                _ => copy_entry(connection, id, segment_start, segment_stop).await?,
+            };
+        }
+
+        Ok(duration)
+    }
 }
 
 pub struct SqliteRegionRepository {
     pool: SqlitePool,
+    stop_policy: StopPolicy,
+    restart_behavior: RestartBehavior,
 }
 
 impl SqliteRegionRepository {
     pub fn new(pool: SqlitePool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            stop_policy: StopPolicy::default(),
+            restart_behavior: RestartBehavior::default(),
+        }
     }
-}
 
-#[async_trait]
-impl RegionRepository for SqliteRegionRepository {
-    async fn start_timer(&self, region: Region) -> Result<(), RepositoryError> {
-        let now = Utc::now();
+    pub fn with_stop_policy(mut self, stop_policy: StopPolicy) -> Self {
+        self.stop_policy = stop_policy;
+        self
+    }
+
+    /// Stores a stopped timer that spans multiple days as one entry per day.
+    pub fn with_split_on_stop(mut self, split_on_stop: bool) -> Self {
+        self.stop_policy.split_on_stop = split_on_stop;
+        self
+    }
+
+    /// Sets the time zone whose midnight separates the days when splitting.
+    pub fn with_time_zone(mut self, time_zone: Tz) -> Self {
+        self.stop_policy.time_zone = time_zone;
+        self
+    }
+
+    /// Removes entries that are shorter than the minimum duration when they are
+    /// stopped.
+    pub fn with_min_duration(mut self, min_duration: Option<MinDuration>) -> Self {
+        self.stop_policy.min_duration = min_duration;
+        self
+    }
+
+    /// Sets what starting the running region does.
+    pub fn with_restart_behavior(mut self, restart_behavior: RestartBehavior) -> Self {
+        self.restart_behavior = restart_behavior;
+        self
+    }
+
+    /// Starts the timer of the region for the user at `now`, stopping their
+    /// running timer and break. Returns the stopped timer.
+    async fn start_on(
+        &self,
+        connection: &mut SqliteConnection,
+        user_id: Option<&str>,
+        region: &Region,
+        now: DateTime<Utc>,
+    ) -> Result<Option<StoppedTimer>, RepositoryError> {
+        if self.restart_behavior == RestartBehavior::Keep
+            && running_entry(connection, user_id, Some(region))
+                .await?
+                .is_some()
+        {
+            return Ok(None);
+        }
 
         // Stop any active timer
+        let mut stopped = None;
+        if let Some((id, running_region, start_time)) =
+            running_entry(connection, user_id, None).await?
+        {
+            let duration = self
+                .stop_policy
+                .stop(connection, id, start_time, now)
+                .await?;
+            stopped = Some(StoppedTimer {
+                region: running_region,
+                duration,
+            });
+        }
+
+        // Starting to work ends a running break
+        stop_running_break(connection, user_id, now).await?;
+
+        // Only the entry that was just stopped can be resumed, so in the end
+        // nothing was stopped
+        if self.restart_behavior == RestartBehavior::Merge
+            && resume_entry(connection, user_id, region, now).await?
+        {
+            return Ok(None);
+        }
+
+        // Start timer for this region
         sqlx::query(
             r#"
-                UPDATE region_history
-                SET stop_time = $1,
-                    duration = (strftime('%s', $1) - strftime('%s', start_time))
-                WHERE stop_time IS NULL
+            INSERT INTO region_history (region, start_time, user_id)
+            VALUES ($1, $2, $3)
             "#,
         )
+        .bind(region)
         .bind(now)
-        .execute(&self.pool)
+        .bind(user_id)
+        .execute(&mut *connection)
+        .await
+        .map_err(|e| match e {
+            // Only one timer can run at a time per user
+            sqlx::Error::Database(ref db_error) if db_error.is_unique_violation() => {
+                RepositoryError::AlreadyExists
+            }
+            e => RepositoryError::from(e),
+        })?;
+
+        Ok(stopped)
+    }
+
+    /// Stops the user's running timer of the region at `now`. Returns the
+    /// duration of the stopped timer, zero if it was too short and removed, or
+    /// `None` if the region was not running.
+    async fn stop_on(
+        &self,
+        connection: &mut SqliteConnection,
+        user_id: Option<&str>,
+        region: &Region,
+        now: DateTime<Utc>,
+    ) -> Result<Option<i64>, RepositoryError> {
+        let Some((id, _, start_time)) = running_entry(connection, user_id, Some(region)).await?
+        else {
+            return Ok(None);
+        };
+        let duration = self
+            .stop_policy
+            .stop(connection, id, start_time, now)
+            .await?;
+        Ok(Some(duration))
+    }
+}
+
+/// Returns the id, region and start time of the user's running timer, if it
+/// belongs to `region` or no region is given.
+pub(crate) async fn running_entry(
+    connection: &mut SqliteConnection,
+    user_id: Option<&str>,
+    region: Option<&Region>,
+) -> Result<Option<(i64, Region, DateTime<Utc>)>, RepositoryError> {
+    let result = sqlx::query_as(
+        r#"
+        SELECT id, region, start_time
+        FROM region_history
+        WHERE stop_time IS NULL AND user_id IS $1 AND ($2 IS NULL OR region = $2)
+          AND deleted_at IS NULL
+        "#,
+    )
+    .bind(user_id)
+    .bind(region)
+    .fetch_optional(&mut *connection)
+    .await?;
+
+    Ok(result)
+}
+
+/// Stops the entry `id` that started at `start_time` and returns its duration.
+pub(crate) async fn stop_entry(
+    connection: &mut SqliteConnection,
+    id: i64,
+    start_time: DateTime<Utc>,
+    stop_time: DateTime<Utc>,
+) -> Result<i64, RepositoryError> {
+    let duration = millis_between(start_time, stop_time);
+    sqlx::query(
+        r#"
+        UPDATE region_history
+        SET stop_time = $1,
+            duration = $2
+        WHERE id = $3
+        "#,
+    )
+    .bind(stop_time)
+    .bind(duration)
+    .bind(id)
+    .execute(&mut *connection)
+    .await?;
+
+    Ok(duration)
+}
+
+/// Inserts a stopped entry with the region, the user and the other attributes
+/// of the entry `id`, and returns its duration.
+async fn copy_entry(
+    connection: &mut SqliteConnection,
+    id: i64,
+    start_time: DateTime<Utc>,
+    stop_time: DateTime<Utc>,
+) -> Result<i64, RepositoryError> {
+    let duration = millis_between(start_time, stop_time);
+    sqlx::query(
+        r#"
+        INSERT INTO region_history (region, start_time, stop_time, duration, auto_stopped,
+                                    user_id)
+        SELECT region, $2, $3, $4, auto_stopped, user_id
+        FROM region_history
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .bind(start_time)
+    .bind(stop_time)
+    .bind(duration)
+    .execute(&mut *connection)
+    .await?;
+
+    Ok(duration)
+}
+
+/// Stops the running entries and breaks that started before `at` at that
+/// moment and lets a copy of each keep running from there, so none spans `at`.
+pub(crate) async fn split_running_entries(
+    connection: &mut SqliteConnection,
+    at: DateTime<Utc>,
+) -> Result<(), RepositoryError> {
+    let running: Vec<(i64, DateTime<Utc>)> = sqlx::query_as(
+        r#"
+        SELECT id, start_time
+        FROM region_history
+        WHERE stop_time IS NULL AND start_time < $1 AND deleted_at IS NULL
+        "#,
+    )
+    .bind(at)
+    .fetch_all(&mut *connection)
+    .await?;
+
+    for (id, start_time) in running {
+        stop_entry(connection, id, start_time, at).await?;
+        sqlx::query(
+            r#"
+            INSERT INTO region_history (region, start_time, auto_stopped, user_id)
+            SELECT region, $2, auto_stopped, user_id
+            FROM region_history
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(at)
+        .execute(&mut *connection)
         .await?;
+    }
 
-        // Start timer for this region
+    let breaks: Vec<(i64, DateTime<Utc>)> = sqlx::query_as(
+        r#"
+        SELECT id, start_time
+        FROM break_history
+        WHERE stop_time IS NULL AND start_time < $1
+        "#,
+    )
+    .bind(at)
+    .fetch_all(&mut *connection)
+    .await?;
+
+    for (id, start_time) in breaks {
+        sqlx::query(
+            r#"
+            UPDATE break_history
+            SET stop_time = $2,
+                duration = $3
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(at)
+        .bind(millis_between(start_time, at))
+        .execute(&mut *connection)
+        .await?;
         sqlx::query(
             r#"
-            INSERT INTO region_history (region, start_time)
-            VALUES ($1, $2)
+            INSERT INTO break_history (start_time, interrupted_region, user_id)
+            SELECT $2, interrupted_region, user_id
+            FROM break_history
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(at)
+        .execute(&mut *connection)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Merges the entry `later_id` into the entry `earlier_id` that ended when it
+/// started, and returns the removed later entry.
+async fn merge_entries(
+    connection: &mut SqliteConnection,
+    earlier_id: i64,
+    earlier_start: DateTime<Utc>,
+    later_id: i64,
+) -> Result<RegionHistory, RepositoryError> {
+    let later: RegionHistory = sqlx::query_as(
+        r#"
+        DELETE FROM region_history
+        WHERE id = $1
+        RETURNING id, region, start_time, stop_time, duration, auto_stopped
+        "#,
+    )
+    .bind(later_id)
+    .fetch_one(&mut *connection)
+    .await?;
+
+    // The earlier entry keeps running if the later one was running
+    sqlx::query(
+        r#"
+        UPDATE region_history
+        SET stop_time = $1,
+            duration = $2,
+            auto_stopped = auto_stopped OR $3
+        WHERE id = $4
+        "#,
+    )
+    .bind(later.stop_time)
+    .bind(
+        later
+            .stop_time
+            .map(|stop_time| millis_between(earlier_start, stop_time)),
+    )
+    .bind(later.auto_stopped)
+    .bind(earlier_id)
+    .execute(&mut *connection)
+    .await?;
+
+    Ok(later)
+}
+
+/// Inserts an entry of the user and returns its id.
+pub(crate) async fn insert_entry(
+    connection: &mut SqliteConnection,
+    user_id: Option<&str>,
+    region: &Region,
+    start_time: DateTime<Utc>,
+    stop_time: Option<DateTime<Utc>>,
+) -> Result<i64, RepositoryError> {
+    let duration = stop_time.map(|stop_time| millis_between(start_time, stop_time));
+    let result = sqlx::query(
+        r#"
+        INSERT INTO region_history (region, start_time, stop_time, duration, user_id)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind(region)
+    .bind(start_time)
+    .bind(stop_time)
+    .bind(duration)
+    .bind(user_id)
+    .execute(&mut *connection)
+    .await?;
+
+    Ok(result.last_insert_rowid())
+}
+
+/// Continues the user's entry of the region that stopped at `now`. Returns
+/// whether there was such an entry.
+async fn resume_entry(
+    connection: &mut SqliteConnection,
+    user_id: Option<&str>,
+    region: &Region,
+    now: DateTime<Utc>,
+) -> Result<bool, RepositoryError> {
+    let result = sqlx::query(
+        r#"
+        UPDATE region_history
+        SET stop_time = NULL,
+            duration = NULL
+        WHERE region = $1 AND stop_time = $2 AND user_id IS $3 AND deleted_at IS NULL
+        "#,
+    )
+    .bind(region)
+    .bind(now)
+    .bind(user_id)
+    .execute(&mut *connection)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Deletes the entry `id` that ran from `start_time` to `stop_time`. When
+/// merging, its time is added to an adjacent entry first. Returns the id of the
+/// entry that received the time.
+async fn remove_short_entry(
+    connection: &mut SqliteConnection,
+    id: i64,
+    start_time: DateTime<Utc>,
+    stop_time: DateTime<Utc>,
+    action: ShortEntryAction,
+) -> Result<Option<i64>, RepositoryError> {
+    let merged_into = match action {
+        ShortEntryAction::Discard => None,
+        ShortEntryAction::Merge => {
+            merge_into_neighbour(connection, id, start_time, stop_time).await?
+        }
+    };
+
+    sqlx::query("DELETE FROM region_history WHERE id = $1")
+        .bind(id)
+        .execute(&mut *connection)
+        .await?;
+
+    Ok(merged_into)
+}
+
+/// Extends the entry of the same user that ended at `start_time` to
+/// `stop_time` or, without one, moves the start of their entry that started at
+/// `stop_time` back to `start_time`. Returns the id of the extended entry.
+async fn merge_into_neighbour(
+    connection: &mut SqliteConnection,
+    id: i64,
+    start_time: DateTime<Utc>,
+    stop_time: DateTime<Utc>,
+) -> Result<Option<i64>, RepositoryError> {
+    let previous: Option<(i64, DateTime<Utc>)> = sqlx::query_as(
+        r#"
+        SELECT id, start_time
+        FROM region_history
+        WHERE stop_time = $1 AND id != $2 AND deleted_at IS NULL
+          AND user_id IS (SELECT user_id FROM region_history WHERE id = $2)
+        "#,
+    )
+    .bind(start_time)
+    .bind(id)
+    .fetch_optional(&mut *connection)
+    .await?;
+    if let Some((previous_id, previous_start)) = previous {
+        stop_entry(connection, previous_id, previous_start, stop_time).await?;
+        return Ok(Some(previous_id));
+    }
+
+    let next: Option<(i64, Option<DateTime<Utc>>)> = sqlx::query_as(
+        r#"
+        SELECT id, stop_time
+        FROM region_history
+        WHERE start_time = $1 AND id != $2 AND deleted_at IS NULL
+          AND user_id IS (SELECT user_id FROM region_history WHERE id = $2)
+        "#,
+    )
+    .bind(stop_time)
+    .bind(id)
+    .fetch_optional(&mut *connection)
+    .await?;
+    let Some((next_id, next_stop)) = next else {
+        return Ok(None);
+    };
+    sqlx::query(
+        r#"
+        UPDATE region_history
+        SET start_time = $1,
+            duration = $2
+        WHERE id = $3
+        "#,
+    )
+    .bind(start_time)
+    .bind(next_stop.map(|next_stop| millis_between(start_time, next_stop)))
+    .bind(next_id)
+    .execute(&mut *connection)
+    .await?;
+
+    Ok(Some(next_id))
+}
+
+#[async_trait]
+impl RegionRepository for SqliteRegionRepository {
+    async fn start_timer(
+        &self,
+        region: Region,
+        caller: &Caller,
+    ) -> Result<Option<StoppedTimer>, RepositoryError> {
+        let now = Utc::now();
+        let mut transaction = begin_write(&self.pool).await?;
+        begin_action(&mut transaction, caller, ActionKind::StartTimer, now).await?;
+        let stopped = self
+            .start_on(&mut transaction, caller.user_id.as_deref(), &region, now)
+            .await?;
+        end_action(&mut transaction).await?;
+        transaction.commit().await?;
+        Ok(stopped)
+    }
+
+    async fn stop_timer(&self, region: Region, caller: &Caller) -> Result<i64, RepositoryError> {
+        let now = Utc::now();
+        let mut transaction = begin_write(&self.pool).await?;
+        begin_action(&mut transaction, caller, ActionKind::StopTimer, now).await?;
+        let duration = self
+            .stop_on(&mut transaction, caller.user_id.as_deref(), &region, now)
+            .await?
+            .ok_or(RepositoryError::TimerNotRunning)?;
+        end_action(&mut transaction).await?;
+        transaction.commit().await?;
+        Ok(duration)
+    }
+
+    async fn toggle_timer(
+        &self,
+        region: Region,
+        caller: &Caller,
+    ) -> Result<ToggledTimer, RepositoryError> {
+        let now = Utc::now();
+        let mut transaction = begin_write(&self.pool).await?;
+        begin_action(&mut transaction, caller, ActionKind::ToggleTimer, now).await?;
+        let user_id = caller.user_id.as_deref();
+        let toggled = match self
+            .stop_on(&mut transaction, user_id, &region, now)
+            .await?
+        {
+            Some(duration) => ToggledTimer {
+                region,
+                running: false,
+                duration,
+                stopped: None,
+            },
+            None => {
+                let stopped = self
+                    .start_on(&mut transaction, user_id, &region, now)
+                    .await?;
+                ToggledTimer {
+                    region,
+                    running: true,
+                    duration: 0,
+                    stopped,
+                }
+            }
+        };
+        end_action(&mut transaction).await?;
+        transaction.commit().await?;
+        Ok(toggled)
+    }
+
+    async fn get_history_by_region(
+        &self,
+        user_id: Option<&str>,
+        region: Region,
+    ) -> Result<Vec<RegionHistory>, RepositoryError> {
+        let result: Vec<RegionHistory> = sqlx::query_as(
+            r#"
+            SELECT id, region, start_time, stop_time, duration, auto_stopped
+            FROM region_history
+            WHERE region = $1 AND user_id IS $2 AND deleted_at IS NULL
+            ORDER BY start_time DESC
             "#,
         )
         .bind(&region)
-        .bind(now)
-        .execute(&self.pool)
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    async fn get_history_between(
+        &self,
+        user_id: Option<&str>,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<RegionHistory>, RepositoryError> {
+        let result: Vec<RegionHistory> = sqlx::query_as(
+            r#"
+            SELECT id, region, start_time, stop_time, duration, auto_stopped
+            FROM region_history
+            WHERE start_time < $2 AND (stop_time IS NULL OR stop_time > $1) AND user_id IS $3
+              AND deleted_at IS NULL
+            ORDER BY start_time ASC
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    async fn currently_active_timer(
+        &self,
+        user_id: Option<&str>,
+    ) -> Result<CurrentlyActiveRegion, RepositoryError> {
+        let now = Utc::now();
+        let result: Option<(Region, DateTime<Utc>)> = sqlx::query_as(
+            r#"
+            SELECT region, start_time
+            FROM region_history
+            WHERE stop_time IS NULL AND user_id IS $1 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let on_break: Option<(i64,)> = sqlx::query_as(
+            "SELECT id FROM break_history WHERE stop_time IS NULL AND user_id IS $1",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let active_region = match result {
+            Option::None => CurrentlyActiveRegion {
+                on_break: on_break.is_some(),
+                ..CurrentlyActiveRegion::nothing_active()
+            },
+            Option::Some((region, start_time)) => CurrentlyActiveRegion {
+                region: Some(region),
+                duration: Some(millis_between(start_time, now)),
+                on_break: false,
+            },
+        };
+
+        Ok(active_region)
+    }
+
+    async fn get_running_timer(
+        &self,
+        user_id: Option<&str>,
+    ) -> Result<Option<RegionHistory>, RepositoryError> {
+        let result: Option<RegionHistory> = sqlx::query_as(
+            r#"
+            SELECT id, region, start_time, stop_time, duration, auto_stopped
+            FROM region_history
+            WHERE stop_time IS NULL AND user_id IS $1 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    async fn get_running_timers(&self) -> Result<Vec<OwnedEntry>, RepositoryError> {
+        let result: Vec<OwnedEntry> = sqlx::query_as(
+            r#"
+            SELECT id, region, start_time, stop_time, duration, auto_stopped, user_id
+            FROM region_history
+            WHERE stop_time IS NULL AND deleted_at IS NULL
+            ORDER BY start_time ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    async fn auto_stop_timer(
+        &self,
+        id: i64,
+        stop_time: DateTime<Utc>,
+    ) -> Result<Option<RegionHistory>, RepositoryError> {
+        let mut transaction = begin_write(&self.pool).await?;
+        let running: Option<(Region, DateTime<Utc>)> = sqlx::query_as(
+            r#"
+            SELECT region, start_time
+            FROM region_history
+            WHERE id = $1 AND stop_time IS NULL AND deleted_at IS NULL
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&mut *transaction)
+        .await?;
+        let Some((region, start_time)) = running else {
+            return Ok(None);
+        };
+        if start_time > stop_time {
+            return Ok(None);
+        }
+        begin_action(
+            &mut transaction,
+            &Caller::system(),
+            ActionKind::AutoStopTimer,
+            Utc::now(),
+        )
+        .await?;
+
+        // Marked first, so that the entries of further days are marked as well
+        sqlx::query("UPDATE region_history SET auto_stopped = TRUE WHERE id = $1")
+            .bind(id)
+            .execute(&mut *transaction)
+            .await?;
+        let duration = self
+            .stop_policy
+            .stop(&mut transaction, id, start_time, stop_time)
+            .await?;
+
+        end_action(&mut transaction).await?;
+        transaction.commit().await?;
+        Ok(Some(RegionHistory {
+            id,
+            region,
+            start_time,
+            stop_time: Some(stop_time),
+            duration: Some(duration),
+            auto_stopped: true,
+        }))
+    }
+
+    async fn get_auto_stopped_history(
+        &self,
+        user_id: Option<&str>,
+    ) -> Result<Vec<RegionHistory>, RepositoryError> {
+        let result: Vec<RegionHistory> = sqlx::query_as(
+            r#"
+            SELECT id, region, start_time, stop_time, duration, auto_stopped
+            FROM region_history
+            WHERE auto_stopped AND user_id IS $1 AND deleted_at IS NULL
+            ORDER BY start_time DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
         .await?;
 
+        Ok(result)
+    }
+
+    async fn remove_short_entries(
+        &self,
+        min_duration: MinDuration,
+        dry_run: bool,
+        caller: &Caller,
+    ) -> Result<Vec<RemovedEntry>, RepositoryError> {
+        let mut transaction = begin_write(&self.pool).await?;
+        begin_action(
+            &mut transaction,
+            caller,
+            ActionKind::CleanUpHistory,
+            Utc::now(),
+        )
+        .await?;
+        let candidates: Vec<(i64,)> = sqlx::query_as(
+            r#"
+            SELECT id
+            FROM region_history
+            WHERE stop_time IS NOT NULL AND duration < $1 AND user_id IS $2
+              AND deleted_at IS NULL
+            ORDER BY start_time ASC
+            "#,
+        )
+        .bind(min_duration.duration.num_milliseconds())
+        .bind(caller.user_id.as_deref())
+        .fetch_all(&mut *transaction)
+        .await?;
+
+        let mut removed = Vec::new();
+        for (id,) in candidates {
+            // Merging an earlier entry may have extended this one
+            let entry: RegionHistory = sqlx::query_as(
+                r#"
+                SELECT id, region, start_time, stop_time, duration, auto_stopped
+                FROM region_history
+                WHERE id = $1
+                "#,
+            )
+            .bind(id)
+            .fetch_one(&mut *transaction)
+            .await?;
+            let Some(stop_time) = entry
+                .stop_time
+                .filter(|stop_time| min_duration.is_too_short(entry.start_time, *stop_time))
+            else {
+                continue;
+            };
+            let time_zone = &self.stop_policy.time_zone;
+            if is_day_boundary(entry.start_time, time_zone) || is_day_boundary(stop_time, time_zone)
+            {
+                continue;
+            }
+
+            // Entries of a closed month stay as they are
+            let mut savepoint = transaction.begin().await?;
+            let merged_into = match remove_short_entry(
+                &mut savepoint,
+                id,
+                entry.start_time,
+                stop_time,
+                min_duration.action,
+            )
+            .await
+            {
+                Ok(merged_into) => merged_into,
+                Err(RepositoryError::PeriodLocked) => continue,
+                Err(e) => return Err(e),
+            };
+            savepoint.commit().await?;
+            removed.push(RemovedEntry { entry, merged_into });
+        }
+
+        // A dry run rolls back by dropping the transaction
+        if !dry_run {
+            end_action(&mut transaction).await?;
+            transaction.commit().await?;
+        }
+        Ok(removed)
+    }
+
+    async fn merge_contiguous_entries(
+        &self,
+        dry_run: bool,
+        caller: &Caller,
+    ) -> Result<Vec<RemovedEntry>, RepositoryError> {
+        let mut transaction = begin_write(&self.pool).await?;
+        begin_action(
+            &mut transaction,
+            caller,
+            ActionKind::MergeHistory,
+            Utc::now(),
+        )
+        .await?;
+        let mut merged = Vec::new();
+        let mut merged_until: Option<DateTime<Utc>> = None;
+
+        // Merging changes the following pairs, so they are looked up one by one
+        loop {
+            let pair: Option<(i64, DateTime<Utc>, i64, DateTime<Utc>)> = sqlx::query_as(
+                r#"
+                SELECT later.id, later.start_time, earlier.id, earlier.start_time
+                FROM region_history earlier
+                JOIN region_history later
+                  ON later.region = earlier.region AND later.start_time = earlier.stop_time
+                 AND later.user_id IS earlier.user_id AND later.id != earlier.id
+                WHERE earlier.user_id IS $2
+                  AND earlier.deleted_at IS NULL AND later.deleted_at IS NULL
+                  AND ($1 IS NULL OR later.start_time > $1)
+                ORDER BY later.start_time ASC
+                LIMIT 1
+                "#,
+            )
+            .bind(merged_until)
+            .bind(caller.user_id.as_deref())
+            .fetch_optional(&mut *transaction)
+            .await?;
+            let Some((later_id, boundary, earlier_id, earlier_start)) = pair else {
+                break;
+            };
+            merged_until = Some(boundary);
+
+            // Splitting on stop separates the days on purpose
+            if self.stop_policy.split_on_stop
+                && is_day_boundary(boundary, &self.stop_policy.time_zone)
+            {
+                continue;
+            }
+
+            // Entries of a closed month stay as they are
+            let mut savepoint = transaction.begin().await?;
+            let later =
+                match merge_entries(&mut savepoint, earlier_id, earlier_start, later_id).await {
+                    Ok(later) => later,
+                    Err(RepositoryError::PeriodLocked) => continue,
+                    Err(e) => return Err(e),
+                };
+            savepoint.commit().await?;
+
+            merged.push(RemovedEntry {
+                entry: later,
+                merged_into: Some(earlier_id),
+            });
+        }
+
+        if !dry_run {
+            end_action(&mut transaction).await?;
+            transaction.commit().await?;
+        }
+        Ok(merged)
+    }
+
+    async fn delete_entry(
+        &self,
+        id: i64,
+        caller: &Caller,
+    ) -> Result<DeletedEntry, RepositoryError> {
+        let now = Utc::now();
+        let mut transaction = begin_write(&self.pool).await?;
+        begin_action(&mut transaction, caller, ActionKind::DeleteEntry, now).await?;
+
+        // Entries in the trash never run, so they can be restored next to a
+        // running timer
+        let user_id = caller.user_id.as_deref();
+        let mut stopped = None;
+        if let Some((running_id, region, start_time)) =
+            running_entry(&mut transaction, user_id, None).await?
+            && running_id == id
+        {
+            self.stop_policy
+                .stop(&mut transaction, id, start_time, now)
+                .await?;
+            stopped = Some((region, start_time));
+        }
+
+        let deleted: Vec<DeletedEntry> = sqlx::query_as(
+            r#"
+            UPDATE region_history
+            SET deleted_at = $1
+            WHERE user_id IS $3 AND deleted_at IS NULL
+              AND (id = $2 OR (id > $2 AND region = $4 AND start_time >= $5 AND stop_time <= $1))
+            RETURNING id, region, start_time, stop_time, duration, auto_stopped, deleted_at
+            "#,
+        )
+        .bind(now)
+        .bind(id)
+        .bind(user_id)
+        .bind(stopped.as_ref().map(|(region, _)| region))
+        .bind(stopped.as_ref().map(|(_, start_time)| start_time))
+        .fetch_all(&mut *transaction)
+        .await?;
+        let deleted = match (
+            deleted.into_iter().find(|deleted| deleted.entry.id == id),
+            stopped,
+        ) {
+            (Some(deleted), _) => deleted,
+            // The stopped entry was too short and is already removed
+            (None, Some((region, start_time))) => DeletedEntry {
+                entry: RegionHistory {
+                    id,
+                    region,
+                    start_time,
+                    stop_time: Some(now),
+                    duration: Some(0),
+                    auto_stopped: false,
+                },
+                deleted_at: now,
+            },
+            (None, None) => return Err(RepositoryError::NotFound),
+        };
+
+        end_action(&mut transaction).await?;
+        transaction.commit().await?;
+        Ok(deleted)
+    }
+
+    async fn get_deleted_history(
+        &self,
+        user_id: Option<&str>,
+    ) -> Result<Vec<DeletedEntry>, RepositoryError> {
+        let result: Vec<DeletedEntry> = sqlx::query_as(
+            r#"
+            SELECT id, region, start_time, stop_time, duration, auto_stopped, deleted_at
+            FROM region_history
+            WHERE deleted_at IS NOT NULL AND user_id IS $1
+            ORDER BY deleted_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    async fn restore_entry(
+        &self,
+        id: i64,
+        caller: &Caller,
+    ) -> Result<RegionHistory, RepositoryError> {
+        let mut transaction = begin_write(&self.pool).await?;
+        begin_action(
+            &mut transaction,
+            caller,
+            ActionKind::RestoreEntry,
+            Utc::now(),
+        )
+        .await?;
+
+        let restored: RegionHistory = sqlx::query_as(
+            r#"
+            UPDATE region_history
+            SET deleted_at = NULL
+            WHERE id = $1 AND user_id IS $2 AND deleted_at IS NOT NULL
+            RETURNING id, region, start_time, stop_time, duration, auto_stopped
+            "#,
+        )
+        .bind(id)
+        .bind(caller.user_id.as_deref())
+        .fetch_optional(&mut *transaction)
+        .await?
+        .ok_or(RepositoryError::NotFound)?;
+
+        end_action(&mut transaction).await?;
+        transaction.commit().await?;
+        Ok(restored)
+    }
+
+    async fn purge_deleted_entries(
+        &self,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<u64, RepositoryError> {
+        let result = sqlx::query("DELETE FROM region_history WHERE deleted_at < $1")
+            .bind(deleted_before)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test]
+    async fn test_start_timer(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteRegionRepository::new(pool);
+
+        // When
+        let result = repo.start_timer(Region::Ac1, &Caller::default()).await;
+        assert!(result.is_ok(), "Starting timer should succeed");
+
+        // Then
+        let history = repo
+            .get_history_by_region(None, Region::Ac1)
+            .await
+            .expect("History should succeed");
+        assert_eq!(history.len(), 1, "History should contain one entry");
+        assert_eq!(history[0].region, Region::Ac1, "Region should be Ac1");
+        assert!(history[0].stop_time.is_none(), "Stop time should be None");
+        assert!(history[0].duration.is_none(), "Duration should be None");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_start_while_timer_for_same_region_already_running(
+        pool: SqlitePool,
+    ) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteRegionRepository::new(pool);
+        repo.start_timer(Region::Ac1, &Caller::default())
+            .await
+            .unwrap();
+
+        // When
+        let result = repo.start_timer(Region::Ac1, &Caller::default()).await;
+
+        // Then
+        assert!(result.is_ok(), "Starting the same timer should succeed");
+
+        // The previous timer should have stopped
+        let ac1_history = repo
+            .get_history_by_region(None, Region::Ac1)
+            .await
+            .expect("History should succeed");
+        assert_eq!(
+            ac1_history.len(),
+            2,
+            "Ac1 history should contain two entries, one is stopped and one started"
+        );
+
+        // The timers are sorted descending. The "first one" is the currently running
+        // timer and the "second one" is the stopped timer
+        assert!(
+            ac1_history[0].stop_time.is_none(),
+            "First timer should be running"
+        );
+        assert!(
+            ac1_history[0].duration.is_none(),
+            "First timer should not have a duration"
+        );
+        assert!(
+            ac1_history[1].stop_time.is_some(),
+            "Second timer should be stopped"
+        );
+        assert!(
+            ac1_history[1].duration.is_some(),
+            "Second timer should have a duration"
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_start_while_other_timer_already_running(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteRegionRepository::new(pool);
+        repo.start_timer(Region::Ac1, &Caller::default())
+            .await
+            .expect("First timer should start normally");
+
+        // When
+        let result = repo.start_timer(Region::Ac2, &Caller::default()).await;
+        assert!(result.is_ok(), "Starting another timer should succeed");
+
+        // Then
+        // Verify the previous timer (Ac1) was stopped
+        let ac1_history = repo
+            .get_history_by_region(None, Region::Ac1)
+            .await
+            .expect("History should succeed");
+        assert_eq!(ac1_history.len(), 1, "Ac1 history should contain one entry");
+        assert!(
+            ac1_history[0].stop_time.is_some(),
+            "Ac1 timer should be stopped"
+        );
+        assert!(
+            ac1_history[0].duration.is_some(),
+            "Ac1 timer should have a duration"
+        );
+
+        // Verify the new timer (Ac2) is running
+        let ac2_history = repo
+            .get_history_by_region(None, Region::Ac2)
+            .await
+            .expect("History should succeed");
+        assert_eq!(ac2_history.len(), 1, "Ac2 history should contain one entry");
+        assert!(
+            ac2_history[0].stop_time.is_none(),
+            "Ac2 timer should be running"
+        );
+        assert!(
+            ac2_history[0].duration.is_none(),
+            "Ac2 timer should have no duration"
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_users_keep_their_own_timers(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteRegionRepository::new(pool);
+        let worker = |user_id: &str| Caller {
+            client_id: "kiosk".to_string(),
+            user_id: Some(user_id.to_string()),
+            device_id: None,
+        };
+        repo.start_timer(Region::Ac1, &worker("anna"))
+            .await
+            .expect("Anna's timer should start");
+
+        // When
+        repo.start_timer(Region::Ac2, &worker("ben"))
+            .await
+            .expect("Ben's timer should start");
+
+        // Then
+        let anna = repo.get_running_timer(Some("anna")).await.unwrap();
+        let ben = repo.get_running_timer(Some("ben")).await.unwrap();
+        assert_eq!(anna.map(|entry| entry.region), Some(Region::Ac1));
+        assert_eq!(ben.map(|entry| entry.region), Some(Region::Ac2));
+        assert!(
+            repo.get_running_timer(None).await.unwrap().is_none(),
+            "The shared history has no running timer"
+        );
+        assert_eq!(repo.get_running_timers().await.unwrap().len(), 2);
+        assert!(
+            matches!(
+                repo.stop_timer(Region::Ac1, &worker("ben")).await,
+                Err(RepositoryError::TimerNotRunning)
+            ),
+            "Ben can't stop Anna's timer"
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_stop_timer(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteRegionRepository::new(pool);
+        repo.start_timer(Region::Ac3, &Caller::default())
+            .await
+            .expect("Starting timer should succeed");
+
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        // When
+        let duration = repo
+            .stop_timer(Region::Ac3, &Caller::default())
+            .await
+            .expect("Stopping timer should not fail");
+
+        // Then
+        let now = Utc::now();
+        assert!(
+            duration >= 50,
+            "Duration should be at least 50 milliseconds"
+        );
+
+        // Verify the timer was stopped
+        let history = repo
+            .get_history_by_region(None, Region::Ac3)
+            .await
+            .expect("History should succeed");
+        assert_eq!(history.len(), 1, "History should contain one entry");
+        assert_eq!(history[0].region, Region::Ac3, "Region should be Ac3");
+        assert!(history[0].stop_time.is_some(), "Stop time should be set");
+        assert!(
+            history[0].stop_time.unwrap() < now,
+            "Stop time should before the current time"
+        );
+        assert!(
+            history[0].stop_time.unwrap() > history[0].start_time,
+            "Stop time should before the start time"
+        );
+        assert_eq!(history[0].duration, Some(duration), "Duration should match");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_stop_timer_not_running(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteRegionRepository::new(pool);
+
+        // When
+        let result = repo.stop_timer(Region::Ac1, &Caller::default()).await;
+
+        // Then
+        assert!(
+            matches!(result, Err(RepositoryError::TimerNotRunning)),
+            "Should fail with NotRunning error"
+        );
+
+        // History should stay empty
+        let history = repo
+            .get_history_by_region(None, Region::Ac1)
+            .await
+            .expect("History should succeed");
+        assert!(history.is_empty(), "History should be empty");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_concurrent_starts_leave_one_running_timer(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteRegionRepository::new(pool);
+        let caller = Caller::default();
+
+        // When
+        let results = tokio::join!(
+            repo.start_timer(Region::Aa1, &caller),
+            repo.start_timer(Region::Aa2, &caller),
+            repo.start_timer(Region::Ac1, &caller),
+            repo.start_timer(Region::Ac2, &caller),
+        );
+
+        // Then
+        assert!(results.0.is_ok() && results.1.is_ok() && results.2.is_ok() && results.3.is_ok());
+        let running: Vec<(i64,)> =
+            sqlx::query_as("SELECT id FROM region_history WHERE stop_time IS NULL")
+                .fetch_all(&repo.pool)
+                .await?;
+        assert_eq!(running.len(), 1, "Only one timer should be running");
+        let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM region_history")
+            .fetch_one(&repo.pool)
+            .await?;
+        assert_eq!(count, 4, "Every start should create an entry");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_database_rejects_second_running_timer(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let insert = "INSERT INTO region_history (region, start_time) VALUES ('ac1', $1)";
+        sqlx::query(insert)
+            .bind("2025-10-01T08:00:00+00:00")
+            .execute(&pool)
+            .await?;
+
+        // When
+        let result = sqlx::query(insert)
+            .bind("2025-10-01T09:00:00+00:00")
+            .execute(&pool)
+            .await;
+
+        // Then
+        assert!(
+            matches!(result, Err(sqlx::Error::Database(ref e)) if e.is_unique_violation()),
+            "A second running timer should violate the unique index"
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_toggle_timer(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteRegionRepository::new(pool);
+        repo.start_timer(Region::Aa1, &Caller::default())
+            .await
+            .unwrap();
+
+        // When
+        let started = repo
+            .toggle_timer(Region::Ac1, &Caller::default())
+            .await
+            .unwrap();
+        let stopped = repo
+            .toggle_timer(Region::Ac1, &Caller::default())
+            .await
+            .unwrap();
+
+        // Then
+        assert!(started.running, "First toggle should start the timer");
+        assert!(!stopped.running, "Second toggle should stop the timer");
+        assert_eq!(stopped.region, Region::Ac1);
+        assert!(
+            repo.get_running_timer(None).await.unwrap().is_none(),
+            "Starting Ac1 should have stopped Aa1"
+        );
+        let history = repo.get_history_by_region(None, Region::Ac1).await.unwrap();
+        assert_eq!(history.len(), 1, "Toggling twice should create one entry");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_concurrent_toggles_create_one_entry(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteRegionRepository::new(pool);
+        let caller = Caller::default();
+
+        // When
+        let (first, second) = tokio::join!(
+            repo.toggle_timer(Region::Ac1, &caller),
+            repo.toggle_timer(Region::Ac1, &caller)
+        );
+
+        // Then
+        let (first, second) = (first.unwrap(), second.unwrap());
+        assert_ne!(
+            first.running, second.running,
+            "One toggle should start and the other one stop the timer"
+        );
+        let history = repo.get_history_by_region(None, Region::Ac1).await.unwrap();
+        assert_eq!(history.len(), 1, "A double tap should create one entry");
+        assert!(
+            history[0].stop_time.is_some(),
+            "The entry should be stopped"
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_get_history_by_region(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteRegionRepository::new(pool);
+
+        repo.start_timer(Region::Aa1, &Caller::default())
+            .await
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        repo.stop_timer(Region::Aa1, &Caller::default())
+            .await
+            .unwrap();
+
+        repo.start_timer(Region::Aa1, &Caller::default())
+            .await
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        repo.stop_timer(Region::Aa1, &Caller::default())
+            .await
+            .unwrap();
+
+        // When
+        let history = repo
+            .get_history_by_region(None, Region::Aa1)
+            .await
+            .expect("Fetching the history should succeed");
+
+        // Then
+        assert_eq!(history.len(), 2, "History should contain two entries");
+        assert_eq!(history[0].region, Region::Aa1, "Region should be Aa1");
+        assert!(
+            history[0].stop_time.is_some(),
+            "First entry should be stopped"
+        );
+        assert!(
+            history[0].duration.is_some(),
+            "First entry should have duration"
+        );
+        assert!(
+            history[0].start_time > history[1].start_time,
+            "Entries should be ordered by start_time DESC"
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_get_history_between(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        sqlx::query(
+            r#"
+            INSERT INTO region_history (region, start_time, stop_time, duration)
+            VALUES ('ac1', '2025-10-01T07:00:00+00:00', '2025-10-01T08:00:00+00:00', 3600000),
+                   ('ac2', '2025-10-01T08:00:00+00:00', '2025-10-01T10:00:00+00:00', 7200000),
+                   ('aa1', '2025-10-01T11:00:00+00:00', NULL, NULL)
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+        let repo = SqliteRegionRepository::new(pool);
+
+        // When
+        let from = "2025-10-01T09:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let to = "2025-10-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let history = repo
+            .get_history_between(None, from, to)
+            .await
+            .expect("Fetching the history should succeed");
+
+        // Then
+        assert_eq!(history.len(), 2, "Only overlapping entries are returned");
+        assert_eq!(history[0].region, Region::Ac2, "Entries are ordered ASC");
+        assert_eq!(history[1].region, Region::Aa1, "Running entry is returned");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_stop_timer_splits_entry_at_midnight(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let start_time = Utc::now() - chrono::TimeDelta::days(1);
+        sqlx::query("INSERT INTO region_history (region, start_time) VALUES ('ac1', $1)")
+            .bind(start_time)
+            .execute(&pool)
+            .await?;
+        let repo = SqliteRegionRepository::new(pool).with_split_on_stop(true);
+
+        // When
+        let duration = repo
+            .stop_timer(Region::Ac1, &Caller::default())
+            .await
+            .expect("Stopping timer should not fail");
+
+        // Then
+        let history = repo.get_history_by_region(None, Region::Ac1).await.unwrap();
+        assert_eq!(history.len(), 2, "The entry should be split in two days");
+        assert_eq!(
+            history[1].start_time, start_time,
+            "First part should keep its start"
+        );
+        assert_eq!(
+            history[1].stop_time,
+            Some(history[0].start_time),
+            "Second part should start where the first one stops"
+        );
+        assert_eq!(
+            history[0].start_time.time(),
+            chrono::NaiveTime::MIN,
+            "Second part should start at midnight"
+        );
+        let total: i64 = history.iter().filter_map(|entry| entry.duration).sum();
+        assert!(
+            (total - duration).abs() <= 1,
+            "Durations should add up to the total duration"
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_start_other_region_splits_entry_at_midnight(
+        pool: SqlitePool,
+    ) -> sqlx::Result<()> {
+        // Given
+        let start_time = Utc::now() - chrono::TimeDelta::days(1);
+        sqlx::query("INSERT INTO region_history (region, start_time) VALUES ('ac1', $1)")
+            .bind(start_time)
+            .execute(&pool)
+            .await?;
+        let repo = SqliteRegionRepository::new(pool).with_split_on_stop(true);
+
+        // When
+        repo.start_timer(Region::Ac2, &Caller::default())
+            .await
+            .expect("Starting timer should not fail");
+
+        // Then
+        let history = repo.get_history_by_region(None, Region::Ac1).await.unwrap();
+        assert_eq!(history.len(), 2, "The entry should be split in two days");
+        assert_eq!(history[0].start_time.time(), chrono::NaiveTime::MIN);
+        assert!(history.iter().all(|entry| entry.stop_time.is_some()));
+
+        Ok(())
+    }
+
+    fn min_duration(action: ShortEntryAction) -> MinDuration {
+        MinDuration {
+            duration: chrono::TimeDelta::seconds(5),
+            action,
+        }
+    }
+
+    /// Inserts an hour on Ac1 followed by an accidental tap of two seconds on
+    /// Ac2.
+    async fn insert_accidental_tap(pool: &SqlitePool) -> sqlx::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO region_history (region, start_time, stop_time, duration)
+            VALUES ('ac1', '2025-10-01T08:00:00+00:00', '2025-10-01T09:00:00+00:00', 3600000),
+                   ('ac2', '2025-10-01T09:00:00+00:00', '2025-10-01T09:00:02+00:00', 2000)
+            "#,
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_stop_timer_discards_short_entry(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteRegionRepository::new(pool)
+            .with_min_duration(Some(min_duration(ShortEntryAction::Discard)));
+        repo.start_timer(Region::Ac1, &Caller::default())
+            .await
+            .unwrap();
+
+        // When
+        let duration = repo
+            .stop_timer(Region::Ac1, &Caller::default())
+            .await
+            .expect("Stopping timer should not fail");
+
+        // Then
+        assert_eq!(duration, 0, "A discarded entry has no duration");
+        let history = repo.get_history_by_region(None, Region::Ac1).await.unwrap();
+        assert!(history.is_empty(), "The short entry should be discarded");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_remove_short_entries_merges_into_previous_entry(
+        pool: SqlitePool,
+    ) -> sqlx::Result<()> {
+        // Given
+        insert_accidental_tap(&pool).await?;
+        let repo = SqliteRegionRepository::new(pool);
+
+        // When
+        let removed = repo
+            .remove_short_entries(
+                min_duration(ShortEntryAction::Merge),
+                false,
+                &Caller::default(),
+            )
+            .await
+            .expect("Removing short entries should succeed");
+
+        // Then
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].entry.region, Region::Ac2);
+        let history = repo.get_history_by_region(None, Region::Ac1).await.unwrap();
+        assert_eq!(removed[0].merged_into, Some(history[0].id));
+        assert_eq!(
+            history[0].stop_time,
+            Some("2025-10-01T09:00:02Z".parse().unwrap()),
+            "The previous entry should be extended"
+        );
+        assert_eq!(history[0].duration, Some(3_602_000));
+        let ac2_history = repo.get_history_by_region(None, Region::Ac2).await.unwrap();
+        assert!(ac2_history.is_empty(), "The short entry should be removed");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_remove_short_entries_keeps_other_users_entries(
+        pool: SqlitePool,
+    ) -> sqlx::Result<()> {
+        // Given: the accidental tap belongs to the shared history
+        insert_accidental_tap(&pool).await?;
+        let repo = SqliteRegionRepository::new(pool);
+        let anna = Caller {
+            client_id: "phone".to_string(),
+            user_id: Some("anna".to_string()),
+            device_id: None,
+        };
+
+        // When
+        let removed = repo
+            .remove_short_entries(min_duration(ShortEntryAction::Merge), false, &anna)
+            .await
+            .expect("Removing short entries should succeed");
+
+        // Then
+        assert!(removed.is_empty(), "Only Anna's entries should be removed");
+        let ac2_history = repo.get_history_by_region(None, Region::Ac2).await.unwrap();
+        assert_eq!(ac2_history.len(), 1);
+
         Ok(())
     }
 
-    async fn stop_timer(&self, region: Region) -> Result<i64, RepositoryError> {
-        let now = Utc::now();
-        let result: Option<(DateTime<Utc>,)> = sqlx::query_as(
+    #[sqlx::test]
+    async fn test_remove_short_entries_keeps_parts_of_split_entries(
+        pool: SqlitePool,
+    ) -> sqlx::Result<()> {
+        // Given: an entry that was split two seconds before midnight
+        sqlx::query(
             r#"
-            UPDATE region_history
-            SET stop_time = $1,
-                duration = (strftime('%s', $1) - strftime('%s', start_time))
-            WHERE region = $2 AND stop_time IS NULL
-            RETURNING start_time
+            INSERT INTO region_history (region, start_time, stop_time, duration)
+            VALUES ('ac1', '2025-10-01T22:00:00+00:00', '2025-10-02T00:00:00+00:00', 7200000),
+                   ('ac1', '2025-10-02T00:00:00+00:00', '2025-10-02T00:00:02+00:00', 2000)
             "#,
         )
-        .bind(now)
-        .bind(&region)
-        .fetch_optional(&self.pool)
+        .execute(&pool)
         .await?;
+        let repo = SqliteRegionRepository::new(pool);
 
-        match result {
-            Some((start_time,)) => Ok(now.signed_duration_since(start_time).num_seconds()),
-            None => Err(RepositoryError::TimerNotRunning),
-        }
+        // When
+        let removed = repo
+            .remove_short_entries(
+                min_duration(ShortEntryAction::Discard),
+                false,
+                &Caller::default(),
+            )
+            .await
+            .expect("Removing short entries should succeed");
+
+        // Then
+        assert!(removed.is_empty(), "Worked time should not be removed");
+        let history = repo.get_history_by_region(None, Region::Ac1).await.unwrap();
+        assert_eq!(history.len(), 2);
+
+        Ok(())
     }
 
-    async fn get_history_by_region(
-        &self,
-        region: Region,
-    ) -> Result<Vec<RegionHistory>, RepositoryError> {
-        let result: Vec<RegionHistory> = sqlx::query_as(
+    /// Closes October 2025.
+    async fn lock_october(pool: &SqlitePool) -> sqlx::Result<()> {
+        sqlx::query(
             r#"
-            SELECT region, start_time, stop_time, duration
-            FROM region_history
-            WHERE region = $1
-            ORDER BY start_time DESC
+            INSERT INTO period_locks (year, month, starts_at, ends_at, closed_at)
+            VALUES (2025, 10, '2025-10-01T00:00:00+00:00', '2025-11-01T00:00:00+00:00',
+                    '2025-11-02T00:00:00+00:00')
             "#,
         )
-        .bind(&region)
-        .fetch_all(&self.pool)
+        .execute(pool)
         .await?;
-
-        Ok(result)
+        Ok(())
     }
 
-    async fn currently_active_timer(&self) -> Result<CurrentlyActiveRegion, RepositoryError> {
-        let now = Utc::now();
-        let result: Option<(Region, DateTime<Utc>)> = sqlx::query_as(
+    #[sqlx::test]
+    async fn test_remove_short_entries_skips_closed_period(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given: a short entry in the closed October and one in November
+        insert_accidental_tap(&pool).await?;
+        sqlx::query(
             r#"
-            SELECT region, start_time
-            FROM region_history
-            WHERE stop_time is NULL
-        "#,
+            INSERT INTO region_history (region, start_time, stop_time, duration)
+            VALUES ('ac2', '2025-11-03T09:00:00+00:00', '2025-11-03T09:00:02+00:00', 2000)
+            "#,
         )
-        .fetch_optional(&self.pool)
+        .execute(&pool)
         .await?;
+        lock_october(&pool).await?;
+        let repo = SqliteRegionRepository::new(pool);
 
-        let active_region = match result {
-            Option::None => CurrentlyActiveRegion::nothing_active(),
-            Option::Some((region, start_time)) => {
-                let difference = now - start_time;
-                let seconds = difference.num_seconds();
-
-                CurrentlyActiveRegion {
-                    region: Some(region),
-                    duration: Some(seconds),
-                }
-            }
-        };
+        // When
+        let removed = repo
+            .remove_short_entries(
+                min_duration(ShortEntryAction::Merge),
+                false,
+                &Caller::default(),
+            )
+            .await
+            .expect("Cleaning up should succeed");
 
-        Ok(active_region)
-    }
-}
+        // Then
+        assert_eq!(removed.len(), 1, "Only the entry in November is removed");
+        assert_eq!(removed[0].entry.id, 3);
+        let history = repo.get_history_by_region(None, Region::Ac2).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].id, 2, "The closed month is unchanged");
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        Ok(())
+    }
 
     #[sqlx::test]
-    async fn test_start_timer(pool: SqlitePool) -> sqlx::Result<()> {
+    async fn test_remove_short_entries_dry_run(pool: SqlitePool) -> sqlx::Result<()> {
         // Given
+        insert_accidental_tap(&pool).await?;
         let repo = SqliteRegionRepository::new(pool);
 
         // When
-        let result = repo.start_timer(Region::Ac1).await;
-        assert!(result.is_ok(), "Starting timer should succeed");
+        let removed = repo
+            .remove_short_entries(
+                min_duration(ShortEntryAction::Discard),
+                true,
+                &Caller::default(),
+            )
+            .await
+            .expect("A dry run should succeed");
 
         // Then
-        let history = repo
-            .get_history_by_region(Region::Ac1)
+        assert_eq!(removed.len(), 1, "The short entry should be reported");
+        assert_eq!(removed[0].merged_into, None);
+        let history = repo.get_history_by_region(None, Region::Ac2).await.unwrap();
+        assert_eq!(history.len(), 1, "A dry run should not change the history");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_restart_keeps_running_entry(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteRegionRepository::new(pool).with_restart_behavior(RestartBehavior::Keep);
+        repo.start_timer(Region::Ac1, &Caller::default())
             .await
-            .expect("History should succeed");
-        assert_eq!(history.len(), 1, "History should contain one entry");
-        assert_eq!(history[0].region, Region::Ac1, "Region should be Ac1");
-        assert!(history[0].stop_time.is_none(), "Stop time should be None");
-        assert!(history[0].duration.is_none(), "Duration should be None");
+            .unwrap();
+        let running = repo.get_running_timer(None).await.unwrap().unwrap();
+
+        // When
+        repo.start_timer(Region::Ac1, &Caller::default())
+            .await
+            .expect("Restarting the region should succeed");
+
+        // Then
+        let history = repo.get_history_by_region(None, Region::Ac1).await.unwrap();
+        assert_eq!(history.len(), 1, "No second entry should be started");
+        assert_eq!(history[0].id, running.id);
+        assert_eq!(history[0].stop_time, None, "The entry should keep running");
 
         Ok(())
     }
 
     #[sqlx::test]
-    async fn test_start_while_timer_for_same_region_already_running(
-        pool: SqlitePool,
-    ) -> sqlx::Result<()> {
+    async fn test_restart_merges_into_running_entry(pool: SqlitePool) -> sqlx::Result<()> {
         // Given
-        let repo = SqliteRegionRepository::new(pool);
-        repo.start_timer(Region::Ac1).await.unwrap();
+        let repo = SqliteRegionRepository::new(pool).with_restart_behavior(RestartBehavior::Merge);
+        repo.start_timer(Region::Ac1, &Caller::default())
+            .await
+            .unwrap();
+        let running = repo.get_running_timer(None).await.unwrap().unwrap();
 
         // When
-        let result = repo.start_timer(Region::Ac1).await;
+        repo.start_timer(Region::Ac1, &Caller::default())
+            .await
+            .expect("Restarting the region should succeed");
 
         // Then
-        assert!(result.is_ok(), "Starting the same timer should succeed");
+        let history = repo.get_history_by_region(None, Region::Ac1).await.unwrap();
+        assert_eq!(history.len(), 1, "The entry should be continued");
+        assert_eq!(history[0].start_time, running.start_time);
+        assert_eq!(history[0].stop_time, None);
+        assert_eq!(history[0].duration, None);
 
-        // The previous timer should have stopped
-        let ac1_history = repo
-            .get_history_by_region(Region::Ac1)
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_merge_contiguous_entries(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given: Ac1 was restarted twice, then Ac2 followed
+        sqlx::query(
+            r#"
+            INSERT INTO region_history (region, start_time, stop_time, duration)
+            VALUES ('ac1', '2025-10-01T08:00:00+00:00', '2025-10-01T09:00:00+00:00', 3600000),
+                   ('ac1', '2025-10-01T09:00:00+00:00', '2025-10-01T10:00:00+00:00', 3600000),
+                   ('ac1', '2025-10-01T10:00:00+00:00', '2025-10-01T11:00:00+00:00', 3600000),
+                   ('ac2', '2025-10-01T11:00:00+00:00', NULL, NULL)
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+        let repo = SqliteRegionRepository::new(pool);
+
+        // When
+        let merged = repo
+            .merge_contiguous_entries(false, &Caller::default())
             .await
-            .expect("History should succeed");
-        assert_eq!(
-            ac1_history.len(),
-            2,
-            "Ac1 history should contain two entries, one is stopped and one started"
-        );
+            .expect("Merging should succeed");
 
-        // The timers are sorted descending. The "first one" is the currently running
-        // timer and the "second one" is the stopped timer
-        assert!(
-            ac1_history[0].stop_time.is_none(),
-            "First timer should be running"
-        );
-        assert!(
-            ac1_history[0].duration.is_none(),
-            "First timer should not have a duration"
-        );
+        // Then
+        let history = repo.get_history_by_region(None, Region::Ac1).await.unwrap();
+        assert_eq!(history.len(), 1, "The Ac1 entries should be merged");
+        assert_eq!(merged.len(), 2);
         assert!(
-            ac1_history[1].stop_time.is_some(),
-            "Second timer should be stopped"
+            merged
+                .iter()
+                .all(|entry| entry.merged_into == Some(history[0].id)),
+            "Both later entries should be merged into the first one"
         );
-        assert!(
-            ac1_history[1].duration.is_some(),
-            "Second timer should have a duration"
+        assert_eq!(
+            history[0].stop_time,
+            Some("2025-10-01T11:00:00Z".parse().unwrap())
         );
+        assert_eq!(history[0].duration, Some(3 * 3_600_000));
+        let ac2_history = repo.get_history_by_region(None, Region::Ac2).await.unwrap();
+        assert_eq!(ac2_history.len(), 1, "Other regions should not be merged");
 
         Ok(())
     }
 
     #[sqlx::test]
-    async fn test_start_while_other_timer_already_running(pool: SqlitePool) -> sqlx::Result<()> {
-        // Given
+    async fn test_merge_contiguous_entries_of_caller_only(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given: Anna's entry ends when Ben's starts, and Ben restarted Ac1
+        sqlx::query(
+            r#"
+            INSERT INTO region_history (region, start_time, stop_time, duration, user_id)
+            VALUES ('ac1', '2025-10-01T08:00:00+00:00', '2025-10-01T09:00:00+00:00', 3600000, 'anna'),
+                   ('ac1', '2025-10-01T09:00:00+00:00', '2025-10-01T10:00:00+00:00', 3600000, 'ben'),
+                   ('ac1', '2025-10-01T10:00:00+00:00', '2025-10-01T11:00:00+00:00', 3600000, 'ben')
+            "#,
+        )
+        .execute(&pool)
+        .await?;
         let repo = SqliteRegionRepository::new(pool);
-        repo.start_timer(Region::Ac1)
-            .await
-            .expect("First timer should start normally");
+        let anna = Caller {
+            client_id: "phone".to_string(),
+            user_id: Some("anna".to_string()),
+            device_id: None,
+        };
 
         // When
-        let result = repo.start_timer(Region::Ac2).await;
-        assert!(result.is_ok(), "Starting another timer should succeed");
+        let merged = repo
+            .merge_contiguous_entries(false, &anna)
+            .await
+            .expect("Merging should succeed");
 
         // Then
-        // Verify the previous timer (Ac1) was stopped
-        let ac1_history = repo
-            .get_history_by_region(Region::Ac1)
+        assert!(merged.is_empty(), "Ben's entries are not Anna's to merge");
+        let anna = repo
+            .get_history_by_region(Some("anna"), Region::Ac1)
             .await
-            .expect("History should succeed");
-        assert_eq!(ac1_history.len(), 1, "Ac1 history should contain one entry");
-        assert!(
-            ac1_history[0].stop_time.is_some(),
-            "Ac1 timer should be stopped"
-        );
-        assert!(
-            ac1_history[0].duration.is_some(),
-            "Ac1 timer should have a duration"
-        );
+            .unwrap();
+        let ben = repo
+            .get_history_by_region(Some("ben"), Region::Ac1)
+            .await
+            .unwrap();
+        assert_eq!(anna.len(), 1);
+        assert_eq!(ben.len(), 2);
 
-        // Verify the new timer (Ac2) is running
-        let ac2_history = repo
-            .get_history_by_region(Region::Ac2)
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_merge_contiguous_entries_keeps_days_split(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given: an entry that was split at midnight
+        sqlx::query(
+            r#"
+            INSERT INTO region_history (region, start_time, stop_time, duration)
+            VALUES ('ac1', '2025-10-01T22:00:00+00:00', '2025-10-02T00:00:00+00:00', 7200000),
+                   ('ac1', '2025-10-02T00:00:00+00:00', '2025-10-02T02:00:00+00:00', 7200000),
+                   ('ac1', '2025-10-02T02:00:00+00:00', '2025-10-02T03:00:00+00:00', 3600000)
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+        let repo = SqliteRegionRepository::new(pool).with_split_on_stop(true);
+
+        // When
+        let merged = repo
+            .merge_contiguous_entries(false, &Caller::default())
             .await
-            .expect("History should succeed");
-        assert_eq!(ac2_history.len(), 1, "Ac2 history should contain one entry");
-        assert!(
-            ac2_history[0].stop_time.is_none(),
-            "Ac2 timer should be running"
+            .expect("Merging should succeed");
+
+        // Then
+        assert_eq!(merged.len(), 1, "Only the entries of one day are merged");
+        let history = repo.get_history_by_region(None, Region::Ac1).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(
+            history[0].start_time,
+            "2025-10-02T00:00:00Z".parse::<DateTime<Utc>>().unwrap()
         );
-        assert!(
-            ac2_history[0].duration.is_none(),
-            "Ac2 timer should have no duration"
+        assert_eq!(history[0].duration, Some(3 * 3_600_000));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_merge_contiguous_entries_skips_closed_period(
+        pool: SqlitePool,
+    ) -> sqlx::Result<()> {
+        // Given: contiguous entries in the closed October and in November
+        sqlx::query(
+            r#"
+            INSERT INTO region_history (region, start_time, stop_time, duration)
+            VALUES ('ac1', '2025-10-01T08:00:00+00:00', '2025-10-01T09:00:00+00:00', 3600000),
+                   ('ac1', '2025-10-01T09:00:00+00:00', '2025-10-01T10:00:00+00:00', 3600000),
+                   ('ac1', '2025-11-03T08:00:00+00:00', '2025-11-03T09:00:00+00:00', 3600000),
+                   ('ac1', '2025-11-03T09:00:00+00:00', '2025-11-03T10:00:00+00:00', 3600000)
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+        lock_october(&pool).await?;
+        let repo = SqliteRegionRepository::new(pool);
+
+        // When
+        let merged = repo
+            .merge_contiguous_entries(false, &Caller::default())
+            .await
+            .expect("Merging should succeed");
+
+        // Then
+        assert_eq!(merged.len(), 1, "Only the entries in November are merged");
+        assert_eq!(merged[0].entry.id, 4);
+        assert_eq!(
+            repo.get_history_by_region(None, Region::Ac1)
+                .await
+                .unwrap()
+                .len(),
+            3
         );
 
         Ok(())
     }
 
     #[sqlx::test]
-    async fn test_stop_timer(pool: SqlitePool) -> sqlx::Result<()> {
+    async fn test_delete_and_restore_entry(pool: SqlitePool) -> sqlx::Result<()> {
         // Given
         let repo = SqliteRegionRepository::new(pool);
-        repo.start_timer(Region::Ac3)
+        repo.start_timer(Region::Ac1, &Caller::default())
             .await
-            .expect("Starting timer should succeed");
-
-        // We need to wait for more than 1s, as the duration calculated by sqlite is in
-        // seconds
-        tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+            .unwrap();
+        let running = repo.get_running_timer(None).await.unwrap().unwrap();
 
         // When
-        let duration = repo
-            .stop_timer(Region::Ac3)
+        let deleted = repo
+            .delete_entry(running.id, &Caller::default())
             .await
-            .expect("Stopping timer should not fail");
+            .expect("Deleting should succeed");
 
         // Then
-        let now = Utc::now();
-        assert!(duration >= 1, "Duration should be positive");
-
-        // Verify the timer was stopped
-        let history = repo
-            .get_history_by_region(Region::Ac3)
-            .await
-            .expect("History should succeed");
-        assert_eq!(history.len(), 1, "History should contain one entry");
-        assert_eq!(history[0].region, Region::Ac3, "Region should be Ac3");
-        assert!(history[0].stop_time.is_some(), "Stop time should be set");
         assert!(
-            history[0].stop_time.unwrap() < now,
-            "Stop time should before the current time"
+            deleted.entry.stop_time.is_some(),
+            "The entry should be stopped"
         );
         assert!(
-            history[0].stop_time.unwrap() > history[0].start_time,
-            "Stop time should before the start time"
+            repo.get_history_by_region(None, Region::Ac1)
+                .await
+                .unwrap()
+                .is_empty()
         );
-        assert_eq!(history[0].duration, Some(duration), "Duration should match");
+        assert!(repo.get_running_timer(None).await.unwrap().is_none());
+        let trash = repo.get_deleted_history(None).await.unwrap();
+        assert_eq!(trash.len(), 1, "The entry should be in the trash");
+        assert!(matches!(
+            repo.delete_entry(running.id, &Caller::default()).await,
+            Err(RepositoryError::NotFound)
+        ));
+
+        // When
+        let restored = repo
+            .restore_entry(running.id, &Caller::default())
+            .await
+            .expect("Restoring should succeed");
+
+        // Then
+        assert_eq!(restored.stop_time, deleted.entry.stop_time);
+        assert_eq!(
+            repo.get_history_by_region(None, Region::Ac1)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(repo.get_deleted_history(None).await.unwrap().is_empty());
 
         Ok(())
     }
 
     #[sqlx::test]
-    async fn test_stop_timer_not_running(pool: SqlitePool) -> sqlx::Result<()> {
-        // Given
-        let repo = SqliteRegionRepository::new(pool);
+    async fn test_delete_running_entry_applies_stop_policy(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given: a timer that has been running since yesterday
+        let start_time = Utc::now() - chrono::TimeDelta::days(1);
+        sqlx::query("INSERT INTO region_history (region, start_time) VALUES ('ac1', $1)")
+            .bind(start_time)
+            .execute(&pool)
+            .await?;
+        let repo = SqliteRegionRepository::new(pool.clone())
+            .with_split_on_stop(true)
+            .with_min_duration(Some(min_duration(ShortEntryAction::Discard)));
 
         // When
-        let result = repo.stop_timer(Region::Ac1).await;
+        let deleted = repo
+            .delete_entry(1, &Caller::default())
+            .await
+            .expect("Deleting should succeed");
 
-        // Then
+        // Then: both days are in the trash
+        assert_eq!(deleted.entry.id, 1);
+        assert_eq!(repo.get_deleted_history(None).await.unwrap().len(), 2);
         assert!(
-            matches!(result, Err(RepositoryError::TimerNotRunning)),
-            "Should fail with NotRunning error"
+            repo.get_history_by_region(None, Region::Ac1)
+                .await
+                .unwrap()
+                .is_empty()
         );
 
-        // History should stay empty
-        let history = repo
-            .get_history_by_region(Region::Ac1)
+        // When: a timer is deleted right after it was started
+        repo.start_timer(Region::Ac2, &Caller::default())
             .await
-            .expect("History should succeed");
-        assert!(history.is_empty(), "History should be empty");
+            .unwrap();
+        let running = repo.get_running_timer(None).await.unwrap().unwrap();
+        let deleted = repo
+            .delete_entry(running.id, &Caller::default())
+            .await
+            .expect("Deleting should succeed");
+
+        // Then: it was too short to be kept
+        assert_eq!(deleted.entry.duration, Some(0));
+        assert_eq!(repo.get_deleted_history(None).await.unwrap().len(), 2);
+        assert!(repo.get_running_timer(None).await.unwrap().is_none());
 
         Ok(())
     }
 
     #[sqlx::test]
-    async fn test_get_history_by_region(pool: SqlitePool) -> sqlx::Result<()> {
-        // Given
+    async fn test_purge_deleted_entries(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given: one entry was deleted long ago, one recently
+        sqlx::query(
+            r#"
+            INSERT INTO region_history (region, start_time, stop_time, duration, deleted_at)
+            VALUES ('ac1', '2025-10-01T08:00:00+00:00', '2025-10-01T09:00:00+00:00', 3600000,
+                    '2025-10-01T10:00:00+00:00'),
+                   ('ac2', '2025-10-01T09:00:00+00:00', '2025-10-01T10:00:00+00:00', 3600000,
+                    '2025-11-01T10:00:00+00:00')
+            "#,
+        )
+        .execute(&pool)
+        .await?;
         let repo = SqliteRegionRepository::new(pool);
 
-        repo.start_timer(Region::Aa1).await.unwrap();
-        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
-        repo.stop_timer(Region::Aa1).await.unwrap();
-
-        repo.start_timer(Region::Aa1).await.unwrap();
-        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
-        repo.stop_timer(Region::Aa1).await.unwrap();
-
         // When
-        let history = repo
-            .get_history_by_region(Region::Aa1)
+        let purged = repo
+            .purge_deleted_entries("2025-10-15T00:00:00Z".parse().unwrap())
             .await
-            .expect("Fetching the history should succeed");
+            .expect("Purging should succeed");
 
         // Then
-        assert_eq!(history.len(), 2, "History should contain two entries");
-        assert_eq!(history[0].region, Region::Aa1, "Region should be Aa1");
-        assert!(
-            history[0].stop_time.is_some(),
-            "First entry should be stopped"
-        );
-        assert!(
-            history[0].duration.is_some(),
-            "First entry should have duration"
-        );
-        assert!(
-            history[0].start_time > history[1].start_time,
-            "Entries should be ordered by start_time DESC"
-        );
+        assert_eq!(purged, 1);
+        let trash = repo.get_deleted_history(None).await.unwrap();
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].entry.region, Region::Ac2);
 
         Ok(())
     }
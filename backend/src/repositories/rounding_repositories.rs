@@ -0,0 +1,140 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+use crate::models::region::Region;
+use crate::models::rounding::RegionRounding;
+use crate::models::rounding::RoundingPolicy;
+use crate::repositories::region_repositories::RepositoryError;
+
+#[async_trait]
+pub trait RoundingRepository: Send + Sync {
+    /// Sets the rounding policy of a region, replacing an existing one.
+    async fn set_rounding(
+        &self,
+        region: Region,
+        policy: RoundingPolicy,
+    ) -> Result<RegionRounding, RepositoryError>;
+    /// Returns the rounding policies of all regions that have one, ordered by
+    /// region.
+    async fn get_roundings(&self) -> Result<Vec<RegionRounding>, RepositoryError>;
+    /// Removes the rounding policy of a region, so the default applies again.
+    async fn delete_rounding(&self, region: Region) -> Result<(), RepositoryError>;
+}
+
+pub struct SqliteRoundingRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteRoundingRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl RoundingRepository for SqliteRoundingRepository {
+    async fn set_rounding(
+        &self,
+        region: Region,
+        policy: RoundingPolicy,
+    ) -> Result<RegionRounding, RepositoryError> {
+        let result: RegionRounding = sqlx::query_as(
+            r#"
+            INSERT INTO rounding_policies (region, mode, increment)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (region) DO UPDATE SET mode = excluded.mode,
+                                               increment = excluded.increment
+            RETURNING region, mode, increment
+            "#,
+        )
+        .bind(region)
+        .bind(policy.mode)
+        .bind(policy.increment)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    async fn get_roundings(&self) -> Result<Vec<RegionRounding>, RepositoryError> {
+        let result: Vec<RegionRounding> = sqlx::query_as(
+            "SELECT region, mode, increment FROM rounding_policies ORDER BY region ASC",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    async fn delete_rounding(&self, region: Region) -> Result<(), RepositoryError> {
+        let result = sqlx::query("DELETE FROM rounding_policies WHERE region = $1")
+            .bind(region)
+            .execute(&self.pool)
+            .await?;
+
+        match result.rows_affected() {
+            0 => Err(RepositoryError::NotFound),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::rounding::RoundingMode;
+
+    #[sqlx::test]
+    async fn test_set_rounding_replaces_existing_policy(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteRoundingRepository::new(pool);
+        repo.set_rounding(
+            Region::Ac1,
+            RoundingPolicy {
+                mode: RoundingMode::Up,
+                increment: 15,
+            },
+        )
+        .await
+        .unwrap();
+
+        // When
+        let nearest = RoundingPolicy {
+            mode: RoundingMode::Nearest,
+            increment: 6,
+        };
+        repo.set_rounding(Region::Ac1, nearest)
+            .await
+            .expect("Replacing the policy should succeed");
+
+        // Then
+        let roundings = repo.get_roundings().await.unwrap();
+        assert_eq!(
+            roundings,
+            vec![RegionRounding {
+                region: Region::Ac1,
+                policy: nearest,
+            }],
+            "Only the latest policy should be kept"
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_delete_missing_rounding(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteRoundingRepository::new(pool);
+
+        // When
+        let result = repo.delete_rounding(Region::Aa1).await;
+
+        // Then
+        assert!(
+            matches!(result, Err(RepositoryError::NotFound)),
+            "Deleting a missing policy should fail with NotFound"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,507 @@
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Utc;
+use sqlx::Connection;
+use sqlx::SqliteConnection;
+use sqlx::SqlitePool;
+
+use crate::caller::Caller;
+use crate::models::action::ActionKind;
+use crate::models::region::Region;
+use crate::models::sync::ClientEvent;
+use crate::models::sync::SyncAction;
+use crate::models::sync::SyncStatus;
+use crate::models::sync::SyncedEvent;
+use crate::repositories::journal_repositories::begin_action;
+use crate::repositories::journal_repositories::end_action;
+use crate::repositories::region_repositories::RepositoryError;
+use crate::repositories::region_repositories::StopPolicy;
+use crate::repositories::region_repositories::begin_write;
+use crate::repositories::region_repositories::insert_entry;
+
+#[async_trait]
+pub trait SyncRepository: Send + Sync {
+    /// Applies the events of the caller's client to the caller's history in
+    /// the order of their timestamps. Events that the client already
+    /// synchronized are skipped, and events that would change a closed month
+    /// are ignored.
+    ///
+    /// Conflicts with events of other devices are resolved by time: a start
+    /// ends whatever ran at that moment and lasts until the next recorded
+    /// start, a stop ends the entry of its region that ran at that moment.
+    async fn apply_events(
+        &self,
+        caller: &Caller,
+        events: Vec<ClientEvent>,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<SyncedEvent>, RepositoryError>;
+}
+
+pub struct SqliteSyncRepository {
+    pool: SqlitePool,
+    stop_policy: StopPolicy,
+}
+
+impl SqliteSyncRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            pool,
+            stop_policy: StopPolicy::default(),
+        }
+    }
+
+    /// Sets how the entries that the events stop are stored.
+    pub fn with_stop_policy(mut self, stop_policy: StopPolicy) -> Self {
+        self.stop_policy = stop_policy;
+        self
+    }
+}
+
+#[async_trait]
+impl SyncRepository for SqliteSyncRepository {
+    async fn apply_events(
+        &self,
+        caller: &Caller,
+        mut events: Vec<ClientEvent>,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<SyncedEvent>, RepositoryError> {
+        // Sorting is stable, so events with the same timestamp keep the order
+        // of the batch
+        events.sort_by_key(|event| event.timestamp);
+
+        let mut transaction = begin_write(&self.pool).await?;
+        begin_action(&mut transaction, caller, ActionKind::SyncEvents, now).await?;
+        let mut results = Vec::with_capacity(events.len());
+
+        for event in events {
+            let inserted = sqlx::query(
+                r#"
+                INSERT INTO sync_events (client_id, event_id, synced_at)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (client_id, event_id) DO NOTHING
+                "#,
+            )
+            .bind(&caller.client_id)
+            .bind(&event.event_id)
+            .bind(now)
+            .execute(&mut *transaction)
+            .await?;
+
+            let outcome = if inserted.rows_affected() == 0 {
+                Ok(SyncStatus::Duplicate)
+            } else if event.timestamp > now {
+                Err("The event lies in the future")
+            } else {
+                // An event that would change a closed month is rolled back on
+                // its own, so the rest of the batch still applies
+                let mut savepoint = transaction.begin().await?;
+                let user_id = caller.user_id.as_deref();
+                let policy = &self.stop_policy;
+                let (region, time) = (&event.region, event.timestamp);
+                let applied = match event.action {
+                    SyncAction::Start => {
+                        apply_start(&mut savepoint, policy, user_id, region, time).await
+                    }
+                    SyncAction::Stop => {
+                        apply_stop(&mut savepoint, policy, user_id, region, time).await
+                    }
+                };
+                match applied {
+                    Ok(outcome) => {
+                        savepoint.commit().await?;
+                        outcome
+                    }
+                    Err(RepositoryError::PeriodLocked) => Err("The period is closed"),
+                    Err(e) => return Err(e),
+                }
+            };
+
+            results.push(match outcome {
+                Ok(status) => SyncedEvent {
+                    event_id: event.event_id,
+                    status,
+                    reason: None,
+                },
+                Err(reason) => SyncedEvent {
+                    event_id: event.event_id,
+                    status: SyncStatus::Ignored,
+                    reason: Some(reason),
+                },
+            });
+        }
+
+        end_action(&mut transaction).await?;
+        transaction.commit().await?;
+        Ok(results)
+    }
+}
+
+/// Returns the id, region and start time of the user's entry that ran at
+/// `time`.
+async fn entry_at(
+    connection: &mut SqliteConnection,
+    user_id: Option<&str>,
+    time: DateTime<Utc>,
+) -> Result<Option<(i64, Region, DateTime<Utc>)>, RepositoryError> {
+    let result = sqlx::query_as(
+        r#"
+        SELECT id, region, start_time
+        FROM region_history
+        WHERE start_time <= $1 AND (stop_time IS NULL OR stop_time > $1) AND user_id IS $2
+          AND deleted_at IS NULL
+        ORDER BY start_time DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(time)
+    .bind(user_id)
+    .fetch_optional(&mut *connection)
+    .await?;
+
+    Ok(result)
+}
+
+async fn apply_start(
+    connection: &mut SqliteConnection,
+    stop_policy: &StopPolicy,
+    user_id: Option<&str>,
+    region: &Region,
+    time: DateTime<Utc>,
+) -> Result<Result<SyncStatus, &'static str>, RepositoryError> {
+    if let Some((id, running_region, start_time)) = entry_at(connection, user_id, time).await? {
+        if running_region == *region {
+            return Ok(Err("The region was already running"));
+        }
+        if start_time == time {
+            return Ok(Err("Another region was started at the same time"));
+        }
+        stop_policy.stop(connection, id, start_time, time).await?;
+    }
+
+    // The entry lasts until the next recorded start, or keeps running
+    let (next_start,): (Option<DateTime<Utc>>,) = sqlx::query_as(
+        r#"
+        SELECT MIN(start_time)
+        FROM region_history
+        WHERE start_time > $1 AND user_id IS $2 AND deleted_at IS NULL
+        "#,
+    )
+    .bind(time)
+    .bind(user_id)
+    .fetch_one(&mut *connection)
+    .await?;
+
+    // An entry that ends before the next start is stored like any stopped
+    // timer
+    let id = insert_entry(connection, user_id, region, time, next_start).await?;
+    if let Some(next_start) = next_start {
+        stop_policy.stop(connection, id, time, next_start).await?;
+    }
+
+    Ok(Ok(SyncStatus::Applied))
+}
+
+async fn apply_stop(
+    connection: &mut SqliteConnection,
+    stop_policy: &StopPolicy,
+    user_id: Option<&str>,
+    region: &Region,
+    time: DateTime<Utc>,
+) -> Result<Result<SyncStatus, &'static str>, RepositoryError> {
+    match entry_at(connection, user_id, time).await? {
+        Some((id, running_region, start_time))
+            if running_region == *region && start_time < time =>
+        {
+            stop_policy.stop(connection, id, start_time, time).await?;
+            Ok(Ok(SyncStatus::Applied))
+        }
+        _ => Ok(Err("The region was not running")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+
+    use super::*;
+    use crate::configuration::MinDuration;
+    use crate::configuration::ShortEntryAction;
+    use crate::models::region_history::RegionHistory;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        format!("2025-10-01T{hour:02}:{minute:02}:00Z")
+            .parse()
+            .unwrap()
+    }
+
+    fn client(client_id: &str) -> Caller {
+        Caller {
+            client_id: client_id.to_string(),
+            user_id: None,
+            device_id: None,
+        }
+    }
+
+    fn event(
+        id: &str,
+        action: SyncAction,
+        region: Region,
+        timestamp: DateTime<Utc>,
+    ) -> ClientEvent {
+        ClientEvent {
+            event_id: id.to_string(),
+            action,
+            region,
+            timestamp,
+        }
+    }
+
+    async fn history(pool: &SqlitePool) -> Vec<RegionHistory> {
+        sqlx::query_as(
+            r#"
+            SELECT id, region, start_time, stop_time, duration, auto_stopped
+            FROM region_history
+            ORDER BY start_time ASC
+            "#,
+        )
+        .fetch_all(pool)
+        .await
+        .unwrap()
+    }
+
+    #[sqlx::test]
+    async fn test_apply_offline_events(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteSyncRepository::new(pool.clone());
+        let events = vec![
+            event("2", SyncAction::Stop, Region::Ac1, at(12, 0)),
+            event("1", SyncAction::Start, Region::Ac1, at(8, 0)),
+        ];
+
+        // When
+        let results = repo
+            .apply_events(&client("phone"), events, at(13, 0))
+            .await
+            .unwrap();
+
+        // Then
+        assert!(
+            results
+                .iter()
+                .all(|result| result.status == SyncStatus::Applied),
+            "Both events should be applied in the order of their timestamps"
+        );
+        let history = history(&pool).await;
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].start_time, at(8, 0));
+        assert_eq!(history[0].stop_time, Some(at(12, 0)));
+        assert_eq!(history[0].duration, Some(4 * 3_600_000));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_apply_events_applies_stop_policy(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given: entries are split at midnight and must last 5 minutes
+        let repo = SqliteSyncRepository::new(pool.clone()).with_stop_policy(StopPolicy {
+            split_on_stop: true,
+            min_duration: Some(MinDuration {
+                duration: TimeDelta::minutes(5),
+                action: ShortEntryAction::Discard,
+            }),
+            ..StopPolicy::default()
+        });
+        let next_day = at(2, 0) + TimeDelta::days(1);
+        let events = vec![
+            event("1", SyncAction::Start, Region::Ac1, at(22, 0)),
+            event("2", SyncAction::Stop, Region::Ac1, next_day),
+            event(
+                "3",
+                SyncAction::Start,
+                Region::Ac2,
+                next_day + TimeDelta::hours(1),
+            ),
+            event(
+                "4",
+                SyncAction::Start,
+                Region::Ac3,
+                next_day + TimeDelta::minutes(62),
+            ),
+        ];
+        let now = next_day + TimeDelta::hours(2);
+        repo.apply_events(&client("phone"), events, now)
+            .await
+            .unwrap();
+
+        // When: a start between Ac2 and Ac3 arrives late
+        let late = event(
+            "5",
+            SyncAction::Start,
+            Region::Ac1,
+            next_day + TimeDelta::minutes(61),
+        );
+        repo.apply_events(&client("tablet"), vec![late], now)
+            .await
+            .unwrap();
+
+        // Then: the night is split, and the entries of a minute are dropped
+        let entries: Vec<_> = history(&pool)
+            .await
+            .into_iter()
+            .map(|entry| (entry.region, entry.start_time, entry.stop_time))
+            .collect();
+        let midnight = at(0, 0) + TimeDelta::days(1);
+        assert_eq!(
+            entries,
+            vec![
+                (Region::Ac1, at(22, 0), Some(midnight)),
+                (Region::Ac1, midnight, Some(next_day)),
+                (Region::Ac3, next_day + TimeDelta::minutes(62), None),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_apply_events_skips_duplicates(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteSyncRepository::new(pool.clone());
+        let events = vec![event("1", SyncAction::Start, Region::Ac1, at(8, 0))];
+        repo.apply_events(&client("phone"), events.clone(), at(9, 0))
+            .await
+            .unwrap();
+
+        // When
+        let resent = repo
+            .apply_events(&client("phone"), events.clone(), at(9, 0))
+            .await
+            .unwrap();
+        let other_client = repo
+            .apply_events(&client("tablet"), events, at(9, 0))
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(resent[0].status, SyncStatus::Duplicate);
+        assert_eq!(
+            other_client[0].status,
+            SyncStatus::Ignored,
+            "The same event id of another client is a new event"
+        );
+        assert_eq!(history(&pool).await.len(), 1);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_offline_start_ends_at_next_recorded_start(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given: another device worked on Aa1 from 8:00 and on Aa2 from 11:00
+        sqlx::query(
+            r#"
+            INSERT INTO region_history (region, start_time, stop_time, duration)
+            VALUES ('aa1', '2025-10-01T08:00:00+00:00', '2025-10-01T11:00:00+00:00', 10800000)
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO region_history (region, start_time)
+            VALUES ('aa2', '2025-10-01T11:00:00+00:00')
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+        let repo = SqliteSyncRepository::new(pool.clone());
+
+        // When: the phone started Ac1 at 9:00 while offline
+        let events = vec![event("1", SyncAction::Start, Region::Ac1, at(9, 0))];
+        let results = repo
+            .apply_events(&client("phone"), events, at(12, 0))
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(results[0].status, SyncStatus::Applied);
+        let history = history(&pool).await;
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0].stop_time, Some(at(9, 0)), "Aa1 ends at 9:00");
+        assert_eq!(history[1].region, Region::Ac1);
+        assert_eq!(history[1].stop_time, Some(at(11, 0)), "Ac1 ends at 11:00");
+        assert_eq!(history[1].duration, Some(2 * 3_600_000));
+        assert_eq!(history[2].stop_time, None, "Aa2 keeps running");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_apply_conflicting_events(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteSyncRepository::new(pool.clone());
+        let events = vec![
+            event("1", SyncAction::Start, Region::Ac1, at(8, 0)),
+            event("2", SyncAction::Start, Region::Ac1, at(9, 0)),
+            event("3", SyncAction::Stop, Region::Aa1, at(10, 0)),
+            event("4", SyncAction::Start, Region::Aa1, at(14, 0)),
+        ];
+
+        // When
+        let results = repo
+            .apply_events(&client("phone"), events, at(13, 0))
+            .await
+            .unwrap();
+
+        // Then
+        let statuses: Vec<SyncStatus> = results.iter().map(|result| result.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                SyncStatus::Applied,
+                SyncStatus::Ignored,
+                SyncStatus::Ignored,
+                SyncStatus::Ignored
+            ]
+        );
+        assert_eq!(results[3].reason, Some("The event lies in the future"));
+        assert_eq!(history(&pool).await.len(), 1);
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_events_in_closed_period_are_ignored(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given: September is closed
+        sqlx::query(
+            r#"
+            INSERT INTO period_locks (year, month, starts_at, ends_at, closed_at)
+            VALUES (2025, 9, '2025-09-01T00:00:00+00:00', '2025-10-01T00:00:00+00:00',
+                    '2025-10-01T06:00:00+00:00')
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+        let repo = SqliteSyncRepository::new(pool.clone());
+        let september = "2025-09-30T22:00:00Z".parse().unwrap();
+        let events = vec![
+            event("1", SyncAction::Start, Region::Ac1, september),
+            event("2", SyncAction::Start, Region::Aa1, at(8, 0)),
+        ];
+
+        // When
+        let results = repo
+            .apply_events(&client("phone"), events, at(9, 0))
+            .await
+            .unwrap();
+
+        // Then
+        assert_eq!(results[0].status, SyncStatus::Ignored);
+        assert_eq!(results[0].reason, Some("The period is closed"));
+        assert_eq!(results[1].status, SyncStatus::Applied);
+        let history = history(&pool).await;
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].region, Region::Aa1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,260 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+use crate::models::target_hours::NewTargetHours;
+use crate::models::target_hours::TargetHours;
+use crate::repositories::region_repositories::RepositoryError;
+
+#[async_trait]
+pub trait TargetHoursRepository: Send + Sync {
+    /// Adds a new schedule of contracted working hours of the user. The
+    /// validity period must not overlap with another schedule of the user.
+    async fn add_target_hours(
+        &self,
+        target_hours: NewTargetHours,
+    ) -> Result<TargetHours, RepositoryError>;
+    /// Returns the schedules of the user, ordered by start.
+    async fn get_target_hours(
+        &self,
+        user_id: Option<&str>,
+    ) -> Result<Vec<TargetHours>, RepositoryError>;
+    async fn delete_target_hours(&self, id: i64) -> Result<(), RepositoryError>;
+}
+
+pub struct SqliteTargetHoursRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteTargetHoursRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl TargetHoursRepository for SqliteTargetHoursRepository {
+    async fn add_target_hours(
+        &self,
+        target_hours: NewTargetHours,
+    ) -> Result<TargetHours, RepositoryError> {
+        if target_hours
+            .valid_until
+            .is_some_and(|until| until < target_hours.valid_from)
+        {
+            return Err(RepositoryError::InvalidPeriod);
+        }
+
+        // Two periods overlap, if each one starts before the other one ends
+        let overlapping: Option<(i64,)> = sqlx::query_as(
+            r#"
+            SELECT id
+            FROM target_hours
+            WHERE user_id IS $3
+              AND ($2 IS NULL OR valid_from <= $2)
+              AND (valid_until IS NULL OR valid_until >= $1)
+            "#,
+        )
+        .bind(target_hours.valid_from)
+        .bind(target_hours.valid_until)
+        .bind(&target_hours.user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        if overlapping.is_some() {
+            return Err(RepositoryError::OverlappingPeriod);
+        }
+
+        let result: TargetHours = sqlx::query_as(
+            r#"
+            INSERT INTO target_hours (user_id, valid_from, valid_until, monday, tuesday,
+                                      wednesday, thursday, friday, saturday, sunday)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING *
+            "#,
+        )
+        .bind(&target_hours.user_id)
+        .bind(target_hours.valid_from)
+        .bind(target_hours.valid_until)
+        .bind(target_hours.monday)
+        .bind(target_hours.tuesday)
+        .bind(target_hours.wednesday)
+        .bind(target_hours.thursday)
+        .bind(target_hours.friday)
+        .bind(target_hours.saturday)
+        .bind(target_hours.sunday)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    async fn get_target_hours(
+        &self,
+        user_id: Option<&str>,
+    ) -> Result<Vec<TargetHours>, RepositoryError> {
+        let result: Vec<TargetHours> = sqlx::query_as(
+            "SELECT * FROM target_hours WHERE user_id IS $1 ORDER BY valid_from ASC",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    async fn delete_target_hours(&self, id: i64) -> Result<(), RepositoryError> {
+        let result = sqlx::query("DELETE FROM target_hours WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        match result.rows_affected() {
+            0 => Err(RepositoryError::NotFound),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, month, day).unwrap()
+    }
+
+    fn full_time(valid_from: NaiveDate, valid_until: Option<NaiveDate>) -> NewTargetHours {
+        NewTargetHours {
+            user_id: None,
+            valid_from,
+            valid_until,
+            monday: 28800,
+            tuesday: 28800,
+            wednesday: 28800,
+            thursday: 28800,
+            friday: 28800,
+            saturday: 0,
+            sunday: 0,
+        }
+    }
+
+    #[sqlx::test]
+    async fn test_add_target_hours(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteTargetHoursRepository::new(pool);
+
+        // When
+        let added = repo
+            .add_target_hours(full_time(date(1, 1), Some(date(6, 30))))
+            .await
+            .expect("Adding target hours should succeed");
+
+        // Then
+        let all = repo.get_target_hours(None).await.unwrap();
+        assert_eq!(all, vec![added], "The added schedule should be stored");
+        assert_eq!(all[0].monday, 28800, "Monday should be 8 hours");
+        assert_eq!(all[0].sunday, 0, "Sunday should be free");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_add_overlapping_target_hours(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteTargetHoursRepository::new(pool);
+        repo.add_target_hours(full_time(date(1, 1), Some(date(6, 30))))
+            .await
+            .unwrap();
+
+        // When
+        let overlapping = repo.add_target_hours(full_time(date(6, 30), None)).await;
+        let adjacent = repo.add_target_hours(full_time(date(7, 1), None)).await;
+
+        // Then
+        assert!(
+            matches!(overlapping, Err(RepositoryError::OverlappingPeriod)),
+            "Should fail with OverlappingPeriod error"
+        );
+        assert!(adjacent.is_ok(), "Adjacent periods should be allowed");
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_users_have_their_own_target_hours(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given: Anna works full time, Ben part time
+        let repo = SqliteTargetHoursRepository::new(pool);
+        let anna = NewTargetHours {
+            user_id: Some("anna".to_string()),
+            ..full_time(date(1, 1), None)
+        };
+        let ben = NewTargetHours {
+            user_id: Some("ben".to_string()),
+            monday: 14400,
+            ..full_time(date(1, 1), None)
+        };
+
+        // When
+        repo.add_target_hours(anna)
+            .await
+            .expect("Anna's schedule should be added");
+        repo.add_target_hours(ben)
+            .await
+            .expect("Ben's schedule may cover the same period");
+
+        // Then
+        let anna = repo.get_target_hours(Some("anna")).await.unwrap();
+        let ben = repo.get_target_hours(Some("ben")).await.unwrap();
+        assert_eq!(anna.len(), 1);
+        assert_eq!(anna[0].monday, 28800);
+        assert_eq!(ben.len(), 1);
+        assert_eq!(ben[0].monday, 14400);
+        assert!(repo.get_target_hours(None).await.unwrap().is_empty());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_add_target_hours_with_invalid_period(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteTargetHoursRepository::new(pool);
+
+        // When
+        let result = repo
+            .add_target_hours(full_time(date(6, 1), Some(date(5, 31))))
+            .await;
+
+        // Then
+        assert!(
+            matches!(result, Err(RepositoryError::InvalidPeriod)),
+            "Should fail with InvalidPeriod error"
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_delete_target_hours(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteTargetHoursRepository::new(pool);
+        let added = repo
+            .add_target_hours(full_time(date(1, 1), None))
+            .await
+            .unwrap();
+
+        // When
+        let deleted = repo.delete_target_hours(added.id).await;
+        let deleted_again = repo.delete_target_hours(added.id).await;
+
+        // Then
+        assert!(deleted.is_ok(), "Deleting should succeed");
+        assert!(
+            matches!(deleted_again, Err(RepositoryError::NotFound)),
+            "Deleting a missing schedule should fail with NotFound"
+        );
+        assert!(repo.get_target_hours(None).await.unwrap().is_empty());
+
+        Ok(())
+    }
+}
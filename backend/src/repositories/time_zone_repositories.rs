@@ -0,0 +1,101 @@
+use async_trait::async_trait;
+use chrono_tz::Tz;
+use sqlx::SqlitePool;
+
+use crate::repositories::region_repositories::RepositoryError;
+
+#[async_trait]
+pub trait TimeZoneRepository: Send + Sync {
+    /// Returns the time zone the user chose, if any.
+    async fn get_time_zone(&self, user_id: &str) -> Result<Option<Tz>, RepositoryError>;
+    /// Sets the time zone of the user, replacing an earlier one.
+    async fn set_time_zone(&self, user_id: &str, time_zone: Tz) -> Result<(), RepositoryError>;
+    /// Removes the time zone of the user, so the configured one applies again.
+    async fn delete_time_zone(&self, user_id: &str) -> Result<(), RepositoryError>;
+}
+
+pub struct SqliteTimeZoneRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteTimeZoneRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl TimeZoneRepository for SqliteTimeZoneRepository {
+    async fn get_time_zone(&self, user_id: &str) -> Result<Option<Tz>, RepositoryError> {
+        let result: Option<(String,)> =
+            sqlx::query_as("SELECT time_zone FROM user_time_zones WHERE user_id = $1")
+                .bind(user_id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        // A zone that is no longer in the time zone database counts as unset
+        Ok(result.and_then(|(time_zone,)| time_zone.parse().ok()))
+    }
+
+    async fn set_time_zone(&self, user_id: &str, time_zone: Tz) -> Result<(), RepositoryError> {
+        sqlx::query(
+            r#"
+            INSERT INTO user_time_zones (user_id, time_zone)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id) DO UPDATE SET time_zone = excluded.time_zone
+            "#,
+        )
+        .bind(user_id)
+        .bind(time_zone.name())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_time_zone(&self, user_id: &str) -> Result<(), RepositoryError> {
+        let result = sqlx::query("DELETE FROM user_time_zones WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        match result.rows_affected() {
+            0 => Err(RepositoryError::NotFound),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test]
+    async fn test_set_time_zone_replaces_existing_one(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteTimeZoneRepository::new(pool);
+        repo.set_time_zone("anna", Tz::Europe__Berlin)
+            .await
+            .unwrap();
+
+        // When
+        repo.set_time_zone("anna", Tz::Asia__Tokyo)
+            .await
+            .expect("Replacing the time zone should succeed");
+
+        // Then
+        assert_eq!(
+            repo.get_time_zone("anna").await.unwrap(),
+            Some(Tz::Asia__Tokyo)
+        );
+        assert_eq!(repo.get_time_zone("ben").await.unwrap(), None);
+        repo.delete_time_zone("anna").await.unwrap();
+        assert_eq!(repo.get_time_zone("anna").await.unwrap(), None);
+        assert!(matches!(
+            repo.delete_time_zone("anna").await,
+            Err(RepositoryError::NotFound)
+        ));
+
+        Ok(())
+    }
+}
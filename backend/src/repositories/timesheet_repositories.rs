@@ -0,0 +1,331 @@
+use async_trait::async_trait;
+use chrono::DateTime;
+use chrono::Utc;
+use sqlx::SqliteConnection;
+use sqlx::SqlitePool;
+
+use crate::models::timesheet::Timesheet;
+use crate::models::timesheet::TimesheetEvent;
+use crate::models::timesheet::TimesheetStatus;
+use crate::repositories::region_repositories::RepositoryError;
+use crate::repositories::region_repositories::begin_write;
+
+#[async_trait]
+pub trait TimesheetRepository: Send + Sync {
+    /// Submits the user's timesheet of the month for approval. A rejected
+    /// timesheet can be submitted again.
+    async fn submit_timesheet(
+        &self,
+        user_id: &str,
+        year: i32,
+        month: u32,
+    ) -> Result<Timesheet, RepositoryError>;
+    /// Approves or rejects a submitted timesheet.
+    async fn review_timesheet(
+        &self,
+        user_id: &str,
+        year: i32,
+        month: u32,
+        status: TimesheetStatus,
+        reviewer: &str,
+        comment: Option<&str>,
+    ) -> Result<Timesheet, RepositoryError>;
+    /// Returns the timesheets with the status, or all of them, most recent
+    /// month first.
+    /// Returns the timesheets of the user, or of everyone without a user.
+    async fn get_timesheets(
+        &self,
+        user_id: Option<&str>,
+        status: Option<TimesheetStatus>,
+    ) -> Result<Vec<Timesheet>, RepositoryError>;
+    async fn get_timesheet(
+        &self,
+        user_id: &str,
+        year: i32,
+        month: u32,
+    ) -> Result<Timesheet, RepositoryError>;
+    /// Returns every change of the status of the timesheet, oldest first.
+    async fn get_timesheet_events(
+        &self,
+        user_id: &str,
+        year: i32,
+        month: u32,
+    ) -> Result<Vec<TimesheetEvent>, RepositoryError>;
+}
+
+pub struct SqliteTimesheetRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteTimesheetRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+async fn record_event(
+    connection: &mut SqliteConnection,
+    timesheet: &Timesheet,
+    actor: &str,
+    changed_at: DateTime<Utc>,
+) -> Result<(), RepositoryError> {
+    sqlx::query(
+        r#"
+        INSERT INTO timesheet_events (user_id, year, month, status, actor, comment, changed_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        "#,
+    )
+    .bind(&timesheet.user_id)
+    .bind(timesheet.year)
+    .bind(timesheet.month)
+    .bind(timesheet.status)
+    .bind(actor)
+    .bind(&timesheet.comment)
+    .bind(changed_at)
+    .execute(&mut *connection)
+    .await?;
+
+    Ok(())
+}
+
+#[async_trait]
+impl TimesheetRepository for SqliteTimesheetRepository {
+    async fn submit_timesheet(
+        &self,
+        user_id: &str,
+        year: i32,
+        month: u32,
+    ) -> Result<Timesheet, RepositoryError> {
+        let now = Utc::now();
+        let mut transaction = begin_write(&self.pool).await?;
+
+        let timesheet: Timesheet = sqlx::query_as(
+            r#"
+            INSERT INTO timesheets (user_id, year, month, status, submitted_at)
+            VALUES ($1, $2, $3, 'submitted', $4)
+            ON CONFLICT (user_id, year, month) DO UPDATE SET status = 'submitted',
+                                                             submitted_at = excluded.submitted_at,
+                                                             reviewed_by = NULL,
+                                                             reviewed_at = NULL,
+                                                             comment = NULL
+            WHERE timesheets.status = 'rejected'
+            RETURNING user_id, year, month, status, submitted_at, reviewed_by, reviewed_at, comment
+            "#,
+        )
+        .bind(user_id)
+        .bind(year)
+        .bind(month)
+        .bind(now)
+        .fetch_optional(&mut *transaction)
+        .await?
+        .ok_or(RepositoryError::InvalidStatus)?;
+        record_event(&mut transaction, &timesheet, user_id, now).await?;
+
+        transaction.commit().await?;
+        Ok(timesheet)
+    }
+
+    async fn review_timesheet(
+        &self,
+        user_id: &str,
+        year: i32,
+        month: u32,
+        status: TimesheetStatus,
+        reviewer: &str,
+        comment: Option<&str>,
+    ) -> Result<Timesheet, RepositoryError> {
+        let now = Utc::now();
+        let mut transaction = begin_write(&self.pool).await?;
+
+        let reviewed: Option<Timesheet> = sqlx::query_as(
+            r#"
+            UPDATE timesheets
+            SET status = $1,
+                reviewed_by = $2,
+                reviewed_at = $3,
+                comment = $4
+            WHERE user_id = $5 AND year = $6 AND month = $7 AND status = 'submitted'
+            RETURNING user_id, year, month, status, submitted_at, reviewed_by, reviewed_at, comment
+            "#,
+        )
+        .bind(status)
+        .bind(reviewer)
+        .bind(now)
+        .bind(comment)
+        .bind(user_id)
+        .bind(year)
+        .bind(month)
+        .fetch_optional(&mut *transaction)
+        .await?;
+        let Some(timesheet) = reviewed else {
+            let exists: Option<(i64,)> = sqlx::query_as(
+                "SELECT 1 FROM timesheets WHERE user_id = $1 AND year = $2 AND month = $3",
+            )
+            .bind(user_id)
+            .bind(year)
+            .bind(month)
+            .fetch_optional(&mut *transaction)
+            .await?;
+            return Err(match exists {
+                Some(_) => RepositoryError::InvalidStatus,
+                None => RepositoryError::NotFound,
+            });
+        };
+        record_event(&mut transaction, &timesheet, reviewer, now).await?;
+
+        transaction.commit().await?;
+        Ok(timesheet)
+    }
+
+    async fn get_timesheets(
+        &self,
+        user_id: Option<&str>,
+        status: Option<TimesheetStatus>,
+    ) -> Result<Vec<Timesheet>, RepositoryError> {
+        let result: Vec<Timesheet> = sqlx::query_as(
+            r#"
+            SELECT user_id, year, month, status, submitted_at, reviewed_by, reviewed_at, comment
+            FROM timesheets
+            WHERE ($1 IS NULL OR status = $1)
+              AND ($2 IS NULL OR user_id = $2)
+            ORDER BY year DESC, month DESC, user_id ASC
+            "#,
+        )
+        .bind(status)
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    async fn get_timesheet(
+        &self,
+        user_id: &str,
+        year: i32,
+        month: u32,
+    ) -> Result<Timesheet, RepositoryError> {
+        let result: Option<Timesheet> = sqlx::query_as(
+            r#"
+            SELECT user_id, year, month, status, submitted_at, reviewed_by, reviewed_at, comment
+            FROM timesheets
+            WHERE user_id = $1 AND year = $2 AND month = $3
+            "#,
+        )
+        .bind(user_id)
+        .bind(year)
+        .bind(month)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        result.ok_or(RepositoryError::NotFound)
+    }
+
+    async fn get_timesheet_events(
+        &self,
+        user_id: &str,
+        year: i32,
+        month: u32,
+    ) -> Result<Vec<TimesheetEvent>, RepositoryError> {
+        let result: Vec<TimesheetEvent> = sqlx::query_as(
+            r#"
+            SELECT id, status, actor, comment, changed_at
+            FROM timesheet_events
+            WHERE user_id = $1 AND year = $2 AND month = $3
+            ORDER BY id ASC
+            "#,
+        )
+        .bind(user_id)
+        .bind(year)
+        .bind(month)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test]
+    async fn test_rejected_timesheet_can_be_resubmitted(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteTimesheetRepository::new(pool);
+        repo.submit_timesheet("anna", 2025, 10).await.unwrap();
+        repo.review_timesheet(
+            "anna",
+            2025,
+            10,
+            TimesheetStatus::Rejected,
+            "ben",
+            Some("The 3rd is missing"),
+        )
+        .await
+        .unwrap();
+
+        // When
+        let resubmitted = repo
+            .submit_timesheet("anna", 2025, 10)
+            .await
+            .expect("Resubmitting should succeed");
+
+        // Then
+        assert_eq!(resubmitted.status, TimesheetStatus::Submitted);
+        assert_eq!(resubmitted.reviewed_by, None);
+        let events = repo.get_timesheet_events("anna", 2025, 10).await.unwrap();
+        let statuses: Vec<TimesheetStatus> = events.iter().map(|event| event.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                TimesheetStatus::Submitted,
+                TimesheetStatus::Rejected,
+                TimesheetStatus::Submitted
+            ]
+        );
+        assert_eq!(events[1].actor, "ben");
+        assert_eq!(events[1].comment.as_deref(), Some("The 3rd is missing"));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_only_submitted_timesheets_can_be_reviewed(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        let repo = SqliteTimesheetRepository::new(pool);
+        repo.submit_timesheet("anna", 2025, 10).await.unwrap();
+        repo.review_timesheet("anna", 2025, 10, TimesheetStatus::Approved, "ben", None)
+            .await
+            .unwrap();
+
+        // When
+        let reviewed_again = repo
+            .review_timesheet("anna", 2025, 10, TimesheetStatus::Rejected, "ben", None)
+            .await;
+        let resubmitted = repo.submit_timesheet("anna", 2025, 10).await;
+        let unknown = repo
+            .review_timesheet("anna", 2025, 9, TimesheetStatus::Approved, "ben", None)
+            .await;
+
+        // Then
+        assert!(matches!(
+            reviewed_again,
+            Err(RepositoryError::InvalidStatus)
+        ));
+        assert!(
+            matches!(resubmitted, Err(RepositoryError::InvalidStatus)),
+            "An approved timesheet is final"
+        );
+        assert!(matches!(unknown, Err(RepositoryError::NotFound)));
+        let approved = repo
+            .get_timesheets(None, Some(TimesheetStatus::Approved))
+            .await
+            .unwrap();
+        assert_eq!(approved.len(), 1);
+        let of_ben = repo.get_timesheets(Some("ben"), None).await.unwrap();
+        assert!(of_ben.is_empty(), "Ben has no timesheets");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,193 @@
+use axum::Json;
+use axum::extract::Path;
+use axum::extract::Query;
+use axum::extract::State;
+use chrono::Datelike;
+use chrono::Months;
+use chrono::NaiveDate;
+use chrono::Utc;
+use serde::Deserialize;
+
+use crate::ApiContext;
+use crate::caller::Caller;
+use crate::error::AppError;
+use crate::models::absence::Absence;
+use crate::models::absence::NewAbsence;
+use crate::models::absence::VacationBalance;
+use crate::models::absence::VacationEntitlement;
+use crate::models::permission::Role;
+use crate::reports::Calendar;
+use crate::reports::vacation_balance;
+use crate::routes::TimeZoneQuery;
+use crate::routes::holidays::load_holidays;
+use crate::routes::permissions::authorize_user;
+use crate::routes::permissions::require_role;
+
+#[derive(Deserialize)]
+pub struct UserQuery {
+    user: Option<String>,
+}
+
+/// Returns the user whose absences are read or changed. Defaults to the
+/// caller, other users require the manager role.
+async fn select_user(
+    context: &ApiContext,
+    caller: &Caller,
+    user_id: Option<String>,
+) -> Result<Option<String>, AppError> {
+    let user_id = user_id.or(caller.user_id.clone());
+    authorize_user(context, caller, user_id.as_deref(), Role::Manager).await?;
+    Ok(user_id)
+}
+
+#[derive(Deserialize)]
+pub struct AbsenceQuery {
+    from: NaiveDate,
+    to: NaiveDate,
+}
+
+pub async fn list_absences(
+    Query(query): Query<AbsenceQuery>,
+    Query(user): Query<UserQuery>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<Json<Vec<Absence>>, AppError> {
+    let user_id = select_user(&context, &caller, user.user).await?;
+    let absences = context
+        .absence_repository
+        .get_absences(user_id.as_deref(), query.from, query.to)
+        .await?;
+    Ok(Json(absences))
+}
+
+/// Records an absence for every working day in the requested period. Days
+/// without target hours, like weekends and holidays, are skipped.
+pub async fn add_absence(
+    caller: Caller,
+    State(context): State<ApiContext>,
+    Json(absence): Json<NewAbsence>,
+) -> Result<Json<Vec<Absence>>, AppError> {
+    let user_id = select_user(&context, &caller, absence.user_id).await?;
+    let user_id = user_id.as_deref();
+    let from = absence.from;
+    let to = absence.to.unwrap_or(from);
+    if from
+        .checked_add_months(Months::new(12))
+        .is_none_or(|limit| to >= limit)
+    {
+        return Err(AppError::InvalidInput(
+            "Absences can be added for at most a year at once",
+        ));
+    }
+
+    let schedules = context
+        .target_hours_repository
+        .get_target_hours(user_id)
+        .await?;
+    let holidays = load_holidays(&context, from, to, context.settings.holiday_state).await?;
+    let calendar = Calendar {
+        schedules: &schedules,
+        holidays: &holidays,
+        absences: &[],
+    };
+
+    let dates = calendar.working_days(from, to);
+    if dates.is_empty() {
+        return Err(AppError::InvalidInput(
+            "The period does not contain any working days",
+        ));
+    }
+
+    let absences = context
+        .absence_repository
+        .add_absences(user_id, dates, absence.kind, absence.half_day)
+        .await?;
+    Ok(Json(absences))
+}
+
+pub async fn delete_absence(
+    Path(id): Path<i64>,
+    Query(user): Query<UserQuery>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<(), AppError> {
+    let user_id = select_user(&context, &caller, user.user).await?;
+    context
+        .absence_repository
+        .delete_absence(user_id.as_deref(), id)
+        .await?;
+    Ok(())
+}
+
+#[derive(Deserialize)]
+pub struct VacationQuery {
+    year: Option<i32>,
+}
+
+pub async fn remaining_vacation(
+    Query(query): Query<VacationQuery>,
+    Query(user): Query<UserQuery>,
+    Query(zone): Query<TimeZoneQuery>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<Json<VacationBalance>, AppError> {
+    let user_id = select_user(&context, &caller, user.user).await?;
+    let user_id = user_id.as_deref();
+    let year = match query.year {
+        Some(year) => year,
+        None => {
+            let tz = zone.time_zone(&context, &caller).await?;
+            Utc::now().with_timezone(&tz).year()
+        }
+    };
+    let entitlements = context
+        .absence_repository
+        .get_vacation_entitlements(user_id)
+        .await?;
+
+    // The carry-over depends on all vacation since the first entitlement
+    let first_year = entitlements
+        .first()
+        .map(|entitlement| entitlement.year.min(year))
+        .unwrap_or(year);
+    let (Some(from), Some(to)) = (
+        NaiveDate::from_ymd_opt(first_year, 1, 1),
+        NaiveDate::from_ymd_opt(year, 12, 31),
+    ) else {
+        return Err(AppError::InvalidInput("The year is out of range"));
+    };
+    let absences = context
+        .absence_repository
+        .get_absences(user_id, from, to)
+        .await?;
+
+    Ok(Json(vacation_balance(year, &entitlements, &absences)))
+}
+
+#[derive(Deserialize)]
+pub struct EntitlementRequest {
+    /// The user who is entitled to the vacation. Defaults to the caller.
+    user_id: Option<String>,
+    days: f64,
+}
+
+pub async fn set_vacation_entitlement(
+    Path(year): Path<i32>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+    Json(request): Json<EntitlementRequest>,
+) -> Result<(), AppError> {
+    require_role(&context, &caller, Role::Admin).await?;
+    let user_id = request.user_id.or(caller.user_id);
+    context
+        .absence_repository
+        .set_vacation_entitlement(
+            user_id.as_deref(),
+            VacationEntitlement {
+                year,
+                days: request.days,
+            },
+        )
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use axum::Json;
+use axum::extract::Path;
+use axum::extract::State;
+
+use crate::ApiContext;
+use crate::caller::Caller;
+use crate::error::AppError;
+use crate::models::audit::AuditEntry;
+use crate::models::permission::Role;
+use crate::repositories::region_repositories::RepositoryError;
+use crate::routes::permissions::authorize_user;
+
+/// Returns who changed the entry when, including entries that were deleted.
+/// Only the owner of the entry and managers may read it.
+pub async fn entry_changes(
+    Path(id): Path<i64>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<Json<Vec<AuditEntry>>, AppError> {
+    let owner = context.audit_repository.get_entry_owner(id).await?;
+    authorize_user(&context, &caller, owner.as_deref(), Role::Manager).await?;
+    let changes = context.audit_repository.get_entry_changes(id).await?;
+    if changes.is_empty() {
+        return Err(RepositoryError::NotFound.into());
+    }
+    Ok(Json(changes))
+}
@@ -0,0 +1,35 @@
+use axum::Json;
+use axum::extract::State;
+
+use crate::ApiContext;
+use crate::caller::Caller;
+use crate::error::AppError;
+use crate::events::TimerEvent;
+
+pub async fn start_break(
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<(), AppError> {
+    context.break_repository.start_break(&caller).await?;
+    context.events.publish(TimerEvent::BreakStarted {
+        user_id: caller.user_id.clone(),
+    });
+    Ok(())
+}
+
+#[derive(serde::Serialize)]
+pub struct StopBreakResponse {
+    duration: i64,
+}
+
+pub async fn stop_break(
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<Json<StopBreakResponse>, AppError> {
+    let duration = context.break_repository.stop_break(&caller).await?;
+    context.events.publish(TimerEvent::BreakStopped {
+        user_id: caller.user_id.clone(),
+        duration,
+    });
+    Ok(Json(StopBreakResponse { duration }))
+}
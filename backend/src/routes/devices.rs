@@ -0,0 +1,97 @@
+use axum::Json;
+use axum::extract::Path;
+use axum::extract::State;
+use axum::http::HeaderMap;
+
+use crate::ApiContext;
+use crate::caller::Caller;
+use crate::caller::SETUP_TOKEN;
+use crate::caller::device_token_hash;
+use crate::error::AppError;
+use crate::models::device::Device;
+use crate::models::device::DeviceKind;
+use crate::models::device::RegisterDevice;
+use crate::models::device::RegisteredDevice;
+use crate::models::permission::Role;
+use crate::routes::permissions::require_role;
+
+/// Creates a random device token with 256 bits of entropy.
+fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    getrandom::fill(&mut bytes).expect("The system provides randomness");
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Returns whether the request carries the configured setup token. The hashes
+/// are compared, so the comparison takes the same time for every guess.
+fn has_setup_token(context: &ApiContext, headers: &HeaderMap) -> bool {
+    let (Some(setup_token), Some(header)) =
+        (&context.settings.setup_token, headers.get(SETUP_TOKEN))
+    else {
+        return false;
+    };
+    header
+        .to_str()
+        .is_ok_and(|header| device_token_hash(header) == device_token_hash(setup_token))
+}
+
+/// Registers a device and returns its token. Only the hash of the token is
+/// stored, so the device has to keep it. Personal devices, whose requests act
+/// as their user, and kiosks, which identify workers by PIN, can only be
+/// registered by admins or with the setup token.
+pub async fn register_device(
+    caller: Caller,
+    headers: HeaderMap,
+    State(context): State<ApiContext>,
+    Json(request): Json<RegisterDevice>,
+) -> Result<Json<RegisteredDevice>, AppError> {
+    let name = request.name.trim();
+    if name.is_empty() || name.len() > 255 {
+        return Err(AppError::InvalidInput(
+            "The device name must have 1 to 255 characters",
+        ));
+    }
+    if let Some(user_id) = &request.user_id
+        && (user_id.is_empty() || user_id.len() > 255)
+    {
+        return Err(AppError::InvalidInput(
+            "Client and user ids must have 1 to 255 ASCII characters",
+        ));
+    }
+    let privileged = request.user_id.is_some() || request.kind == DeviceKind::Kiosk;
+    if privileged && !has_setup_token(&context, &headers) {
+        require_role(&context, &caller, Role::Admin).await?;
+    }
+    let token = generate_token();
+    let device = context
+        .device_repository
+        .register_device(
+            name,
+            request.kind,
+            request.user_id.as_deref(),
+            &device_token_hash(&token),
+        )
+        .await?;
+    Ok(Json(RegisteredDevice { device, token }))
+}
+
+pub async fn list_devices(
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<Json<Vec<Device>>, AppError> {
+    require_role(&context, &caller, Role::Admin).await?;
+    let devices = context.device_repository.get_devices().await?;
+    Ok(Json(devices))
+}
+
+/// Revokes a device, e.g. a lost phone. Its requests are rejected from then
+/// on, but its past changes stay attributed to it.
+pub async fn revoke_device(
+    Path(id): Path<i64>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<Json<Device>, AppError> {
+    require_role(&context, &caller, Role::Admin).await?;
+    let device = context.device_repository.revoke_device(id).await?;
+    Ok(Json(device))
+}
@@ -0,0 +1,36 @@
+use std::convert::Infallible;
+
+use axum::extract::State;
+use axum::response::Sse;
+use axum::response::sse::Event;
+use axum::response::sse::KeepAlive;
+use tokio_stream::Stream;
+use tokio_stream::StreamExt;
+use tokio_stream::wrappers::BroadcastStream;
+
+use crate::ApiContext;
+use crate::caller::Caller;
+use crate::error::AppError;
+use crate::routes::permissions::caller_role;
+
+/// Streams the caller's [`TimerEvent`](crate::events::TimerEvent)s as
+/// server-sent events. A client that falls too far behind misses events and
+/// should reload the current state. The role is resolved when subscribing.
+pub async fn events(
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    let role = caller_role(&context, &caller).await?;
+    let stream = BroadcastStream::new(context.events.subscribe()).filter_map(move |event| {
+        let event = event
+            .ok()
+            .filter(|event| event.is_visible_to(caller.user_id.as_deref(), role))?;
+        Event::default()
+            .event(event.name())
+            .json_data(&event)
+            .ok()
+            .map(Ok)
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
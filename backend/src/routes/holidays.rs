@@ -0,0 +1,92 @@
+use axum::Json;
+use axum::extract::Path;
+use axum::extract::Query;
+use axum::extract::State;
+use chrono::Datelike;
+use chrono::NaiveDate;
+use chrono::Utc;
+use serde::Deserialize;
+
+use crate::ApiContext;
+use crate::caller::Caller;
+use crate::error::AppError;
+use crate::holidays::FederalState;
+use crate::holidays::Holiday;
+use crate::holidays::SUPPORTED_YEARS;
+use crate::holidays::holidays_between;
+use crate::models::custom_holiday::CustomHoliday;
+use crate::models::custom_holiday::NewCustomHoliday;
+use crate::models::permission::Role;
+use crate::routes::TimeZoneQuery;
+use crate::routes::permissions::require_role;
+
+/// Loads the public holidays of the given federal state and the custom
+/// holidays within `[from, to]`.
+pub async fn load_holidays(
+    context: &ApiContext,
+    from: NaiveDate,
+    to: NaiveDate,
+    state: Option<FederalState>,
+) -> Result<Vec<Holiday>, AppError> {
+    let custom = context
+        .holiday_repository
+        .get_custom_holidays(from, to)
+        .await?;
+    Ok(holidays_between(from, to, state, custom))
+}
+
+#[derive(Deserialize)]
+pub struct HolidayQuery {
+    year: Option<i32>,
+    /// Overrides the configured federal state
+    state: Option<FederalState>,
+}
+
+pub async fn list_holidays(
+    Query(query): Query<HolidayQuery>,
+    Query(zone): Query<TimeZoneQuery>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<Json<Vec<Holiday>>, AppError> {
+    let year = match query.year {
+        Some(year) => year,
+        None => {
+            let tz = zone.time_zone(&context, &caller).await?;
+            Utc::now().with_timezone(&tz).year()
+        }
+    };
+    if !SUPPORTED_YEARS.contains(&year) {
+        return Err(AppError::InvalidInput(
+            "The year must be between 1583 and 9999",
+        ));
+    }
+    let from = NaiveDate::from_ymd_opt(year, 1, 1).expect("Valid date");
+    let to = NaiveDate::from_ymd_opt(year, 12, 31).expect("Valid date");
+    let state = query.state.or(context.settings.holiday_state);
+
+    let holidays = load_holidays(&context, from, to, state).await?;
+    Ok(Json(holidays))
+}
+
+pub async fn add_custom_holiday(
+    caller: Caller,
+    State(context): State<ApiContext>,
+    Json(holiday): Json<NewCustomHoliday>,
+) -> Result<Json<CustomHoliday>, AppError> {
+    require_role(&context, &caller, Role::Admin).await?;
+    let holiday = context
+        .holiday_repository
+        .add_custom_holiday(holiday)
+        .await?;
+    Ok(Json(holiday))
+}
+
+pub async fn delete_custom_holiday(
+    Path(id): Path<i64>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<(), AppError> {
+    require_role(&context, &caller, Role::Admin).await?;
+    context.holiday_repository.delete_custom_holiday(id).await?;
+    Ok(())
+}
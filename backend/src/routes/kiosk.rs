@@ -0,0 +1,140 @@
+use axum::Json;
+use axum::extract::Path;
+use axum::extract::State;
+use chrono::Utc;
+
+use crate::ApiContext;
+use crate::caller::Caller;
+use crate::configuration::KioskSettings;
+use crate::error::AppError;
+use crate::models::device::DeviceKind;
+use crate::models::kiosk::Credential;
+use crate::models::kiosk::KioskAction;
+use crate::models::kiosk::KioskCredential;
+use crate::models::kiosk::KioskRequest;
+use crate::models::kiosk::KioskResponse;
+use crate::models::permission::Role;
+use crate::routes::permissions::require_role;
+use crate::routes::start_region;
+use crate::routes::stop_region;
+use crate::routes::toggle_region;
+
+fn kiosk_settings(context: &ApiContext) -> Result<&KioskSettings, AppError> {
+    context
+        .settings
+        .kiosk
+        .as_ref()
+        .ok_or(AppError::InvalidInput("Kiosk mode is not configured"))
+}
+
+fn validate(credential: &Credential) -> Result<(), AppError> {
+    match credential.is_valid() {
+        true => Ok(()),
+        false => Err(AppError::InvalidInput(
+            "PINs have 4 to 8 digits, badges 1 to 64 letters or digits",
+        )),
+    }
+}
+
+/// Returns the id of the kiosk device that sends the request. Only registered
+/// kiosks accept PINs, so failed attempts are limited per terminal.
+async fn require_kiosk_device(context: &ApiContext, caller: &Caller) -> Result<i64, AppError> {
+    const NOT_A_KIOSK: AppError =
+        AppError::Forbidden("The kiosk requires a registered kiosk device");
+
+    let device_id = caller.device_id.ok_or(NOT_A_KIOSK)?;
+    let device = context.device_repository.get_device(device_id).await?;
+    match device.kind {
+        DeviceKind::Kiosk => Ok(device_id),
+        _ => Err(NOT_A_KIOSK),
+    }
+}
+
+/// Performs a single action for the worker with the PIN or badge. The worker
+/// is identified for this request only, so the next worker at the terminal
+/// starts from scratch.
+pub async fn kiosk(
+    caller: Caller,
+    State(context): State<ApiContext>,
+    Json(request): Json<KioskRequest>,
+) -> Result<Json<KioskResponse>, AppError> {
+    let settings = kiosk_settings(&context)?;
+    let device_id = require_kiosk_device(&context, &caller).await?;
+    validate(&request.credential)?;
+    let hash = settings.hash(request.credential.kind(), request.credential.value());
+    let user_id = context
+        .kiosk_repository
+        .identify(
+            &hash,
+            device_id,
+            &caller.client_id,
+            settings.max_attempts,
+            Utc::now() - settings.lockout,
+        )
+        .await?;
+
+    // The terminal acts on behalf of the worker
+    let caller = Caller {
+        user_id: Some(user_id.clone()),
+        ..caller
+    };
+    let region = request.region;
+    let (running, duration) = match request.action {
+        KioskAction::Start => {
+            start_region(&context, region.clone(), &caller).await?;
+            (true, 0)
+        }
+        KioskAction::Stop => (false, stop_region(&context, region.clone(), &caller).await?),
+        KioskAction::Toggle => {
+            let toggled = toggle_region(&context, region.clone(), &caller).await?;
+            (toggled.running, toggled.duration)
+        }
+    };
+
+    Ok(Json(KioskResponse {
+        user_id,
+        region,
+        running,
+        duration,
+    }))
+}
+
+pub async fn list_kiosk_credentials(
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<Json<Vec<KioskCredential>>, AppError> {
+    require_role(&context, &caller, Role::Admin).await?;
+    let credentials = context.kiosk_repository.get_credentials().await?;
+    Ok(Json(credentials))
+}
+
+/// Sets the PIN or badge of a user, e.g. `{"badge": "04A2B9"}`.
+pub async fn set_kiosk_credential(
+    Path(user_id): Path<String>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+    Json(credential): Json<Credential>,
+) -> Result<(), AppError> {
+    require_role(&context, &caller, Role::Admin).await?;
+    let settings = kiosk_settings(&context)?;
+    validate(&credential)?;
+    let hash = settings.hash(credential.kind(), credential.value());
+    context
+        .kiosk_repository
+        .set_credential(&user_id, credential.kind(), &hash)
+        .await?;
+    Ok(())
+}
+
+pub async fn delete_kiosk_credentials(
+    Path(user_id): Path<String>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<(), AppError> {
+    require_role(&context, &caller, Role::Admin).await?;
+    context
+        .kiosk_repository
+        .delete_credentials(&user_id)
+        .await?;
+    Ok(())
+}
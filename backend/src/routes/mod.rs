@@ -1,12 +1,88 @@
+pub mod absences;
+pub mod audit;
+pub mod breaks;
+pub mod devices;
+pub mod events;
+pub mod holidays;
+pub mod kiosk;
+pub mod periods;
+pub mod permissions;
+pub mod reports;
+pub mod rounding;
+pub mod sync;
+pub mod target_hours;
+pub mod time_zones;
+pub mod timesheets;
+pub mod undo;
+pub mod websocket;
+
 use axum::Json;
 use axum::extract::Path;
+use axum::extract::Query;
 use axum::extract::State;
+use axum::response::IntoResponse;
+use axum::response::Response;
+use chrono::DateTime;
+use chrono::Utc;
+use chrono_tz::Tz;
+use serde::Deserialize;
 
 use crate::ApiContext;
+use crate::caller::Caller;
 use crate::error::AppError;
+use crate::events::TimerEvent;
 use crate::models::region::CurrentlyActiveRegion;
 use crate::models::region::Region;
+use crate::models::region::StoppedTimer;
+use crate::models::region::ToggledTimer;
+use crate::models::region_history::DeletedEntry;
+use crate::models::region_history::LocalRegionHistory;
 use crate::models::region_history::RegionHistory;
+use crate::models::region_history::RemovedEntry;
+use crate::reports::split_history_by_day;
+use crate::repositories::region_repositories::RepositoryError;
+use crate::routes::permissions::authorize_region;
+use crate::routes::time_zones::user_time_zone;
+
+/// Selects the time zone of a response. Without `tz`, the caller's time zone
+/// or else the configured one is used.
+#[derive(Deserialize)]
+pub struct TimeZoneQuery {
+    tz: Option<Tz>,
+    /// Renders timestamps with the offset of the time zone instead of UTC
+    #[serde(default)]
+    offsets: bool,
+}
+
+impl TimeZoneQuery {
+    pub async fn time_zone(&self, context: &ApiContext, caller: &Caller) -> Result<Tz, AppError> {
+        match self.tz {
+            Some(tz) => Ok(tz),
+            None => Ok(user_time_zone(context, caller.user_id.as_deref())
+                .await?
+                .time_zone),
+        }
+    }
+
+    /// Renders the history in UTC or, if requested, with the offsets of the
+    /// selected time zone.
+    async fn render(
+        &self,
+        context: &ApiContext,
+        caller: &Caller,
+        history: Vec<RegionHistory>,
+    ) -> Result<Response, AppError> {
+        if !self.offsets {
+            return Ok(Json(history).into_response());
+        }
+        let tz = self.time_zone(context, caller).await?;
+        let history: Vec<LocalRegionHistory> = history
+            .into_iter()
+            .map(|entry| entry.in_time_zone(&tz))
+            .collect();
+        Ok(Json(history).into_response())
+    }
+}
 
 pub async fn hello_world() -> &'static str {
     "Hello, World!"
@@ -15,40 +91,270 @@ pub async fn hello_world() -> &'static str {
 #[axum_macros::debug_handler]
 pub async fn start_timer(
     Path(region): Path<Region>,
+    caller: Caller,
     State(context): State<ApiContext>,
 ) -> Result<(), AppError> {
-    context.region_repository.start_timer(region).await?;
+    start_region(&context, region, &caller).await?;
+    Ok(())
+}
+
+/// Starts the timer of the region and notifies the connected clients, also of
+/// the timer that was stopped for it.
+pub(crate) async fn start_region(
+    context: &ApiContext,
+    region: Region,
+    caller: &Caller,
+) -> Result<(), RepositoryError> {
+    authorize_region(context, caller, &region).await?;
+    let stopped = context
+        .region_repository
+        .start_timer(region.clone(), caller)
+        .await?;
+    publish_stopped(context, caller, stopped);
+    context.events.publish(TimerEvent::TimerStarted {
+        user_id: caller.user_id.clone(),
+        region,
+    });
     Ok(())
 }
 
+fn publish_stopped(context: &ApiContext, caller: &Caller, stopped: Option<StoppedTimer>) {
+    if let Some(stopped) = stopped {
+        context.events.publish(TimerEvent::TimerStopped {
+            user_id: caller.user_id.clone(),
+            region: stopped.region,
+            duration: stopped.duration,
+            auto_stopped: false,
+        });
+    }
+}
+
+/// Stops the timer of the region and notifies the connected clients. Returns
+/// the duration of the stopped entry.
+pub(crate) async fn stop_region(
+    context: &ApiContext,
+    region: Region,
+    caller: &Caller,
+) -> Result<i64, RepositoryError> {
+    authorize_region(context, caller, &region).await?;
+    let duration = context
+        .region_repository
+        .stop_timer(region.clone(), caller)
+        .await?;
+    context.events.publish(TimerEvent::TimerStopped {
+        user_id: caller.user_id.clone(),
+        region,
+        duration,
+        auto_stopped: false,
+    });
+    Ok(duration)
+}
+
 #[derive(serde::Serialize)]
 pub struct StopTimerResponse {
+    /// The duration of the stopped entry in milliseconds
     duration: i64,
 }
 
 pub async fn stop_timer(
     Path(region): Path<Region>,
+    caller: Caller,
     State(context): State<ApiContext>,
 ) -> Result<Json<StopTimerResponse>, AppError> {
-    let duration = context.region_repository.stop_timer(region).await?;
+    let duration = stop_region(&context, region, &caller).await?;
     Ok(Json(StopTimerResponse { duration }))
 }
 
+/// Starts the region if it is not running and stops it otherwise, so devices
+/// with a single button only need one request.
+pub async fn toggle_timer(
+    Path(region): Path<Region>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<Json<ToggledTimer>, AppError> {
+    let toggled = toggle_region(&context, region, &caller).await?;
+    Ok(Json(toggled))
+}
+
+/// Toggles the timer of the region and notifies the connected clients.
+pub(crate) async fn toggle_region(
+    context: &ApiContext,
+    region: Region,
+    caller: &Caller,
+) -> Result<ToggledTimer, RepositoryError> {
+    authorize_region(context, caller, &region).await?;
+    let toggled = context
+        .region_repository
+        .toggle_timer(region, caller)
+        .await?;
+    publish_stopped(context, caller, toggled.stopped.clone());
+    let user_id = caller.user_id.clone();
+    context.events.publish(match toggled.running {
+        true => TimerEvent::TimerStarted {
+            user_id,
+            region: toggled.region.clone(),
+        },
+        false => TimerEvent::TimerStopped {
+            user_id,
+            region: toggled.region.clone(),
+            duration: toggled.duration,
+            auto_stopped: false,
+        },
+    });
+    Ok(toggled)
+}
+
 pub async fn history_by_region(
     Path(region): Path<Region>,
+    Query(zone): Query<TimeZoneQuery>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<Response, AppError> {
+    let region_history = context
+        .region_repository
+        .get_history_by_region(caller.user_id.as_deref(), region)
+        .await?;
+    zone.render(&context, &caller, region_history).await
+}
+
+#[derive(Deserialize)]
+pub struct HistoryQuery {
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    /// Splits entries that span multiple days into one entry per day of the
+    /// requested time zone
+    #[serde(default)]
+    split_days: bool,
+}
+
+/// Returns the caller's history of all regions that overlaps `[from, to)`,
+/// ordered by start time.
+pub async fn history(
+    Query(query): Query<HistoryQuery>,
+    Query(zone): Query<TimeZoneQuery>,
+    caller: Caller,
     State(context): State<ApiContext>,
-) -> Result<Json<Vec<RegionHistory>>, AppError> {
+) -> Result<Response, AppError> {
+    let mut region_history = context
+        .region_repository
+        .get_history_between(caller.user_id.as_deref(), query.from, query.to)
+        .await?;
+
+    if query.split_days {
+        let tz = zone.time_zone(&context, &caller).await?;
+        region_history = split_history_by_day(region_history, &tz, Utc::now());
+    }
+    zone.render(&context, &caller, region_history).await
+}
+
+/// Lists the entries that were stopped automatically, so they can be reviewed.
+pub async fn auto_stopped_history(
+    Query(zone): Query<TimeZoneQuery>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<Response, AppError> {
     let region_history = context
         .region_repository
-        .get_history_by_region(region)
+        .get_auto_stopped_history(caller.user_id.as_deref())
+        .await?;
+    zone.render(&context, &caller, region_history).await
+}
+
+#[derive(Deserialize)]
+pub struct CleanupQuery {
+    /// Only reports the entries that would be removed or merged
+    #[serde(default)]
+    dry_run: bool,
+}
+
+/// Applies the configured minimum duration to the caller's whole history,
+/// removing accidental taps that were recorded before it was configured.
+pub async fn clean_up_history(
+    Query(query): Query<CleanupQuery>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<Json<Vec<RemovedEntry>>, AppError> {
+    let Some(min_duration) = context.settings.min_duration else {
+        return Err(AppError::InvalidInput("No minimum duration is configured"));
+    };
+    let removed = context
+        .region_repository
+        .remove_short_entries(min_duration, query.dry_run, &caller)
+        .await?;
+
+    if !query.dry_run && !removed.is_empty() {
+        context.events.publish(TimerEvent::HistoryChanged {
+            user_id: caller.user_id.clone(),
+        });
+    }
+    Ok(Json(removed))
+}
+
+/// Merges the caller's contiguous entries of the same region, e.g. ones that
+/// were split by restarting the running region.
+pub async fn merge_history(
+    Query(query): Query<CleanupQuery>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<Json<Vec<RemovedEntry>>, AppError> {
+    let merged = context
+        .region_repository
+        .merge_contiguous_entries(query.dry_run, &caller)
+        .await?;
+
+    if !query.dry_run && !merged.is_empty() {
+        context.events.publish(TimerEvent::HistoryChanged {
+            user_id: caller.user_id.clone(),
+        });
+    }
+    Ok(Json(merged))
+}
+
+/// Moves the entry to the trash, from which it can be restored until it is
+/// purged.
+pub async fn delete_entry(
+    Path(id): Path<i64>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<Json<DeletedEntry>, AppError> {
+    let deleted = context.region_repository.delete_entry(id, &caller).await?;
+    context.events.publish(TimerEvent::HistoryChanged {
+        user_id: caller.user_id.clone(),
+    });
+    Ok(Json(deleted))
+}
+
+pub async fn deleted_history(
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<Json<Vec<DeletedEntry>>, AppError> {
+    let deleted = context
+        .region_repository
+        .get_deleted_history(caller.user_id.as_deref())
         .await?;
-    Ok(Json(region_history))
+    Ok(Json(deleted))
+}
+
+pub async fn restore_entry(
+    Path(id): Path<i64>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<Json<RegionHistory>, AppError> {
+    let restored = context.region_repository.restore_entry(id, &caller).await?;
+    context.events.publish(TimerEvent::HistoryChanged {
+        user_id: caller.user_id.clone(),
+    });
+    Ok(Json(restored))
 }
 
 pub async fn currently_active(
+    caller: Caller,
     State(context): State<ApiContext>,
 ) -> Result<Json<CurrentlyActiveRegion>, AppError> {
-    let result = context.region_repository.currently_active_timer().await?;
+    let result = context
+        .region_repository
+        .currently_active_timer(caller.user_id.as_deref())
+        .await?;
     Ok(Json(result))
 }
 
@@ -0,0 +1,91 @@
+use axum::Json;
+use axum::extract::Path;
+use axum::extract::State;
+use chrono::DateTime;
+use chrono::Months;
+use chrono::NaiveDate;
+use chrono::Utc;
+
+use crate::ApiContext;
+use crate::caller::Caller;
+use crate::error::AppError;
+use crate::models::period::PeriodLock;
+use crate::models::period::PeriodLogEntry;
+use crate::models::period::ReopenPeriod;
+use crate::models::permission::Role;
+use crate::reports::start_of_local_day;
+use crate::routes::permissions::require_role;
+
+/// Returns the first day of the month and of the month after it.
+pub(crate) fn month_days(year: i32, month: u32) -> Result<(NaiveDate, NaiveDate), AppError> {
+    let first_day = NaiveDate::from_ymd_opt(year, month, 1)
+        .ok_or(AppError::InvalidInput("The month does not exist"))?;
+    let next_month = first_day
+        .checked_add_months(Months::new(1))
+        .ok_or(AppError::InvalidInput("The month does not exist"))?;
+    Ok((first_day, next_month))
+}
+
+/// Returns the bounds of the month in the configured time zone.
+pub(crate) fn month_bounds(
+    context: &ApiContext,
+    year: i32,
+    month: u32,
+) -> Result<(DateTime<Utc>, DateTime<Utc>), AppError> {
+    let (first_day, next_month) = month_days(year, month)?;
+    let tz = context.settings.time_zone;
+    Ok((
+        start_of_local_day(first_day, &tz),
+        start_of_local_day(next_month, &tz),
+    ))
+}
+
+pub async fn list_locked_periods(
+    State(context): State<ApiContext>,
+) -> Result<Json<Vec<PeriodLock>>, AppError> {
+    let locks = context.period_repository.get_locked_periods().await?;
+    Ok(Json(locks))
+}
+
+/// Closes a past month, after which its entries can't be changed.
+pub async fn close_period(
+    Path((year, month)): Path<(i32, u32)>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<Json<PeriodLock>, AppError> {
+    require_role(&context, &caller, Role::Admin).await?;
+    let (starts_at, ends_at) = month_bounds(&context, year, month)?;
+    if ends_at > Utc::now() {
+        return Err(AppError::InvalidInput("Only past months can be closed"));
+    }
+    let lock = context
+        .period_repository
+        .close_period(year, month, starts_at, ends_at, &caller)
+        .await?;
+    Ok(Json(lock))
+}
+
+pub async fn reopen_period(
+    Path((year, month)): Path<(i32, u32)>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+    Json(request): Json<ReopenPeriod>,
+) -> Result<Json<PeriodLogEntry>, AppError> {
+    require_role(&context, &caller, Role::Admin).await?;
+    if request.reason.trim().is_empty() {
+        return Err(AppError::InvalidInput("A reason is required"));
+    }
+    let entry = context
+        .period_repository
+        .reopen_period(year, month, &request.reason, &caller)
+        .await?;
+    Ok(Json(entry))
+}
+
+/// Returns who closed and reopened which month, oldest first.
+pub async fn period_log(
+    State(context): State<ApiContext>,
+) -> Result<Json<Vec<PeriodLogEntry>>, AppError> {
+    let log = context.period_repository.get_period_log().await?;
+    Ok(Json(log))
+}
@@ -0,0 +1,170 @@
+use axum::Json;
+use axum::extract::Path;
+use axum::extract::State;
+
+use crate::ApiContext;
+use crate::caller::Caller;
+use crate::error::AppError;
+use crate::models::permission::AllowList;
+use crate::models::permission::GroupMember;
+use crate::models::permission::RegionPermissions;
+use crate::models::permission::Role;
+use crate::models::permission::SetRole;
+use crate::models::permission::UserRole;
+use crate::models::region::Region;
+use crate::repositories::region_repositories::RepositoryError;
+
+/// Returns the role of the caller. Configured admins are admins regardless of
+/// their stored role, callers without a user id are members.
+pub(crate) async fn caller_role(
+    context: &ApiContext,
+    caller: &Caller,
+) -> Result<Role, RepositoryError> {
+    let Some(user_id) = caller.user_id.as_deref() else {
+        return Ok(Role::Member);
+    };
+    if context.settings.admins.iter().any(|admin| admin == user_id) {
+        return Ok(Role::Admin);
+    }
+    let role = context.permission_repository.get_role(user_id).await?;
+    Ok(role.unwrap_or_default())
+}
+
+/// Fails unless the caller has at least the role.
+pub(crate) async fn require_role(
+    context: &ApiContext,
+    caller: &Caller,
+    role: Role,
+) -> Result<(), AppError> {
+    if caller_role(context, caller).await? >= role {
+        return Ok(());
+    }
+    Err(AppError::Forbidden(match role {
+        Role::Admin => "The request requires the admin role",
+        Role::Manager | Role::Member => "The request requires the manager role",
+    }))
+}
+
+/// Fails unless the caller is the user or has at least the role, e.g. to read
+/// the hours of someone else. Callers without a user id are the user of the
+/// shared history.
+pub(crate) async fn authorize_user(
+    context: &ApiContext,
+    caller: &Caller,
+    user_id: Option<&str>,
+    role: Role,
+) -> Result<(), AppError> {
+    if caller.user_id.as_deref() == user_id {
+        return Ok(());
+    }
+    require_role(context, caller, role).await
+}
+
+/// Fails unless the caller may book time on the region. Admins may book time
+/// on every region.
+pub(crate) async fn authorize_region(
+    context: &ApiContext,
+    caller: &Caller,
+    region: &Region,
+) -> Result<(), RepositoryError> {
+    if context
+        .permission_repository
+        .may_book(region, caller.user_id.as_deref())
+        .await?
+        || caller_role(context, caller).await? == Role::Admin
+    {
+        return Ok(());
+    }
+    Err(RepositoryError::Forbidden)
+}
+
+pub async fn list_roles(
+    State(context): State<ApiContext>,
+) -> Result<Json<Vec<UserRole>>, AppError> {
+    let roles = context.permission_repository.get_roles().await?;
+    Ok(Json(roles))
+}
+
+pub async fn set_role(
+    Path(user_id): Path<String>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+    Json(request): Json<SetRole>,
+) -> Result<Json<UserRole>, AppError> {
+    require_role(&context, &caller, Role::Admin).await?;
+    let role = context
+        .permission_repository
+        .set_role(&user_id, request.role)
+        .await?;
+    Ok(Json(role))
+}
+
+/// Removes the stored role, so the user is a member again.
+pub async fn delete_role(
+    Path(user_id): Path<String>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<(), AppError> {
+    require_role(&context, &caller, Role::Admin).await?;
+    context.permission_repository.delete_role(&user_id).await?;
+    Ok(())
+}
+
+pub async fn list_group_members(
+    State(context): State<ApiContext>,
+) -> Result<Json<Vec<GroupMember>>, AppError> {
+    let members = context.permission_repository.get_group_members().await?;
+    Ok(Json(members))
+}
+
+pub async fn add_group_member(
+    Path((group_name, user_id)): Path<(String, String)>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<(), AppError> {
+    require_role(&context, &caller, Role::Admin).await?;
+    context
+        .permission_repository
+        .add_group_member(&group_name, &user_id)
+        .await?;
+    Ok(())
+}
+
+pub async fn remove_group_member(
+    Path((group_name, user_id)): Path<(String, String)>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<(), AppError> {
+    require_role(&context, &caller, Role::Admin).await?;
+    context
+        .permission_repository
+        .remove_group_member(&group_name, &user_id)
+        .await?;
+    Ok(())
+}
+
+/// Lists the allow-lists of the restricted regions. Regions that are not
+/// listed are open to everyone.
+pub async fn list_region_permissions(
+    State(context): State<ApiContext>,
+) -> Result<Json<Vec<RegionPermissions>>, AppError> {
+    let permissions = context
+        .permission_repository
+        .get_region_permissions()
+        .await?;
+    Ok(Json(permissions))
+}
+
+pub async fn set_region_permissions(
+    Path(region): Path<Region>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+    Json(allow_list): Json<AllowList>,
+) -> Result<Json<RegionPermissions>, AppError> {
+    require_role(&context, &caller, Role::Admin).await?;
+    let permissions = context
+        .permission_repository
+        .set_region_permissions(region, allow_list)
+        .await?;
+    Ok(Json(permissions))
+}
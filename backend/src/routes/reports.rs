@@ -0,0 +1,143 @@
+use axum::Json;
+use axum::extract::Query;
+use axum::extract::State;
+use chrono::DateTime;
+use chrono::Datelike;
+use chrono::Days;
+use chrono::NaiveDate;
+use chrono::Utc;
+use chrono_tz::Tz;
+use serde::Deserialize;
+
+use crate::ApiContext;
+use crate::caller::Caller;
+use crate::error::AppError;
+use crate::holidays::SUPPORTED_YEARS;
+use crate::models::flextime::Flextime;
+use crate::models::summary::Summary;
+use crate::reports::Calendar;
+use crate::reports::Rounding;
+use crate::reports::flextime;
+use crate::reports::start_of_local_day;
+use crate::reports::summarize;
+use crate::routes::TimeZoneQuery;
+use crate::routes::holidays::load_holidays;
+
+#[derive(Deserialize)]
+pub struct SummaryQuery {
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+}
+
+/// Summarizes the user's work and breaks in `[from, to)`.
+pub(crate) async fn user_summary(
+    context: &ApiContext,
+    user_id: Option<&str>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Summary, AppError> {
+    let history = context
+        .region_repository
+        .get_history_between(user_id, from, to)
+        .await?;
+    let breaks = context
+        .break_repository
+        .get_breaks_between(user_id, from, to)
+        .await?;
+    let regions = context.rounding_repository.get_roundings().await?;
+    let rounding = Rounding {
+        default: context.settings.rounding,
+        regions: &regions,
+    };
+
+    Ok(summarize(
+        &history,
+        &breaks,
+        &rounding,
+        from,
+        to,
+        Utc::now(),
+    ))
+}
+
+/// Summarizes the caller's work and breaks in `[from, to)`.
+pub async fn summary(
+    Query(query): Query<SummaryQuery>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<Json<Summary>, AppError> {
+    let summary = user_summary(&context, caller.user_id.as_deref(), query.from, query.to).await?;
+    Ok(Json(summary))
+}
+
+#[derive(Deserialize)]
+pub struct FlextimeQuery {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+}
+
+/// Returns the caller's flextime balance. Without a range, it is calculated
+/// from the start of the first working hours schedule until today. Holidays of
+/// the configured federal state have no target hours and absences are credited.
+/// Days start and end at midnight of the requested time zone or else the
+/// caller's.
+pub async fn flextime_balance(
+    Query(query): Query<FlextimeQuery>,
+    Query(zone): Query<TimeZoneQuery>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<Json<Flextime>, AppError> {
+    let tz = zone.time_zone(&context, &caller).await?;
+    let to = query
+        .to
+        .unwrap_or(Utc::now().with_timezone(&tz).date_naive());
+    let flextime = user_flextime(&context, caller.user_id.as_deref(), query.from, to, &tz).await?;
+    Ok(Json(flextime))
+}
+
+/// Returns the user's flextime balance of the days until `to`, starting with
+/// `from` or else the first working hours schedule.
+pub(crate) async fn user_flextime(
+    context: &ApiContext,
+    user_id: Option<&str>,
+    from: Option<NaiveDate>,
+    to: NaiveDate,
+    tz: &Tz,
+) -> Result<Flextime, AppError> {
+    let schedules = context
+        .target_hours_repository
+        .get_target_hours(user_id)
+        .await?;
+    let from = from
+        .or(schedules.first().map(|schedule| schedule.valid_from))
+        .unwrap_or(to);
+    // Every day of the range is calculated, so it must stay within the years
+    // that have holidays
+    if !SUPPORTED_YEARS.contains(&from.year()) || !SUPPORTED_YEARS.contains(&to.year()) {
+        return Err(AppError::InvalidInput(
+            "The period must lie between the years 1583 and 9999",
+        ));
+    }
+
+    let history = context
+        .region_repository
+        .get_history_between(
+            user_id,
+            start_of_local_day(from, tz),
+            start_of_local_day(to + Days::new(1), tz),
+        )
+        .await?;
+
+    let holidays = load_holidays(context, from, to, context.settings.holiday_state).await?;
+    let absences = context
+        .absence_repository
+        .get_absences(user_id, from, to)
+        .await?;
+    let calendar = Calendar {
+        schedules: &schedules,
+        holidays: &holidays,
+        absences: &absences,
+    };
+
+    Ok(flextime(&history, &calendar, from, to, tz, Utc::now()))
+}
@@ -0,0 +1,55 @@
+use axum::Json;
+use axum::extract::Path;
+use axum::extract::State;
+
+use crate::ApiContext;
+use crate::caller::Caller;
+use crate::error::AppError;
+use crate::models::permission::Role;
+use crate::models::region::Region;
+use crate::models::rounding::INCREMENTS;
+use crate::models::rounding::RegionRounding;
+use crate::models::rounding::RoundingPolicies;
+use crate::models::rounding::RoundingPolicy;
+use crate::routes::permissions::require_role;
+
+/// Returns the configured default rounding policy and the policies of the
+/// regions that override it.
+pub async fn list_roundings(
+    State(context): State<ApiContext>,
+) -> Result<Json<RoundingPolicies>, AppError> {
+    let regions = context.rounding_repository.get_roundings().await?;
+    Ok(Json(RoundingPolicies {
+        default: context.settings.rounding,
+        regions,
+    }))
+}
+
+pub async fn set_rounding(
+    Path(region): Path<Region>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+    Json(policy): Json<RoundingPolicy>,
+) -> Result<Json<RegionRounding>, AppError> {
+    require_role(&context, &caller, Role::Admin).await?;
+    if !INCREMENTS.contains(&policy.increment) {
+        return Err(AppError::InvalidInput(
+            "The increment must be between 1 and 1440 minutes",
+        ));
+    }
+    let rounding = context
+        .rounding_repository
+        .set_rounding(region, policy)
+        .await?;
+    Ok(Json(rounding))
+}
+
+pub async fn delete_rounding(
+    Path(region): Path<Region>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<(), AppError> {
+    require_role(&context, &caller, Role::Admin).await?;
+    context.rounding_repository.delete_rounding(region).await?;
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use axum::Json;
+use axum::extract::State;
+use chrono::Utc;
+
+use crate::ApiContext;
+use crate::caller::Caller;
+use crate::error::AppError;
+use crate::events::TimerEvent;
+use crate::models::sync::SyncRequest;
+use crate::models::sync::SyncResponse;
+use crate::models::sync::SyncStatus;
+use crate::models::sync::SyncedEvent;
+use crate::repositories::region_repositories::RepositoryError;
+use crate::routes::permissions::authorize_region;
+
+/// Applies the events that a client queued while it was offline and returns
+/// the reconciled history since the earliest event. The changes are attributed
+/// to the client id of the batch.
+pub async fn sync(
+    caller: Caller,
+    State(context): State<ApiContext>,
+    Json(request): Json<SyncRequest>,
+) -> Result<Json<SyncResponse>, AppError> {
+    if request.client_id.is_empty() {
+        return Err(AppError::InvalidInput("The client id must not be empty"));
+    }
+
+    let now = Utc::now();
+    let Some(from) = request.events.iter().map(|event| event.timestamp).min() else {
+        return Ok(Json(SyncResponse {
+            events: Vec::new(),
+            history: Vec::new(),
+        }));
+    };
+
+    // Events on regions the caller may not book are ignored, so the rest of
+    // the batch still applies
+    let mut permitted = Vec::with_capacity(request.events.len());
+    let mut forbidden = Vec::new();
+    for event in request.events {
+        match authorize_region(&context, &caller, &event.region).await {
+            Ok(()) => permitted.push(event),
+            Err(RepositoryError::Forbidden) => forbidden.push(SyncedEvent {
+                event_id: event.event_id,
+                status: SyncStatus::Ignored,
+                reason: Some("Booking time on the region is not permitted"),
+            }),
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    let caller = Caller {
+        client_id: request.client_id,
+        ..caller
+    };
+    let mut events = context
+        .sync_repository
+        .apply_events(&caller, permitted, now)
+        .await?;
+    if events
+        .iter()
+        .any(|event| event.status == SyncStatus::Applied)
+    {
+        context.events.publish(TimerEvent::HistoryChanged {
+            user_id: caller.user_id.clone(),
+        });
+    }
+    events.extend(forbidden);
+
+    let history = context
+        .region_repository
+        .get_history_between(caller.user_id.as_deref(), from.min(now), now)
+        .await?;
+
+    Ok(Json(SyncResponse { events, history }))
+}
@@ -0,0 +1,69 @@
+use axum::Json;
+use axum::extract::Path;
+use axum::extract::Query;
+use axum::extract::State;
+use serde::Deserialize;
+
+use crate::ApiContext;
+use crate::caller::Caller;
+use crate::error::AppError;
+use crate::models::permission::Role;
+use crate::models::target_hours::NewTargetHours;
+use crate::models::target_hours::TargetHours;
+use crate::routes::permissions::authorize_user;
+use crate::routes::permissions::require_role;
+
+#[derive(Deserialize)]
+pub struct TargetHoursQuery {
+    /// The user whose schedules are listed. Defaults to the caller.
+    user: Option<String>,
+}
+
+/// Lists the schedules of the caller or, for managers, of another user.
+pub async fn list_target_hours(
+    Query(query): Query<TargetHoursQuery>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<Json<Vec<TargetHours>>, AppError> {
+    let user_id = query.user.or(caller.user_id.clone());
+    authorize_user(&context, &caller, user_id.as_deref(), Role::Manager).await?;
+    let target_hours = context
+        .target_hours_repository
+        .get_target_hours(user_id.as_deref())
+        .await?;
+    Ok(Json(target_hours))
+}
+
+pub async fn add_target_hours(
+    caller: Caller,
+    State(context): State<ApiContext>,
+    Json(mut target_hours): Json<NewTargetHours>,
+) -> Result<Json<TargetHours>, AppError> {
+    require_role(&context, &caller, Role::Admin).await?;
+    if !target_hours.has_valid_targets() {
+        return Err(AppError::InvalidInput(
+            "Targets must be between 0 and 86400 seconds",
+        ));
+    }
+    if target_hours.user_id.is_none() {
+        target_hours.user_id = caller.user_id;
+    }
+    let target_hours = context
+        .target_hours_repository
+        .add_target_hours(target_hours)
+        .await?;
+    Ok(Json(target_hours))
+}
+
+pub async fn delete_target_hours(
+    Path(id): Path<i64>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<(), AppError> {
+    require_role(&context, &caller, Role::Admin).await?;
+    context
+        .target_hours_repository
+        .delete_target_hours(id)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use axum::Json;
+use axum::extract::State;
+
+use crate::ApiContext;
+use crate::caller::Caller;
+use crate::error::AppError;
+use crate::models::time_zone::SetTimeZone;
+use crate::models::time_zone::UserTimeZone;
+
+/// Returns the time zone the user chose or else the configured one.
+pub(crate) async fn user_time_zone(
+    context: &ApiContext,
+    user_id: Option<&str>,
+) -> Result<UserTimeZone, AppError> {
+    let personal = match user_id {
+        Some(user_id) => context.time_zone_repository.get_time_zone(user_id).await?,
+        None => None,
+    };
+    Ok(UserTimeZone {
+        time_zone: personal.unwrap_or(context.settings.time_zone),
+        personal: personal.is_some(),
+    })
+}
+
+pub async fn get_time_zone(
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<Json<UserTimeZone>, AppError> {
+    let time_zone = user_time_zone(&context, caller.user_id.as_deref()).await?;
+    Ok(Json(time_zone))
+}
+
+/// Sets the caller's time zone, e.g. `{"time_zone": "Europe/Berlin"}`, in which
+/// the days of their reports start and end.
+pub async fn set_time_zone(
+    caller: Caller,
+    State(context): State<ApiContext>,
+    Json(request): Json<SetTimeZone>,
+) -> Result<Json<UserTimeZone>, AppError> {
+    let user_id = caller.require_user()?;
+    context
+        .time_zone_repository
+        .set_time_zone(user_id, request.time_zone)
+        .await?;
+    Ok(Json(UserTimeZone {
+        time_zone: request.time_zone,
+        personal: true,
+    }))
+}
+
+/// Removes the caller's time zone, so the configured one applies again.
+pub async fn delete_time_zone(
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<(), AppError> {
+    let user_id = caller.require_user()?;
+    context
+        .time_zone_repository
+        .delete_time_zone(user_id)
+        .await?;
+    Ok(())
+}
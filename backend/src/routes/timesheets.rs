@@ -0,0 +1,174 @@
+use axum::Json;
+use axum::extract::Path;
+use axum::extract::Query;
+use axum::extract::State;
+use chrono::Days;
+use chrono::Utc;
+use serde::Deserialize;
+
+use crate::ApiContext;
+use crate::caller::Caller;
+use crate::error::AppError;
+use crate::events::TimerEvent;
+use crate::models::permission::Role;
+use crate::models::timesheet::Timesheet;
+use crate::models::timesheet::TimesheetDetails;
+use crate::models::timesheet::TimesheetReview;
+use crate::models::timesheet::TimesheetStatus;
+use crate::routes::periods::month_bounds;
+use crate::routes::periods::month_days;
+use crate::routes::permissions::authorize_user;
+use crate::routes::permissions::caller_role;
+use crate::routes::permissions::require_role;
+use crate::routes::reports::user_flextime;
+use crate::routes::reports::user_summary;
+
+#[derive(Deserialize)]
+pub struct TimesheetQuery {
+    status: Option<TimesheetStatus>,
+}
+
+fn publish(context: &ApiContext, timesheet: &Timesheet) {
+    context.events.publish(TimerEvent::TimesheetChanged {
+        user_id: timesheet.user_id.clone(),
+        year: timesheet.year,
+        month: timesheet.month,
+        status: timesheet.status,
+    });
+}
+
+/// Lists the timesheets, e.g. the ones that wait for a review with
+/// `status=submitted`. Managers see everyone's timesheets, members only their
+/// own.
+pub async fn list_timesheets(
+    Query(query): Query<TimesheetQuery>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<Json<Vec<Timesheet>>, AppError> {
+    let user_id = if caller_role(&context, &caller).await? >= Role::Manager {
+        None
+    } else {
+        Some(caller.require_user()?)
+    };
+    let timesheets = context
+        .timesheet_repository
+        .get_timesheets(user_id, query.status)
+        .await?;
+    Ok(Json(timesheets))
+}
+
+/// Returns the timesheet with the summary and flextime balance of the month,
+/// so reviewers see the hours they approve. Only the owner and managers may
+/// read it.
+pub async fn get_timesheet(
+    Path((user_id, year, month)): Path<(String, i32, u32)>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<Json<TimesheetDetails>, AppError> {
+    authorize_user(&context, &caller, Some(&user_id), Role::Manager).await?;
+    let timesheet = context
+        .timesheet_repository
+        .get_timesheet(&user_id, year, month)
+        .await?;
+    let events = context
+        .timesheet_repository
+        .get_timesheet_events(&user_id, year, month)
+        .await?;
+
+    let (starts_at, ends_at) = month_bounds(&context, year, month)?;
+    let summary = user_summary(&context, Some(&user_id), starts_at, ends_at).await?;
+    let (first_day, next_month) = month_days(year, month)?;
+    let last_day = next_month - Days::new(1);
+    let tz = context.settings.time_zone;
+    let flextime = user_flextime(&context, Some(&user_id), Some(first_day), last_day, &tz).await?;
+
+    Ok(Json(TimesheetDetails {
+        timesheet,
+        events,
+        summary,
+        flextime,
+    }))
+}
+
+/// Submits the caller's timesheet of a past month for approval.
+pub async fn submit_timesheet(
+    Path((year, month)): Path<(i32, u32)>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<Json<Timesheet>, AppError> {
+    let (_, ends_at) = month_bounds(&context, year, month)?;
+    if ends_at > Utc::now() {
+        return Err(AppError::InvalidInput(
+            "Only timesheets of past months can be submitted",
+        ));
+    }
+    let user_id = caller.require_user()?;
+    let timesheet = context
+        .timesheet_repository
+        .submit_timesheet(user_id, year, month)
+        .await?;
+    publish(&context, &timesheet);
+    Ok(Json(timesheet))
+}
+
+async fn review_timesheet(
+    context: &ApiContext,
+    (user_id, year, month): (String, i32, u32),
+    caller: &Caller,
+    status: TimesheetStatus,
+    comment: Option<&str>,
+) -> Result<Json<Timesheet>, AppError> {
+    let reviewer = caller.require_user()?;
+    require_role(context, caller, Role::Manager).await?;
+    if reviewer == user_id {
+        return Err(AppError::InvalidInput(
+            "Timesheets must be reviewed by someone else",
+        ));
+    }
+    let timesheet = context
+        .timesheet_repository
+        .review_timesheet(&user_id, year, month, status, reviewer, comment)
+        .await?;
+    publish(context, &timesheet);
+    Ok(Json(timesheet))
+}
+
+pub async fn approve_timesheet(
+    Path(timesheet): Path<(String, i32, u32)>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+    Json(review): Json<TimesheetReview>,
+) -> Result<Json<Timesheet>, AppError> {
+    review_timesheet(
+        &context,
+        timesheet,
+        &caller,
+        TimesheetStatus::Approved,
+        review.comment.as_deref(),
+    )
+    .await
+}
+
+/// Rejects a submitted timesheet. The comment tells the user what to correct.
+pub async fn reject_timesheet(
+    Path(timesheet): Path<(String, i32, u32)>,
+    caller: Caller,
+    State(context): State<ApiContext>,
+    Json(review): Json<TimesheetReview>,
+) -> Result<Json<Timesheet>, AppError> {
+    let Some(comment) = review
+        .comment
+        .as_deref()
+        .filter(|comment| !comment.trim().is_empty())
+    else {
+        return Err(AppError::InvalidInput("A rejection requires a comment"));
+    };
+    review_timesheet(
+        &context,
+        timesheet,
+        &caller,
+        TimesheetStatus::Rejected,
+        Some(comment),
+    )
+    .await
+}
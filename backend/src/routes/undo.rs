@@ -0,0 +1,27 @@
+use axum::Json;
+use axum::extract::State;
+use chrono::Utc;
+
+use crate::ApiContext;
+use crate::caller::Caller;
+use crate::error::AppError;
+use crate::events::TimerEvent;
+use crate::models::action::Action;
+
+/// Reverts the most recent action of the caller within the undo window and
+/// returns it. Calling it again reverts the action before.
+pub async fn undo(
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<Json<Action>, AppError> {
+    let performed_after = Utc::now() - context.settings.undo_window;
+    let action = context
+        .journal_repository
+        .undo_last_action(&caller, performed_after)
+        .await?;
+
+    context.events.publish(TimerEvent::HistoryChanged {
+        user_id: caller.user_id.clone(),
+    });
+    Ok(Json(action))
+}
@@ -0,0 +1,127 @@
+use axum::extract::State;
+use axum::extract::WebSocketUpgrade;
+use axum::extract::ws::Message;
+use axum::extract::ws::WebSocket;
+use axum::response::Response;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::ApiContext;
+use crate::caller::Caller;
+use crate::error::AppError;
+use crate::events::TimerEvent;
+use crate::models::permission::Role;
+use crate::models::region::CurrentlyActiveRegion;
+use crate::models::region::Region;
+use crate::repositories::region_repositories::RepositoryError;
+use crate::routes::permissions::caller_role;
+use crate::routes::start_region;
+use crate::routes::stop_region;
+
+/// A command sent by the client.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Command {
+    Start { region: Region },
+    Stop { region: Region },
+    Status,
+}
+
+/// A message sent to the client, either as the reply to a command or when
+/// another client changed a timer.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage {
+    Status(CurrentlyActiveRegion),
+    Error {
+        message: String,
+    },
+    #[serde(untagged)]
+    Event(TimerEvent),
+}
+
+/// Upgrades to a WebSocket that accepts `start`, `stop` and `status` commands.
+/// Every command is answered with the current `status` or an `error`, and the
+/// caller's timer events are pushed as they happen. The commands are performed
+/// on behalf of the client that opened the socket.
+pub async fn websocket(
+    ws: WebSocketUpgrade,
+    caller: Caller,
+    State(context): State<ApiContext>,
+) -> Result<Response, AppError> {
+    let role = caller_role(&context, &caller).await?;
+    Ok(ws.on_upgrade(move |socket| handle_socket(socket, context, caller, role)))
+}
+
+async fn handle_socket(mut socket: WebSocket, context: ApiContext, caller: Caller, role: Role) {
+    let mut events = context.events.subscribe();
+
+    loop {
+        let reply = tokio::select! {
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => handle_command(&context, &caller, &text).await,
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+            event = events.recv() => match event {
+                Ok(event) if event.is_visible_to(caller.user_id.as_deref(), role) => {
+                    ServerMessage::Event(event)
+                }
+                Ok(_) => continue,
+                // Missed events are replaced by the current state
+                Err(RecvError::Lagged(_)) => status(&context, &caller).await,
+                Err(RecvError::Closed) => break,
+            },
+        };
+
+        let Ok(reply) = serde_json::to_string(&reply) else {
+            break;
+        };
+        if socket.send(Message::Text(reply.into())).await.is_err() {
+            break;
+        }
+    }
+}
+
+async fn handle_command(context: &ApiContext, caller: &Caller, text: &str) -> ServerMessage {
+    let command = match serde_json::from_str::<Command>(text) {
+        Ok(command) => command,
+        Err(e) => {
+            return ServerMessage::Error {
+                message: format!("Invalid command: {}", e),
+            };
+        }
+    };
+
+    let result = match command {
+        Command::Start { region } => start_region(context, region, caller).await,
+        Command::Stop { region } => stop_region(context, region, caller).await.map(|_| ()),
+        Command::Status => Ok(()),
+    };
+
+    match result {
+        Ok(()) => status(context, caller).await,
+        Err(e) => error(e),
+    }
+}
+
+async fn status(context: &ApiContext, caller: &Caller) -> ServerMessage {
+    match context
+        .region_repository
+        .currently_active_timer(caller.user_id.as_deref())
+        .await
+    {
+        Ok(active) => ServerMessage::Status(active),
+        Err(e) => error(e),
+    }
+}
+
+fn error(error: RepositoryError) -> ServerMessage {
+    if let RepositoryError::DatabaseError(ref e) = error {
+        eprintln!("{}", e);
+    }
+    ServerMessage::Error {
+        message: error.to_string(),
+    }
+}
@@ -0,0 +1,323 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::DateTime;
+use chrono::Days;
+use chrono::NaiveTime;
+use chrono::TimeDelta;
+use chrono::Utc;
+use chrono_tz::Tz;
+use tokio::task::JoinHandle;
+
+use crate::configuration::Configuration;
+use crate::events::Events;
+use crate::events::TimerEvent;
+use crate::models::region_history::OwnedEntry;
+use crate::reports::local_to_utc;
+use crate::repositories::idempotency_repositories::IdempotencyRepository;
+use crate::repositories::journal_repositories::JournalRepository;
+use crate::repositories::region_repositories::RegionRepository;
+use crate::repositories::region_repositories::RepositoryError;
+
+/// How often the background tasks check for work.
+const TASK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Decides when a forgotten timer is stopped automatically. A timer is stopped
+/// once it ran for `max_duration` or once the daily `cutoff` passed, whichever
+/// comes first. The cutoff is a local time of `time_zone`.
+#[derive(Debug, Clone)]
+pub struct AutoStopPolicy {
+    pub max_duration: Option<TimeDelta>,
+    pub cutoff: Option<NaiveTime>,
+    pub time_zone: Tz,
+}
+
+impl Default for AutoStopPolicy {
+    fn default() -> Self {
+        Self {
+            max_duration: None,
+            cutoff: None,
+            time_zone: Tz::UTC,
+        }
+    }
+}
+
+impl From<&Configuration> for AutoStopPolicy {
+    fn from(configuration: &Configuration) -> Self {
+        Self {
+            max_duration: configuration.auto_stop_max_duration.map(TimeDelta::seconds),
+            cutoff: configuration.auto_stop_cutoff,
+            time_zone: configuration.time_zone(),
+        }
+    }
+}
+
+impl AutoStopPolicy {
+    pub fn is_enabled(&self) -> bool {
+        self.max_duration.is_some() || self.cutoff.is_some()
+    }
+
+    /// Returns the point in time at which a timer started at `start_time` is
+    /// stopped, or `None` if timers are never stopped automatically.
+    pub fn deadline(&self, start_time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let by_duration = self
+            .max_duration
+            .map(|max_duration| start_time + max_duration);
+        let by_cutoff = self.cutoff.map(|cutoff| {
+            let date = start_time.with_timezone(&self.time_zone).date_naive();
+            let same_day = local_to_utc(date, cutoff, &self.time_zone);
+            if same_day > start_time {
+                same_day
+            } else {
+                local_to_utc(date + Days::new(1), cutoff, &self.time_zone)
+            }
+        });
+
+        match (by_duration, by_cutoff) {
+            (Some(duration), Some(cutoff)) => Some(duration.min(cutoff)),
+            (duration, cutoff) => duration.or(cutoff),
+        }
+    }
+}
+
+/// Stops the running timers of all users whose deadline already passed, each
+/// at its deadline. Returns the stopped entries.
+pub async fn auto_stop(
+    repository: &dyn RegionRepository,
+    policy: &AutoStopPolicy,
+    now: DateTime<Utc>,
+) -> Result<Vec<OwnedEntry>, RepositoryError> {
+    let mut stopped = Vec::new();
+    for running in repository.get_running_timers().await? {
+        if let Some(deadline) = policy.deadline(running.entry.start_time)
+            && deadline <= now
+            && let Some(entry) = repository
+                .auto_stop_timer(running.entry.id, deadline)
+                .await?
+        {
+            stopped.push(OwnedEntry {
+                user_id: running.user_id,
+                entry,
+            });
+        }
+    }
+
+    Ok(stopped)
+}
+
+/// Periodically stops forgotten timers in the background and notifies the
+/// connected clients.
+pub fn spawn_auto_stop_task(
+    repository: Arc<dyn RegionRepository>,
+    policy: AutoStopPolicy,
+    events: Events,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(TASK_INTERVAL);
+        loop {
+            interval.tick().await;
+            match auto_stop(repository.as_ref(), &policy, Utc::now()).await {
+                Ok(stopped) => {
+                    for OwnedEntry { user_id, entry } in stopped {
+                        println!(
+                            "Automatically stopped the timer for {:?} at {:?}",
+                            entry.region, entry.stop_time
+                        );
+                        events.publish(TimerEvent::TimerStopped {
+                            user_id,
+                            region: entry.region,
+                            duration: entry.duration.unwrap_or_default(),
+                            auto_stopped: true,
+                        });
+                    }
+                }
+                Err(e) => eprintln!("Failed to automatically stop the timers: {}", e),
+            }
+        }
+    })
+}
+
+/// Periodically deletes the responses of idempotency keys whose retention
+/// window expired.
+pub fn spawn_idempotency_purge_task(
+    repository: Arc<dyn IdempotencyRepository>,
+    retention: TimeDelta,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(TASK_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = repository.purge_responses(Utc::now() - retention).await {
+                eprintln!("Failed to purge idempotency keys: {}", e);
+            }
+        }
+    })
+}
+
+/// Periodically deletes the actions that can no longer be undone.
+pub fn spawn_journal_purge_task(
+    repository: Arc<dyn JournalRepository>,
+    undo_window: TimeDelta,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(TASK_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = repository.purge_actions(Utc::now() - undo_window).await {
+                eprintln!("Failed to purge expired actions: {}", e);
+            }
+        }
+    })
+}
+
+/// Periodically deletes the entries that were in the trash for longer than
+/// the retention period.
+pub fn spawn_trash_purge_task(
+    repository: Arc<dyn RegionRepository>,
+    retention: TimeDelta,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(TASK_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = repository
+                .purge_deleted_entries(Utc::now() - retention)
+                .await
+            {
+                eprintln!("Failed to purge the trash: {}", e);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::SqlitePool;
+
+    use super::*;
+    use crate::models::region::Region;
+    use crate::repositories::region_repositories::SqliteRegionRepository;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        format!("2025-10-{day:02}T{hour:02}:{minute:02}:00Z")
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_deadline_by_cutoff() {
+        let policy = AutoStopPolicy {
+            max_duration: None,
+            cutoff: Some(NaiveTime::from_hms_opt(20, 0, 0).unwrap()),
+            time_zone: Tz::UTC,
+        };
+
+        assert_eq!(policy.deadline(at(1, 8, 0)), Some(at(1, 20, 0)));
+        assert_eq!(policy.deadline(at(1, 22, 0)), Some(at(2, 20, 0)));
+    }
+
+    #[test]
+    fn test_deadline_uses_whatever_comes_first() {
+        let policy = AutoStopPolicy {
+            max_duration: Some(TimeDelta::hours(10)),
+            cutoff: Some(NaiveTime::from_hms_opt(20, 0, 0).unwrap()),
+            time_zone: Tz::UTC,
+        };
+
+        assert_eq!(policy.deadline(at(1, 8, 0)), Some(at(1, 18, 0)));
+        assert_eq!(policy.deadline(at(1, 12, 0)), Some(at(1, 20, 0)));
+        assert_eq!(AutoStopPolicy::default().deadline(at(1, 8, 0)), None);
+    }
+
+    #[test]
+    fn test_deadline_by_cutoff_in_time_zone() {
+        let policy = AutoStopPolicy {
+            max_duration: None,
+            cutoff: Some(NaiveTime::from_hms_opt(20, 0, 0).unwrap()),
+            time_zone: Tz::Europe__Berlin,
+        };
+
+        // 20:00 in Berlin is 18:00 UTC during daylight saving time
+        assert_eq!(policy.deadline(at(1, 8, 0)), Some(at(1, 18, 0)));
+        assert_eq!(policy.deadline(at(1, 19, 0)), Some(at(2, 18, 0)));
+        // 23:30 UTC is already the next day in Berlin
+        assert_eq!(policy.deadline(at(1, 23, 30)), Some(at(2, 18, 0)));
+    }
+
+    #[sqlx::test]
+    async fn test_auto_stop_forgotten_timer(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        sqlx::query(
+            r#"
+            INSERT INTO region_history (region, start_time)
+            VALUES ('ac1', '2025-10-01T08:00:00+00:00')
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+        let repo = SqliteRegionRepository::new(pool);
+        let policy = AutoStopPolicy {
+            max_duration: Some(TimeDelta::hours(10)),
+            cutoff: None,
+            time_zone: Tz::UTC,
+        };
+
+        // When
+        let before_deadline = auto_stop(&repo, &policy, at(1, 17, 0)).await.unwrap();
+        let after_deadline = auto_stop(&repo, &policy, at(2, 7, 0)).await.unwrap();
+
+        // Then
+        assert!(before_deadline.is_empty(), "Timer should keep running");
+        let [OwnedEntry { entry: stopped, .. }] = after_deadline.as_slice() else {
+            panic!("Timer should be stopped");
+        };
+        assert_eq!(stopped.region, Region::Ac1);
+        assert_eq!(stopped.stop_time, Some(at(1, 18, 0)), "Stopped at deadline");
+        assert_eq!(stopped.duration, Some(36_000_000));
+        assert!(
+            stopped.auto_stopped,
+            "Entry should be marked as auto-stopped"
+        );
+
+        let review = repo.get_auto_stopped_history(None).await.unwrap();
+        assert_eq!(review.len(), 1, "Entry should be listed for review");
+        assert!(repo.get_running_timer(None).await.unwrap().is_none());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_auto_stop_splits_entry_at_midnight(pool: SqlitePool) -> sqlx::Result<()> {
+        // Given
+        sqlx::query(
+            r#"
+            INSERT INTO region_history (region, start_time)
+            VALUES ('ac1', '2025-10-01T20:00:00+00:00')
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+        let repo = SqliteRegionRepository::new(pool).with_split_on_stop(true);
+        let policy = AutoStopPolicy {
+            max_duration: Some(TimeDelta::hours(10)),
+            cutoff: None,
+            time_zone: Tz::UTC,
+        };
+
+        // When
+        let stopped = auto_stop(&repo, &policy, at(2, 7, 0)).await.unwrap();
+        let [OwnedEntry { entry: stopped, .. }] = stopped.as_slice() else {
+            panic!("Timer should be stopped");
+        };
+
+        // Then
+        assert_eq!(stopped.duration, Some(36_000_000));
+        let review = repo.get_auto_stopped_history(None).await.unwrap();
+        assert_eq!(review.len(), 2, "The entry should be split in two days");
+        assert_eq!(review[0].start_time, at(2, 0, 0));
+        assert_eq!(review[0].stop_time, Some(at(2, 6, 0)));
+        assert_eq!(review[1].stop_time, Some(at(2, 0, 0)));
+
+        Ok(())
+    }
+}
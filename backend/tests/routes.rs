@@ -4,9 +4,30 @@ use axum::body::Body;
 use axum::http::Request;
 use axum::http::StatusCode;
 use backend::ApiContext;
+use backend::SqliteAbsenceRepository;
+use backend::SqliteAuditRepository;
+use backend::SqliteBreakRepository;
+use backend::SqliteDeviceRepository;
+use backend::SqliteHolidayRepository;
+use backend::SqliteIdempotencyRepository;
+use backend::SqliteJournalRepository;
+use backend::SqliteKioskRepository;
+use backend::SqlitePeriodRepository;
+use backend::SqlitePermissionRepository;
 use backend::SqliteRegionRepository;
+use backend::SqliteRoundingRepository;
+use backend::SqliteSyncRepository;
+use backend::SqliteTargetHoursRepository;
+use backend::SqliteTimeZoneRepository;
+use backend::SqliteTimesheetRepository;
 use backend::app;
+use backend::configuration::KioskSettings;
+use backend::configuration::MinDuration;
+use backend::configuration::Settings;
+use backend::configuration::ShortEntryAction;
+use backend::events::Events;
 use chrono::DateTime;
+use chrono::Datelike;
 use chrono::TimeDelta;
 use chrono::Utc;
 use http_body_util::BodyExt;
@@ -20,8 +41,48 @@ use crate::utils::RouterExt;
 mod utils;
 
 fn setup_api_context(pool: SqlitePool) -> ApiContext {
-    let region_repository = Arc::new(SqliteRegionRepository::new(pool));
-    ApiContext { region_repository }
+    let region_repository = Arc::new(SqliteRegionRepository::new(pool.clone()));
+    let break_repository = Arc::new(SqliteBreakRepository::new(pool.clone()));
+    let target_hours_repository = Arc::new(SqliteTargetHoursRepository::new(pool.clone()));
+    let holiday_repository = Arc::new(SqliteHolidayRepository::new(pool.clone()));
+    let absence_repository = Arc::new(SqliteAbsenceRepository::new(pool.clone()));
+    let idempotency_repository = Arc::new(SqliteIdempotencyRepository::new(pool.clone()));
+    let sync_repository = Arc::new(SqliteSyncRepository::new(pool.clone()));
+    let rounding_repository = Arc::new(SqliteRoundingRepository::new(pool.clone()));
+    let journal_repository = Arc::new(SqliteJournalRepository::new(pool.clone()));
+    let audit_repository = Arc::new(SqliteAuditRepository::new(pool.clone()));
+    let period_repository = Arc::new(SqlitePeriodRepository::new(pool.clone()));
+    let timesheet_repository = Arc::new(SqliteTimesheetRepository::new(pool.clone()));
+    let permission_repository = Arc::new(SqlitePermissionRepository::new(pool.clone()));
+    let kiosk_repository = Arc::new(SqliteKioskRepository::new(pool.clone()));
+    let device_repository = Arc::new(SqliteDeviceRepository::new(pool.clone()));
+    let time_zone_repository = Arc::new(SqliteTimeZoneRepository::new(pool));
+    ApiContext {
+        region_repository,
+        break_repository,
+        target_hours_repository,
+        holiday_repository,
+        absence_repository,
+        idempotency_repository,
+        sync_repository,
+        rounding_repository,
+        journal_repository,
+        audit_repository,
+        period_repository,
+        timesheet_repository,
+        permission_repository,
+        kiosk_repository,
+        device_repository,
+        time_zone_repository,
+        settings: Settings {
+            admins: vec!["admin".to_string()],
+            trust_user_id_header: true,
+            setup_token: Some("test-setup".to_string()),
+            kiosk: Some(KioskSettings::new("test-secret")),
+            ..Settings::default()
+        },
+        events: Events::default(),
+    }
 }
 
 #[sqlx::test]
@@ -104,9 +165,7 @@ async fn test_stop_timer(pool: SqlitePool) {
     )
     .await;
 
-    // We need to wait for more than 1s, as the duration calculated by sqlite is in
-    // seconds
-    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
 
     // When
     let response = app
@@ -125,7 +184,10 @@ async fn test_stop_timer(pool: SqlitePool) {
     let body = response.into_body().collect().await.unwrap().to_bytes();
     let parsed: Value = serde_json::from_slice(body.iter().as_slice()).unwrap();
     let duration = parsed["duration"].as_i64().unwrap();
-    assert!(duration >= 1, "The duration should be around 1 second");
+    assert!(
+        duration >= 50,
+        "The duration should be at least 50 milliseconds"
+    );
 
     // Then: Internal state
     let history = sqlx::query!(
@@ -140,7 +202,7 @@ async fn test_stop_timer(pool: SqlitePool) {
     assert!(history[0].stop_time.is_some());
     assert!(!history[0].start_time.is_empty());
     assert!(history[0].duration.is_some());
-    assert!(history[0].duration.unwrap() >= 1);
+    assert!(history[0].duration.unwrap() >= 50);
 }
 
 #[sqlx::test]
@@ -186,7 +248,6 @@ async fn test_stop_timer_when_no_time_was_started(pool: SqlitePool) {
 // TODO: test what happens, if we start a timer while another timer for another
 // region is running
 
-// TODO: Test the general history route, instead of the region history route
 #[sqlx::test]
 async fn test_history_is_empty_if_no_timer_ever_existed(pool: SqlitePool) {
     // Cloning the pool results in a new pool that is tied to the same shared
@@ -289,9 +350,7 @@ async fn test_history_by_region_returns_a_stopped_timer(pool: SqlitePool) {
     )
     .await;
 
-    // We need to wait for more than 1s, as the duration calculated by sqlite is in
-    // seconds
-    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
 
     app.call_request(
         Request::builder()
@@ -328,7 +387,7 @@ async fn test_history_by_region_returns_a_stopped_timer(pool: SqlitePool) {
     assert!(history[0].stop_time.unwrap() > now.checked_sub_signed(TimeDelta::seconds(5)).unwrap());
     assert!(history[0].stop_time.unwrap() > history[0].start_time);
     assert!(history[0].duration.is_some());
-    assert!(history[0].duration.unwrap() >= 1);
+    assert!(history[0].duration.unwrap() >= 50);
 }
 
 #[sqlx::test]
@@ -369,9 +428,7 @@ async fn test_currently_active_timer_when_none_is_active(pool: SqlitePool) {
     )
     .await;
 
-    // We need to wait for more than 1s, as the duration calculated by sqlite is in
-    // seconds
-    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
 
     app.call_request(
         Request::builder()
@@ -417,9 +474,7 @@ async fn test_currently_active_timer_when_timer_is_active(pool: SqlitePool) {
     )
     .await;
 
-    // We need to wait for more than 1s, as the duration calculated by sqlite is in
-    // seconds
-    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
 
     // When
     let response = app
@@ -437,5 +492,2057 @@ async fn test_currently_active_timer_when_timer_is_active(pool: SqlitePool) {
     let body = response.into_body().collect().await.unwrap().to_bytes();
     let history = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
     assert_eq!(history["region"], "ac1");
-    assert_eq!(history["duration"], 1)
+    assert!(history["duration"].as_i64().unwrap() >= 50)
+}
+
+#[sqlx::test]
+async fn test_break_interrupts_and_resumes_timer(pool: SqlitePool) {
+    // Given
+    let mut app = app(setup_api_context(pool));
+
+    app.call_request(
+        Request::builder()
+            .uri("/api/ac1/start")
+            .method("POST")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+
+    // When
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/break/start")
+                .method("POST")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+
+    // Then: the timer is interrupted
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/currently_active")
+                .method("GET")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let active = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    assert_eq!(active["region"], JsonValue::Null);
+    assert_eq!(active["on_break"], true);
+
+    // When
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/break/stop")
+                .method("POST")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+
+    // Then: the timer is resumed
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/currently_active")
+                .method("GET")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let active = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    assert_eq!(active["region"], "ac1");
+    assert_eq!(active["on_break"], false);
+}
+
+#[sqlx::test]
+async fn test_stop_break_when_no_break_was_started(pool: SqlitePool) {
+    let app = app(setup_api_context(pool));
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/break/stop")
+                .method("POST")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    assert_eq!(&body[..], b"No break is running");
+}
+
+#[sqlx::test]
+async fn test_summary_separates_work_and_breaks(pool: SqlitePool) {
+    // Given
+    sqlx::query(
+        r#"
+        INSERT INTO region_history (region, start_time, stop_time, duration)
+        VALUES ('ac1', '2025-10-01T08:00:00+00:00', '2025-10-01T12:00:00+00:00', 14400000),
+               ('aa2', '2025-10-01T12:30:00+00:00', '2025-10-01T16:00:00+00:00', 12600000)
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        r#"
+        INSERT INTO break_history (start_time, stop_time, duration, interrupted_region)
+        VALUES ('2025-10-01T12:00:00+00:00', '2025-10-01T12:30:00+00:00', 1800000, NULL)
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let app = app(setup_api_context(pool));
+
+    // When
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/summary?from=2025-10-01T00:00:00Z&to=2025-10-02T00:00:00Z")
+                .method("GET")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // Then
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let summary = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    assert_eq!(summary["work_duration"], 27_000_000);
+    assert_eq!(summary["break_duration"], 1_800_000);
+    assert_eq!(summary["break_count"], 1);
+    assert_eq!(summary["regions"][0]["region"], "aa2");
+    assert_eq!(summary["regions"][0]["duration"], 12_600_000);
+    assert_eq!(summary["regions"][1]["region"], "ac1");
+    assert_eq!(summary["regions"][1]["duration"], 14_400_000);
+}
+
+#[sqlx::test]
+async fn test_summary_rounds_billable_duration(pool: SqlitePool) {
+    // Given: 50 minutes on Ac1
+    sqlx::query(
+        r#"
+        INSERT INTO region_history (region, start_time, stop_time, duration)
+        VALUES ('ac1', '2025-10-01T08:00:00+00:00', '2025-10-01T08:50:00+00:00', 3000000)
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let mut app = app(setup_api_context(pool));
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/rounding/ac1")
+                .method("PUT")
+                .header("User-Id", "admin")
+                .header("Content-Type", "application/json")
+                .body(Body::from(r#"{"mode": "up", "increment": 15}"#))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // When
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/summary?from=2025-10-01T00:00:00Z&to=2025-10-02T00:00:00Z")
+                .method("GET")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+
+    // Then
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let summary = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    assert_eq!(summary["work_duration"], 3_000_000);
+    assert_eq!(summary["billable_duration"], 3_600_000);
+    assert_eq!(summary["regions"][0]["billable_duration"], 3_600_000);
+}
+
+#[sqlx::test]
+async fn test_rounding_increment_out_of_range(pool: SqlitePool) {
+    let mut app = app(setup_api_context(pool));
+
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/rounding/ac1")
+                .method("PUT")
+                .header("User-Id", "admin")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    r#"{"mode": "up", "increment": 9223372036854775807}"#,
+                ))
+                .unwrap(),
+        )
+        .await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn test_flextime_balance_against_target_hours(pool: SqlitePool) {
+    // Given: Anna has 8 hours on weekdays and worked 9 hours on Monday
+    // 2025-09-29
+    sqlx::query(
+        r#"
+        INSERT INTO region_history (region, start_time, stop_time, duration, user_id)
+        VALUES ('ac1', '2025-09-29T07:00:00+00:00', '2025-09-29T16:00:00+00:00', 32400000, 'anna')
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let mut app = app(setup_api_context(pool));
+
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/target_hours")
+                .method("POST")
+                .header("User-Id", "admin")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    r#"{"user_id": "anna", "valid_from": "2025-09-01", "monday": 28800,
+                    "tuesday": 28800}"#,
+                ))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // When
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/flextime?from=2025-09-29&to=2025-09-30")
+                .method("GET")
+                .header("User-Id", "anna")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+
+    // Then
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let flextime = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    assert_eq!(flextime["days"][0]["date"], "2025-09-29");
+    assert_eq!(flextime["days"][0]["balance"], 3600);
+    assert_eq!(flextime["days"][1]["balance"], -28800);
+    assert_eq!(flextime["weeks"][0]["period"], "2025-W40");
+    assert_eq!(flextime["months"][0]["period"], "2025-09");
+    assert_eq!(flextime["overall"]["actual"], 32400);
+    assert_eq!(flextime["overall"]["target"], 57600);
+    assert_eq!(flextime["overall"]["balance"], -25200);
+
+    // Then: ranges beyond the supported years are rejected
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/flextime?from=0001-01-01&to=2025-09-30")
+                .method("GET")
+                .header("User-Id", "anna")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn test_target_hours_outside_of_a_day_are_rejected(pool: SqlitePool) {
+    let mut app = app(setup_api_context(pool));
+
+    for targets in [r#""monday": -1"#, r#""friday": 86401"#] {
+        let response = app
+            .call_request(
+                Request::builder()
+                    .uri("/api/target_hours")
+                    .method("POST")
+                    .header("User-Id", "admin")
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(format!(
+                        r#"{{"valid_from": "2025-09-01", {targets}}}"#
+                    )))
+                    .unwrap(),
+            )
+            .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
+
+#[sqlx::test]
+async fn test_holidays_for_year_and_state(pool: SqlitePool) {
+    let app = app(setup_api_context(pool));
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/holidays?year=2025&state=BY")
+                .method("GET")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let holidays = serde_json::from_slice::<Vec<Value>>(body.iter().as_slice()).unwrap();
+    assert_eq!(holidays.len(), 12);
+    assert_eq!(holidays[0]["date"], "2025-01-01");
+    assert_eq!(holidays[1]["name"], "Heilige Drei Könige");
+}
+
+#[sqlx::test]
+async fn test_holidays_for_unsupported_year(pool: SqlitePool) {
+    let mut app = app(setup_api_context(pool));
+
+    for year in [-2, 1582, 10000] {
+        let response = app
+            .call_request(
+                Request::builder()
+                    .uri(format!("/api/holidays?year={year}"))
+                    .method("GET")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
+
+#[sqlx::test]
+async fn test_custom_holidays_are_listed(pool: SqlitePool) {
+    // Given
+    let mut app = app(setup_api_context(pool));
+
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/holidays")
+                .method("POST")
+                .header("User-Id", "admin")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    r#"{"date": "2025-12-24", "name": "Heiligabend"}"#,
+                ))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // When
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/holidays?year=2025")
+                .method("GET")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+
+    // Then
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let holidays = serde_json::from_slice::<Vec<Value>>(body.iter().as_slice()).unwrap();
+    assert_eq!(holidays.len(), 10);
+    assert_eq!(holidays[7]["name"], "Heiligabend");
+    assert_eq!(holidays[7]["custom"], true);
+}
+
+#[sqlx::test]
+async fn test_vacation_is_recorded_on_working_days_only(pool: SqlitePool) {
+    // Given: Anna has 8 hours from Monday to Friday and 30 vacation days
+    let mut app = app(setup_api_context(pool));
+
+    app.call_request(
+        Request::builder()
+            .uri("/api/target_hours")
+            .method("POST")
+            .header("User-Id", "admin")
+            .header("Content-Type", "application/json")
+            .body(Body::from(
+                r#"{"user_id": "anna", "valid_from": "2025-01-01", "monday": 28800, "tuesday": 28800,
+                    "wednesday": 28800, "thursday": 28800, "friday": 28800}"#,
+            ))
+            .unwrap(),
+    )
+    .await;
+    app.call_request(
+        Request::builder()
+            .uri("/api/vacation/2025")
+            .method("PUT")
+            .header("User-Id", "admin")
+            .header("Content-Type", "application/json")
+            .body(Body::from(r#"{"user_id": "anna", "days": 30}"#))
+            .unwrap(),
+    )
+    .await;
+
+    // When: taking vacation from Thursday until Tuesday, with the Tag der
+    // Deutschen Einheit on Friday
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/absences")
+                .method("POST")
+                .header("User-Id", "anna")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    r#"{"from": "2025-10-02", "to": "2025-10-07", "kind": "vacation"}"#,
+                ))
+                .unwrap(),
+        )
+        .await;
+
+    // Then
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let absences = serde_json::from_slice::<Vec<Value>>(body.iter().as_slice()).unwrap();
+    let dates: Vec<&str> = absences
+        .iter()
+        .map(|absence| absence["date"].as_str().unwrap())
+        .collect();
+    assert_eq!(dates, vec!["2025-10-02", "2025-10-06", "2025-10-07"]);
+
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/vacation?year=2025")
+                .method("GET")
+                .header("User-Id", "anna")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let vacation = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    assert_eq!(vacation["taken"], 3.0);
+    assert_eq!(vacation["remaining"], 27.0);
+
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/flextime?from=2025-10-02&to=2025-10-02")
+                .method("GET")
+                .header("User-Id", "anna")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let flextime = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    assert_eq!(flextime["days"][0]["absence"], "vacation");
+    assert_eq!(flextime["days"][0]["balance"], 0);
+
+    // Then: absences of more than a year are rejected
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/absences")
+                .method("POST")
+                .header("User-Id", "anna")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    r#"{"from": "2026-01-01", "to": "2027-01-01", "kind": "sick"}"#,
+                ))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn test_settings_require_admin(pool: SqlitePool) {
+    let mut app = app(setup_api_context(pool));
+    for (method, uri, body) in [
+        (
+            "PUT",
+            "/api/rounding/ac1",
+            r#"{"mode": "up", "increment": 15}"#,
+        ),
+        ("DELETE", "/api/rounding/ac1", ""),
+        (
+            "POST",
+            "/api/target_hours",
+            r#"{"valid_from": "2025-09-01"}"#,
+        ),
+        ("DELETE", "/api/target_hours/1", ""),
+        (
+            "POST",
+            "/api/holidays",
+            r#"{"date": "2025-12-24", "name": "Heiligabend"}"#,
+        ),
+        ("DELETE", "/api/holidays/1", ""),
+        ("PUT", "/api/vacation/2025", r#"{"days": 30}"#),
+    ] {
+        // When: Anna, a member, changes the settings
+        let response = app
+            .call_request(
+                Request::builder()
+                    .uri(uri)
+                    .method(method)
+                    .header("User-Id", "anna")
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await;
+
+        // Then
+        assert_eq!(response.status(), StatusCode::FORBIDDEN, "{method} {uri}");
+    }
+}
+
+#[sqlx::test]
+async fn test_absences_of_other_users_require_manager(pool: SqlitePool) {
+    // Given
+    let mut app = app(setup_api_context(pool));
+    let list = |user: &str| {
+        Request::builder()
+            .uri("/api/absences?from=2025-10-01&to=2025-10-31&user=ben")
+            .method("GET")
+            .header("User-Id", user)
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    // When
+    let member = app.call_request(list("anna")).await;
+    let own = app.call_request(list("ben")).await;
+    let admin = app.call_request(list("admin")).await;
+
+    // Then
+    assert_eq!(member.status(), StatusCode::FORBIDDEN);
+    assert_eq!(own.status(), StatusCode::OK);
+    assert_eq!(admin.status(), StatusCode::OK);
+}
+
+#[sqlx::test]
+async fn test_history_split_into_days(pool: SqlitePool) {
+    // Given
+    sqlx::query(
+        r#"
+        INSERT INTO region_history (region, start_time, stop_time, duration)
+        VALUES ('ac1', '2025-10-01T22:00:00+00:00', '2025-10-02T02:00:00+00:00', 14400000)
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let mut app = app(setup_api_context(pool));
+
+    // When
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/history?from=2025-10-01T00:00:00Z&to=2025-10-03T00:00:00Z&split_days=true")
+                .method("GET")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+
+    // Then
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let history = serde_json::from_slice::<Vec<TestRegionHistory>>(body.iter().as_slice()).unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].duration, Some(7_200_000));
+    assert_eq!(
+        history[1].start_time,
+        "2025-10-02T00:00:00Z".parse::<DateTime<Utc>>().unwrap()
+    );
+    assert_eq!(history[1].duration, Some(7_200_000));
+
+    // Then: stored data is unchanged
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/history?from=2025-10-01T00:00:00Z&to=2025-10-03T00:00:00Z")
+                .method("GET")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let history = serde_json::from_slice::<Vec<TestRegionHistory>>(body.iter().as_slice()).unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].duration, Some(14_400_000));
+}
+
+#[sqlx::test]
+async fn test_history_in_time_zone(pool: SqlitePool) {
+    // Given: 20:00 to 00:00 in Berlin
+    sqlx::query(
+        r#"
+        INSERT INTO region_history (region, start_time, stop_time, duration)
+        VALUES ('ac1', '2025-10-01T18:00:00+00:00', '2025-10-01T22:00:00+00:00', 14400000)
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let mut app = app(setup_api_context(pool));
+
+    // When
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/history?from=2025-10-01T00:00:00Z&to=2025-10-03T00:00:00Z&split_days=true&tz=Europe/Berlin&offsets=true")
+                .method("GET")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+
+    // Then: the entry does not span midnight in Berlin
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let history = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    assert_eq!(history.as_array().unwrap().len(), 1);
+    assert_eq!(history[0]["start_time"], "2025-10-01T20:00:00+02:00");
+    assert_eq!(history[0]["stop_time"], "2025-10-02T00:00:00+02:00");
+}
+
+#[sqlx::test]
+async fn test_history_in_time_zone_of_user(pool: SqlitePool) {
+    // Given: Anna works in Tokyo
+    sqlx::query(
+        r#"
+        INSERT INTO region_history (region, start_time, stop_time, duration, user_id)
+        VALUES ('ac1', '2025-10-01T00:00:00+00:00', '2025-10-01T04:00:00+00:00', 14400000, 'anna')
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let mut app = app(setup_api_context(pool));
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/time_zone")
+                .method("PUT")
+                .header("User-Id", "anna")
+                .header("Content-Type", "application/json")
+                .body(Body::from(r#"{"time_zone": "Asia/Tokyo"}"#))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // When
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/ac1/history?offsets=true")
+                .method("GET")
+                .header("User-Id", "anna")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+
+    // Then
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let history = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    assert_eq!(history[0]["start_time"], "2025-10-01T09:00:00+09:00");
+
+    // Then: other users keep the configured time zone
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/time_zone")
+                .method("GET")
+                .header("User-Id", "ben")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let time_zone = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    assert_eq!(time_zone["time_zone"], "UTC");
+    assert_eq!(time_zone["personal"], false);
+}
+
+#[sqlx::test]
+async fn test_history_with_unknown_time_zone(pool: SqlitePool) {
+    let mut app = app(setup_api_context(pool));
+
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/ac1/history?tz=Mars/Olympus")
+                .method("GET")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn test_events_are_streamed_to_subscribers(pool: SqlitePool) {
+    // Given
+    let mut app = app(setup_api_context(pool));
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/events")
+                .method("GET")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()["content-type"],
+        "text/event-stream",
+        "Events should be sent as server-sent events"
+    );
+    let mut body = response.into_body();
+
+    // When
+    app.call_request(
+        Request::builder()
+            .uri("/api/ac1/start")
+            .method("POST")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+
+    // Then
+    let frame = body.frame().await.unwrap().unwrap().into_data().unwrap();
+    let event = String::from_utf8(frame.to_vec()).unwrap();
+    assert_eq!(
+        event,
+        "event: timer_started\ndata: {\"type\":\"timer_started\",\"user_id\":null,\"region\":\"ac1\"}\n\n"
+    );
+}
+
+#[sqlx::test]
+async fn test_events_of_other_users_are_not_streamed(pool: SqlitePool) {
+    // Given: Anna listens for events
+    let mut app = app(setup_api_context(pool));
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/events")
+                .method("GET")
+                .header("User-Id", "anna")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    let mut body = response.into_body();
+
+    // When: Ben starts a timer before Anna does
+    for (user, region) in [("ben", "ac1"), ("anna", "ac2")] {
+        let response = app
+            .call_request(
+                Request::builder()
+                    .uri(format!("/api/{region}/start"))
+                    .method("POST")
+                    .header("User-Id", user)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    // Then: the first event Anna receives is her own
+    let frame = body.frame().await.unwrap().unwrap().into_data().unwrap();
+    let event = String::from_utf8(frame.to_vec()).unwrap();
+    assert!(event.contains(r#""user_id":"anna""#), "{event}");
+    assert!(event.contains(r#""region":"ac2""#), "{event}");
+}
+
+#[sqlx::test]
+async fn test_starting_another_region_streams_the_stopped_timer(pool: SqlitePool) {
+    // Given
+    let mut app = app(setup_api_context(pool));
+    app.call_request(
+        Request::builder()
+            .uri("/api/ac1/start")
+            .method("POST")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/events")
+                .method("GET")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    let mut body = response.into_body();
+
+    // When
+    app.call_request(
+        Request::builder()
+            .uri("/api/ac2/start")
+            .method("POST")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+
+    // Then
+    let frame = body.frame().await.unwrap().unwrap().into_data().unwrap();
+    let event = String::from_utf8(frame.to_vec()).unwrap();
+    assert!(
+        event.starts_with("event: timer_stopped\n"),
+        "The stopped timer should be announced first: {event}"
+    );
+    assert!(event.contains(r#""region":"ac1""#));
+    let frame = body.frame().await.unwrap().unwrap().into_data().unwrap();
+    let event = String::from_utf8(frame.to_vec()).unwrap();
+    assert!(event.starts_with("event: timer_started\n"));
+}
+
+#[sqlx::test]
+async fn test_websocket_commands_and_pushed_events(pool: SqlitePool) {
+    use futures_util::SinkExt;
+    use futures_util::StreamExt;
+    use tokio_tungstenite::tungstenite::Message;
+
+    // Given: a running server with two connected clients
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/api/ws", listener.local_addr().unwrap());
+    tokio::spawn(axum::serve(listener, app(setup_api_context(pool))).into_future());
+    let (mut tablet, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    let (mut other, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    async fn receive(
+        socket: &mut tokio_tungstenite::WebSocketStream<
+            tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+        >,
+    ) -> Value {
+        let message = socket.next().await.unwrap().unwrap();
+        serde_json::from_str(message.to_text().unwrap()).unwrap()
+    }
+
+    // When
+    tablet
+        .send(Message::text(r#"{"type":"start","region":"ac1"}"#))
+        .await
+        .unwrap();
+
+    // Then: the command is answered with the status
+    let first = receive(&mut tablet).await;
+    let second = receive(&mut tablet).await;
+    let status = [first, second]
+        .into_iter()
+        .find(|message| message["type"] == "status")
+        .expect("The command should be answered with the status");
+    assert_eq!(status["region"], "ac1");
+    assert_eq!(status["on_break"], false);
+
+    // Then: the other client is notified
+    let event = receive(&mut other).await;
+    assert_eq!(event["type"], "timer_started");
+    assert_eq!(event["region"], "ac1");
+
+    // When: a command fails
+    tablet
+        .send(Message::text(r#"{"type":"stop","region":"aa1"}"#))
+        .await
+        .unwrap();
+
+    // Then
+    let error = receive(&mut tablet).await;
+    assert_eq!(error["type"], "error");
+    assert_eq!(error["message"], "No timer is running for the region");
+
+    // When: the message is not a command
+    tablet.send(Message::text("pause")).await.unwrap();
+
+    // Then
+    let error = receive(&mut tablet).await;
+    assert_eq!(error["type"], "error");
+}
+
+#[sqlx::test]
+async fn test_toggle_timer(pool: SqlitePool) {
+    let mut app = app(setup_api_context(pool));
+    let toggle = || {
+        Request::builder()
+            .uri("/api/ac1/toggle")
+            .method("POST")
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    // When
+    let response = app.call_request(toggle()).await;
+
+    // Then
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let toggled = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    assert_eq!(toggled["region"], "ac1");
+    assert_eq!(toggled["running"], true);
+    assert_eq!(toggled["duration"], 0);
+
+    // When
+    let response = app.call_request(toggle()).await;
+
+    // Then
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let toggled = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    assert_eq!(toggled["running"], false);
+}
+
+#[sqlx::test]
+async fn test_idempotency_key_replays_response(pool: SqlitePool) {
+    let mut app = app(setup_api_context(pool.clone()));
+    let start = |key: &str, region: &str| {
+        Request::builder()
+            .uri(format!("/api/{region}/start"))
+            .method("POST")
+            .header("Idempotency-Key", key)
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    // When: the same request is sent twice
+    let first = app.call_request(start("retry", "ac1")).await;
+    let second = app.call_request(start("retry", "ac1")).await;
+
+    // Then
+    assert_eq!(first.status(), StatusCode::OK);
+    assert_eq!(second.status(), StatusCode::OK);
+    assert!(!first.headers().contains_key("idempotent-replayed"));
+    assert_eq!(second.headers()["idempotent-replayed"], "true");
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM region_history")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 1, "The retry should not start the timer again");
+
+    // When: the key is reused for another request
+    let response = app.call_request(start("retry", "aa1")).await;
+
+    // Then
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn test_idempotency_key_is_bound_to_body_and_client(pool: SqlitePool) {
+    let mut app = app(setup_api_context(pool));
+    let add = |client: &str, day: &str| {
+        Request::builder()
+            .uri("/api/target_hours")
+            .method("POST")
+            .header("User-Id", "admin")
+            .header("Content-Type", "application/json")
+            .header("Client-Id", client)
+            .header("Idempotency-Key", "add-schedule")
+            .body(Body::from(format!(
+                r#"{{"valid_from": "{day}", "valid_until": "{day}"}}"#
+            )))
+            .unwrap()
+    };
+    let response = app.call_request(add("tablet", "2025-09-01")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // When: the key is reused with another body
+    let response = app.call_request(add("tablet", "2026-01-01")).await;
+
+    // Then
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // When: another client uses the same key
+    let response = app.call_request(add("phone", "2026-01-01")).await;
+
+    // Then
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(!response.headers().contains_key("idempotent-replayed"));
+}
+
+#[sqlx::test]
+async fn test_idempotency_key_replays_error_response(pool: SqlitePool) {
+    let mut app = app(setup_api_context(pool));
+    let stop = || {
+        Request::builder()
+            .uri("/api/ac1/stop")
+            .method("POST")
+            .header("Idempotency-Key", "stop-1")
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    // When
+    let first = app.call_request(stop()).await;
+    let second = app.call_request(stop()).await;
+
+    // Then
+    assert_eq!(first.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(second.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(second.headers()["idempotent-replayed"], "true");
+    let body = second.into_body().collect().await.unwrap().to_bytes();
+    assert_eq!(&body[..], b"No timer is running for the region");
+}
+
+#[sqlx::test]
+async fn test_sync_offline_events(pool: SqlitePool) {
+    let mut app = app(setup_api_context(pool));
+    let start = Utc::now() - TimeDelta::hours(3);
+    let stop = Utc::now() - TimeDelta::hours(1);
+    let body = serde_json::json!({
+        "client_id": "phone",
+        "events": [
+            {"event_id": "1", "type": "start", "region": "ac1", "timestamp": start},
+            {"event_id": "2", "type": "stop", "region": "ac1", "timestamp": stop},
+            {"event_id": "3", "type": "stop", "region": "ac1", "timestamp": stop},
+        ]
+    });
+    let sync = || {
+        Request::builder()
+            .uri("/api/sync")
+            .method("POST")
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    // When
+    let response = app.call_request(sync()).await;
+
+    // Then
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let synced = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    assert_eq!(synced["events"][0]["status"], "applied");
+    assert_eq!(synced["events"][1]["status"], "applied");
+    assert_eq!(synced["events"][2]["status"], "ignored");
+    assert_eq!(synced["history"].as_array().unwrap().len(), 1);
+    assert_eq!(synced["history"][0]["duration"], 7_200_000);
+
+    // When: the batch is sent again
+    let response = app.call_request(sync()).await;
+
+    // Then
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let synced = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    assert_eq!(synced["events"][0]["status"], "duplicate");
+    assert_eq!(synced["history"].as_array().unwrap().len(), 1);
+}
+
+#[sqlx::test]
+async fn test_clean_up_history(pool: SqlitePool) {
+    // Given: an accidental tap of two seconds
+    sqlx::query(
+        r#"
+        INSERT INTO region_history (region, start_time, stop_time, duration)
+        VALUES ('ac1', '2025-10-01T08:00:00+00:00', '2025-10-01T08:00:02+00:00', 2000)
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let mut context = setup_api_context(pool.clone());
+    context.settings.min_duration = Some(MinDuration {
+        duration: TimeDelta::seconds(5),
+        action: ShortEntryAction::Discard,
+    });
+    let mut app = app(context);
+
+    // When: previewing the cleanup
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/history/cleanup?dry_run=true")
+                .method("POST")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+
+    // Then
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let removed = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    assert_eq!(removed[0]["region"], "ac1");
+    assert_eq!(removed[0]["merged_into"], JsonValue::Null);
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM region_history")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 1, "The preview should not remove the entry");
+
+    // When: cleaning up
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/history/cleanup")
+                .method("POST")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+
+    // Then
+    assert_eq!(response.status(), StatusCode::OK);
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM region_history")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 0, "The short entry should be removed");
+}
+
+#[sqlx::test]
+async fn test_merge_history(pool: SqlitePool) {
+    // Given: a work block that was split by restarting the region
+    sqlx::query(
+        r#"
+        INSERT INTO region_history (region, start_time, stop_time, duration)
+        VALUES ('ac1', '2025-10-01T08:00:00+00:00', '2025-10-01T09:00:00+00:00', 3600000),
+               ('ac1', '2025-10-01T09:00:00+00:00', NULL, NULL)
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let mut app = app(setup_api_context(pool.clone()));
+
+    // When
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/history/merge")
+                .method("POST")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+
+    // Then
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let merged = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    assert_eq!(merged.as_array().unwrap().len(), 1);
+    let (count, running): (i64, i64) =
+        sqlx::query_as("SELECT COUNT(*), SUM(stop_time IS NULL) FROM region_history")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(count, 1);
+    assert_eq!(running, 1, "The merged entry keeps running");
+}
+
+#[sqlx::test]
+async fn test_undo_last_action(pool: SqlitePool) {
+    // Given: Ac1 runs and the tablet pressed Ac2 by accident
+    let mut app = app(setup_api_context(pool));
+    for region in ["ac1", "ac2"] {
+        let response = app
+            .call_request(
+                Request::builder()
+                    .uri(format!("/api/{region}/start"))
+                    .method("POST")
+                    .header("Client-Id", "tablet")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    // When
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/undo")
+                .method("POST")
+                .header("Client-Id", "tablet")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+
+    // Then
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let action = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    assert_eq!(action["kind"], "start_timer");
+    assert_eq!(action["client_id"], "tablet");
+
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/currently_active")
+                .method("GET")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let active = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    assert_eq!(active["region"], "ac1", "Ac1 should run again");
+
+    // Then: other clients have nothing to undo
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/undo")
+                .method("POST")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn test_entry_audit_log(pool: SqlitePool) {
+    // Given
+    let mut app = app(setup_api_context(pool));
+    for action in ["start", "stop"] {
+        let response = app
+            .call_request(
+                Request::builder()
+                    .uri(format!("/api/ac1/{action}"))
+                    .method("POST")
+                    .header("Client-Id", "tablet")
+                    .header("User-Id", "anna")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    // When
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/history/1/audit")
+                .method("GET")
+                .header("User-Id", "anna")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+
+    // Then
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let changes = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    assert_eq!(changes[0]["operation"], "insert");
+    assert_eq!(changes[0]["old_values"], Value::Null);
+    assert_eq!(changes[0]["new_values"]["region"], "ac1");
+    assert_eq!(changes[1]["operation"], "update");
+    assert_eq!(changes[1]["actor"], "anna");
+    assert_eq!(changes[1]["device"], "tablet");
+
+    // Then: only Anna and managers may read the changes of her entry
+    for (user, status) in [("ben", StatusCode::FORBIDDEN), ("admin", StatusCode::OK)] {
+        let response = app
+            .call_request(
+                Request::builder()
+                    .uri("/api/history/1/audit")
+                    .method("GET")
+                    .header("User-Id", user)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+        assert_eq!(response.status(), status, "{user}");
+    }
+
+    // Then: entries without changes are unknown
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/history/2/audit")
+                .method("GET")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn test_delete_and_restore_entry(pool: SqlitePool) {
+    // Given
+    let mut app = app(setup_api_context(pool));
+    for action in ["start", "stop"] {
+        app.call_request(
+            Request::builder()
+                .uri(format!("/api/ac1/{action}"))
+                .method("POST")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    }
+
+    // When
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/history/1")
+                .method("DELETE")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+
+    // Then
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/ac1/history")
+                .method("GET")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let history = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    assert_eq!(history, Value::Array(vec![]), "The entry should be hidden");
+
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/history/trash")
+                .method("GET")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let trash = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    assert_eq!(trash[0]["id"], 1);
+    assert!(trash[0]["deleted_at"].is_string());
+
+    // When
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/history/1/restore")
+                .method("POST")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+
+    // Then
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/history/1/restore")
+                .method("POST")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(
+        response.status(),
+        StatusCode::NOT_FOUND,
+        "The entry is no longer in the trash"
+    );
+}
+
+#[sqlx::test]
+async fn test_closed_period_is_locked(pool: SqlitePool) {
+    // Given
+    sqlx::query(
+        r#"
+        INSERT INTO region_history (region, start_time, stop_time, duration)
+        VALUES ('ac1', '2025-10-01T08:00:00+00:00', '2025-10-01T09:00:00+00:00', 3600000)
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let mut app = app(setup_api_context(pool));
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/periods/2025/10/close")
+                .method("POST")
+                .header("User-Id", "admin")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // When
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/history/1")
+                .method("DELETE")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+
+    // Then
+    assert_eq!(response.status(), StatusCode::LOCKED);
+
+    // Then: reopening requires a reason
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/periods/2025/10/reopen")
+                .method("POST")
+                .header("User-Id", "admin")
+                .header("Content-Type", "application/json")
+                .body(Body::from(r#"{"reason": " "}"#))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/periods/2025/10/reopen")
+                .method("POST")
+                .header("User-Id", "admin")
+                .header("Content-Type", "application/json")
+                .body(Body::from(r#"{"reason": "Correction"}"#))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/history/1")
+                .method("DELETE")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[sqlx::test]
+async fn test_timesheet_approval(pool: SqlitePool) {
+    // Given: Ben is a manager and Anna worked 4 hours in October
+    sqlx::query(
+        r#"
+        INSERT INTO region_history (region, start_time, stop_time, duration, user_id)
+        VALUES ('ac1', '2025-10-01T08:00:00+00:00', '2025-10-01T12:00:00+00:00', 14400000, 'anna'),
+               ('ac1', '2025-10-02T08:00:00+00:00', '2025-10-02T12:00:00+00:00', 14400000, 'ben')
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let mut app = app(setup_api_context(pool));
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/roles/ben")
+                .method("PUT")
+                .header("User-Id", "admin")
+                .header("Content-Type", "application/json")
+                .body(Body::from(r#"{"role": "manager"}"#))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/timesheets/2025/10/submit")
+                .method("POST")
+                .header("User-Id", "anna")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // When: Anna, a member, approves her own timesheet
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/timesheets/anna/2025/10/approve")
+                .method("POST")
+                .header("User-Id", "anna")
+                .header("Content-Type", "application/json")
+                .body(Body::from("{}"))
+                .unwrap(),
+        )
+        .await;
+
+    // Then
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // When
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/timesheets/anna/2025/10/approve")
+                .method("POST")
+                .header("User-Id", "ben")
+                .header("Content-Type", "application/json")
+                .body(Body::from(r#"{"comment": "Thanks"}"#))
+                .unwrap(),
+        )
+        .await;
+
+    // Then
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/timesheets/anna/2025/10")
+                .method("GET")
+                .header("User-Id", "ben")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let timesheet = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    assert_eq!(timesheet["status"], "approved");
+    assert_eq!(timesheet["reviewed_by"], "ben");
+    assert_eq!(timesheet["events"].as_array().unwrap().len(), 2);
+    assert_eq!(timesheet["summary"]["work_duration"], 14_400_000);
+    assert_eq!(timesheet["flextime"]["from"], "2025-10-01");
+    assert_eq!(timesheet["flextime"]["to"], "2025-10-31");
+    assert_eq!(timesheet["flextime"]["overall"]["actual"], 14_400);
+
+    // Then: Carl, a member, can neither read nor list Anna's timesheet
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/timesheets/anna/2025/10")
+                .method("GET")
+                .header("User-Id", "carl")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/timesheets")
+                .method("GET")
+                .header("User-Id", "carl")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    assert_eq!(&body[..], b"[]");
+
+    // Then: an approved timesheet can't be rejected anymore
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/timesheets/anna/2025/10/reject")
+                .method("POST")
+                .header("User-Id", "ben")
+                .header("Content-Type", "application/json")
+                .body(Body::from(r#"{"comment": "Oops"}"#))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // Then: the current month can't be submitted yet
+    let now = Utc::now();
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri(format!(
+                    "/api/timesheets/{}/{}/submit",
+                    now.year(),
+                    now.month()
+                ))
+                .method("POST")
+                .header("User-Id", "anna")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn test_restricted_region_is_forbidden(pool: SqlitePool) {
+    // Given: only Anna may book time on Ac1
+    let mut app = app(setup_api_context(pool));
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/permissions/ac1")
+                .method("PUT")
+                .header("User-Id", "anna")
+                .header("Content-Type", "application/json")
+                .body(Body::from(r#"{"users": ["anna"]}"#))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(
+        response.status(),
+        StatusCode::FORBIDDEN,
+        "Only admins manage permissions"
+    );
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/permissions/ac1")
+                .method("PUT")
+                .header("User-Id", "admin")
+                .header("Content-Type", "application/json")
+                .body(Body::from(r#"{"users": ["anna"]}"#))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // When
+    let ben = app
+        .call_request(
+            Request::builder()
+                .uri("/api/ac1/start")
+                .method("POST")
+                .header("User-Id", "ben")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    let anna = app
+        .call_request(
+            Request::builder()
+                .uri("/api/ac1/start")
+                .method("POST")
+                .header("User-Id", "anna")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+
+    // Then
+    assert_eq!(ben.status(), StatusCode::FORBIDDEN);
+    assert_eq!(anna.status(), StatusCode::OK);
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/ac1/stop")
+                .method("POST")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(
+        response.status(),
+        StatusCode::FORBIDDEN,
+        "Anonymous callers can't stop a restricted region"
+    );
+}
+
+#[sqlx::test]
+async fn test_sync_without_changes_publishes_nothing(pool: SqlitePool) {
+    // Given
+    let mut app = app(setup_api_context(pool));
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/events")
+                .method("GET")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    let mut body = response.into_body();
+
+    // When: the only event of the batch lies in the future
+    let future = Utc::now() + TimeDelta::hours(1);
+    let batch = serde_json::json!({
+        "client_id": "phone",
+        "events": [{"event_id": "1", "type": "start", "region": "ac1", "timestamp": future}]
+    });
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/sync")
+                .method("POST")
+                .header("Content-Type", "application/json")
+                .body(Body::from(batch.to_string()))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/ac1/start")
+                .method("POST")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Then: the first event is the start, not a change of the history
+    let frame = body.frame().await.unwrap().unwrap().into_data().unwrap();
+    let event = String::from_utf8(frame.to_vec()).unwrap();
+    assert!(event.starts_with("event: timer_started"), "{event}");
+}
+
+#[sqlx::test]
+async fn test_sync_ignores_forbidden_events(pool: SqlitePool) {
+    // Given: only Anna may book time on Ac1
+    let mut app = app(setup_api_context(pool));
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/permissions/ac1")
+                .method("PUT")
+                .header("User-Id", "admin")
+                .header("Content-Type", "application/json")
+                .body(Body::from(r#"{"users": ["anna"]}"#))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = serde_json::json!({
+        "client_id": "phone",
+        "events": [
+            {"event_id": "1", "type": "start", "region": "ac1", "timestamp": Utc::now() - TimeDelta::hours(2)},
+            {"event_id": "2", "type": "start", "region": "aa1", "timestamp": Utc::now() - TimeDelta::hours(1)},
+        ]
+    });
+
+    // When
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/sync")
+                .method("POST")
+                .header("User-Id", "ben")
+                .header("Content-Type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await;
+
+    // Then
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let synced = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    let status = |event_id: &str| {
+        synced["events"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|event| event["event_id"] == event_id)
+            .unwrap()["status"]
+            .clone()
+    };
+    assert_eq!(status("1"), "ignored");
+    assert_eq!(status("2"), "applied");
+    assert_eq!(synced["history"].as_array().unwrap().len(), 1);
+    assert_eq!(synced["history"][0]["region"], "aa1");
+}
+
+#[sqlx::test]
+async fn test_kiosk_acts_for_worker_with_pin(pool: SqlitePool) {
+    // Given
+    let mut app = app(setup_api_context(pool));
+    for (user, pin) in [("anna", "4711"), ("ben", "0815")] {
+        let response = app
+            .call_request(
+                Request::builder()
+                    .uri(format!("/api/kiosk/credentials/{user}"))
+                    .method("PUT")
+                    .header("User-Id", "admin")
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(format!(r#"{{"pin": "{pin}"}}"#)))
+                    .unwrap(),
+            )
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/devices")
+                .method("POST")
+                .header("User-Id", "admin")
+                .header("Content-Type", "application/json")
+                .body(Body::from(r#"{"name": "Entrance", "type": "kiosk"}"#))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let device = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    let token = device["token"].as_str().unwrap().to_string();
+    let kiosk_request = |token: Option<&str>, body: &'static str| {
+        let mut request = Request::builder()
+            .uri("/api/kiosk")
+            .method("POST")
+            .header("Client-Id", "entrance")
+            .header("Content-Type", "application/json");
+        if let Some(token) = token {
+            request = request.header("Device-Token", token);
+        }
+        request.body(Body::from(body)).unwrap()
+    };
+
+    // When
+    let response = app
+        .call_request(kiosk_request(
+            Some(&token),
+            r#"{"pin": "4711", "region": "aa1", "action": "toggle"}"#,
+        ))
+        .await;
+
+    // Then
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let result = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    assert_eq!(result["user_id"], "anna");
+    assert_eq!(result["running"], true);
+
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/history/1/audit")
+                .method("GET")
+                .header("User-Id", "anna")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let changes = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    assert_eq!(changes[0]["actor"], "anna");
+    assert_eq!(changes[0]["device"], "entrance");
+
+    // Then: the next worker's timer doesn't stop the first one's
+    let response = app
+        .call_request(kiosk_request(
+            Some(&token),
+            r#"{"pin": "0815", "region": "ac1", "action": "start"}"#,
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = app
+        .call_request(kiosk_request(
+            Some(&token),
+            r#"{"pin": "4711", "region": "aa1", "action": "toggle"}"#,
+        ))
+        .await;
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let result = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    assert_eq!(result["user_id"], "anna");
+    assert_eq!(result["running"], false, "Anna's timer was still running");
+
+    // Then: a wrong PIN is refused
+    let response = app
+        .call_request(kiosk_request(
+            Some(&token),
+            r#"{"pin": "1234", "region": "aa1", "action": "stop"}"#,
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Then: only registered kiosks accept PINs
+    let response = app
+        .call_request(kiosk_request(
+            None,
+            r#"{"pin": "4711", "region": "aa1", "action": "start"}"#,
+        ))
+        .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[sqlx::test]
+async fn test_device_changes_are_attributed(pool: SqlitePool) {
+    // Given
+    let mut app = app(setup_api_context(pool));
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/devices")
+                .method("POST")
+                .header("Content-Type", "application/json")
+                .body(Body::from(r#"{"name": "Entrance", "type": "tablet"}"#))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let device = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    let token = device["token"].as_str().unwrap().to_string();
+
+    // When
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/ac1/start")
+                .method("POST")
+                .header("Device-Token", &token)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Then
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/history/1/audit")
+                .method("GET")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let changes = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    assert_eq!(changes[0]["device_id"], device["id"]);
+
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/devices")
+                .method("GET")
+                .header("User-Id", "admin")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let devices = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    assert_eq!(devices[0]["name"], "Entrance");
+    assert_eq!(devices[0]["type"], "tablet");
+    assert!(devices[0]["last_seen_at"].is_string());
+    assert_eq!(devices[0].get("token"), None);
+
+    // Then: a revoked device is rejected
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri(format!("/api/devices/{}", device["id"]))
+                .method("DELETE")
+                .header("User-Id", "admin")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/ac1/stop")
+                .method("POST")
+                .header("Device-Token", &token)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test]
+async fn test_untrusted_user_id_header_is_ignored(pool: SqlitePool) {
+    // Given
+    let mut context = setup_api_context(pool);
+    context.settings.trust_user_id_header = false;
+    let mut app = app(context);
+    let register = |setup_token: Option<&str>| {
+        let mut request = Request::builder()
+            .uri("/api/devices")
+            .method("POST")
+            .header("User-Id", "admin")
+            .header("Content-Type", "application/json");
+        if let Some(setup_token) = setup_token {
+            request = request.header("Setup-Token", setup_token);
+        }
+        request
+            .body(Body::from(
+                r#"{"name": "Admin's phone", "type": "phone", "user_id": "admin"}"#,
+            ))
+            .unwrap()
+    };
+
+    // When
+    let header_only = app.call_request(register(None)).await;
+    let wrong_token = app.call_request(register(Some("guess"))).await;
+    let setup_token = app.call_request(register(Some("test-setup"))).await;
+
+    // Then
+    assert_eq!(header_only.status(), StatusCode::FORBIDDEN);
+    assert_eq!(wrong_token.status(), StatusCode::FORBIDDEN);
+    assert_eq!(setup_token.status(), StatusCode::OK);
+    let body = setup_token.into_body().collect().await.unwrap().to_bytes();
+    let device = serde_json::from_slice::<Value>(body.iter().as_slice()).unwrap();
+    assert_eq!(device["user_id"], "admin");
+    let token = device["token"].as_str().unwrap().to_string();
+
+    // Then: the personal device acts as its user, the header doesn't
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/devices")
+                .method("GET")
+                .header("User-Id", "admin")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = app
+        .call_request(
+            Request::builder()
+                .uri("/api/devices")
+                .method("GET")
+                .header("Device-Token", &token)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
 }
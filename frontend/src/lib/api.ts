@@ -1,11 +1,13 @@
 import type { Region } from "./regions";
 
 export interface StopTimerResponse {
+	// Milliseconds
 	duration: number;
 }
 
 export interface CurrentlyActiveResponse {
 	region: Region | null;
+	// Milliseconds
 	duration: number | null;
 }
 